@@ -0,0 +1,20 @@
+//! Helpers shared by the parameter IO benches.
+use roead::aamp::*;
+
+/// Resolves the name of every structure and parameter in a list with a name
+/// table, the same way converting it to YAML does, and returns how many were
+/// found.
+pub fn resolve_list(table: &NameTable, list: &ParameterList, parent_hash: u32) -> usize {
+    let mut count = 0;
+    for (index, (name, child)) in list.lists().iter().enumerate() {
+        count += table.get_name(name.hash(), index, parent_hash).is_some() as usize;
+        count += resolve_list(table, child, name.hash());
+    }
+    for (index, (name, object)) in list.objects().iter().enumerate() {
+        count += table.get_name(name.hash(), index, parent_hash).is_some() as usize;
+        for (index, (param, _)) in object.iter().enumerate() {
+            count += table.get_name(param.hash(), index, name.hash()).is_some() as usize;
+        }
+    }
+    count
+}
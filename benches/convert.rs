@@ -0,0 +1,70 @@
+//! Times converting every binary parameter IO in the test data on 1, 2, 4
+//! and 8 threads at once, sharing the default name table. With the `yaml`
+//! feature, each file is parsed and serialized to YAML. Without it, each file
+//! is parsed and the names are resolved as serializing would.
+//!
+//! Run with `cargo bench --bench convert --features yaml`.
+use std::{path::Path, time::Instant};
+
+use roead::aamp::*;
+
+#[cfg(not(feature = "yaml"))]
+mod common;
+
+const ROUNDS: usize = 5;
+
+fn collect_files(dir: &Path, files: &mut Vec<Vec<u8>>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext != "yml") {
+            files.push(std::fs::read(path).unwrap());
+        }
+    }
+}
+
+fn convert(data: &[u8]) {
+    let pio = ParameterIO::from_binary(data).unwrap();
+    #[cfg(feature = "yaml")]
+    std::hint::black_box(pio.to_text());
+    #[cfg(not(feature = "yaml"))]
+    std::hint::black_box(common::resolve_list(
+        get_default_name_table(),
+        &pio.param_root,
+        Name::from_str("param_root").hash(),
+    ));
+}
+
+fn main() {
+    let mut files = Vec::new();
+    collect_files(Path::new("test/aamp"), &mut files);
+    // Warm up, which also records any guessed names in the table.
+    files.iter().for_each(|data| convert(data));
+    for threads in [1, 2, 4, 8] {
+        let per_thread = files.len() * ROUNDS / threads;
+        let start = Instant::now();
+        std::thread::scope(|scope| {
+            for thread in 0..threads {
+                let files = &files;
+                scope.spawn(move || {
+                    for data in files
+                        .iter()
+                        .cycle()
+                        .skip(thread)
+                        .step_by(threads)
+                        .take(per_thread)
+                    {
+                        convert(data);
+                    }
+                });
+            }
+        });
+        let elapsed = start.elapsed();
+        let total = per_thread * threads;
+        println!(
+            "convert {total} files, {threads} thread(s): {elapsed:?} ({:.0} files/s)",
+            total as f64 / elapsed.as_secs_f64()
+        );
+    }
+}
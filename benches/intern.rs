@@ -0,0 +1,89 @@
+//! Measures the heap memory used by every binary parameter IO in the test
+//! data when all of them are loaded at once, with and without sharing
+//! `StringRef` values through a [`StringInterner`].
+//!
+//! Run with `cargo bench --bench intern`.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use roead::aamp::*;
+
+/// Wraps the system allocator to keep track of the bytes in use.
+struct Counter;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counter {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counter = Counter;
+
+fn collect_files(dir: &Path, files: &mut Vec<Vec<u8>>) {
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else if path.extension().is_some_and(|ext| ext != "yml") {
+            files.push(std::fs::read(path).unwrap());
+        }
+    }
+}
+
+/// Returns the heap bytes still in use after `load` returns, along with its
+/// result.
+fn retained<T>(load: impl FnOnce() -> T) -> (usize, T) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let value = load();
+    (ALLOCATED.load(Ordering::Relaxed) - before, value)
+}
+
+fn main() {
+    let mut files = Vec::new();
+    collect_files(Path::new("test/aamp"), &mut files);
+
+    let (plain, pios) = retained(|| {
+        files
+            .iter()
+            .map(|data| ParameterIO::from_binary(data).unwrap())
+            .collect::<Vec<_>>()
+    });
+    let strings = pios
+        .iter()
+        .map(|pio| {
+            pio.stats()
+                .parameters_by_type
+                .get(&ParameterType::StringRef)
+                .copied()
+                .unwrap_or(0)
+        })
+        .sum::<usize>();
+    drop(pios);
+
+    let (interned, (pios, interner)) = retained(|| {
+        let mut interner = StringInterner::new();
+        let pios = files
+            .iter()
+            .map(|data| ParameterIO::from_binary_with_interner(data, &mut interner).unwrap())
+            .collect::<Vec<_>>();
+        (pios, interner)
+    });
+    println!(
+        "{} files with {strings} StringRef values: {plain} bytes with from_binary, {interned} \
+         bytes with from_binary_with_interner ({} distinct strings interned)",
+        pios.len(),
+        interner.len()
+    );
+}
@@ -0,0 +1,56 @@
+//! Times resolving every name in a large parameter IO with the default name
+//! table, on one thread and on several threads at once, as when converting
+//! many documents in parallel. With the `yaml` feature, this also times
+//! serializing the document to YAML, which resolves the same names.
+//!
+//! Run with `cargo bench --bench names`.
+use std::time::{Duration, Instant};
+
+use roead::aamp::*;
+
+mod common;
+
+const ITERATIONS: u32 = 20;
+const THREADS: usize = 4;
+
+/// Runs `work` `ITERATIONS` times on each of `threads` threads and returns the
+/// average wall time per call.
+fn time_threads(threads: usize, work: impl Fn() + Sync) -> Duration {
+    work();
+    let start = Instant::now();
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for _ in 0..ITERATIONS {
+                    work();
+                }
+            });
+        }
+    });
+    start.elapsed() / (ITERATIONS * threads as u32)
+}
+
+fn main() {
+    let data = std::fs::read("test/aamp/AIProgram/Lizalfos_Ice.baiprog").unwrap();
+    let pio = ParameterIO::from_binary(data).unwrap();
+    let table = get_default_name_table();
+    let root = Name::from_str("param_root").hash();
+    let names = common::resolve_list(table, &pio.param_root, root);
+    for threads in [1, THREADS] {
+        let elapsed = time_threads(threads, || {
+            std::hint::black_box(common::resolve_list(
+                table,
+                std::hint::black_box(&pio.param_root),
+                root,
+            ));
+        });
+        println!("resolve {names} names, {threads} thread(s): {elapsed:?} per document");
+    }
+    #[cfg(feature = "yaml")]
+    for threads in [1, THREADS] {
+        let elapsed = time_threads(threads, || {
+            std::hint::black_box(std::hint::black_box(&pio).to_text());
+        });
+        println!("to_text, {threads} thread(s): {elapsed:?} per document");
+    }
+}
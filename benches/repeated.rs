@@ -0,0 +1,35 @@
+//! Times serializing a parameter IO with many repeated child objects to YAML.
+//! Each of the lists holds the same object, whose parameters have names which
+//! are not in the name table, so every occurrence has to be resolved again
+//! unless the result is cached for the conversion.
+//!
+//! Run with `cargo bench --bench repeated --features yaml`.
+use std::time::Instant;
+
+use roead::aamp::*;
+
+const LISTS: u32 = 2000;
+const PARAMS: u32 = 8;
+const ITERATIONS: u32 = 20;
+
+fn main() {
+    let object = ParameterObject::new()
+        .with_parameters((0..PARAMS).map(|i| (0xC0FF_EE00 + i, Parameter::I32(i as i32))));
+    let pio = ParameterIO::new().with_lists((0..LISTS).map(|i| {
+        (
+            format!("Actor_{i}"),
+            ParameterList::new().with_object("Param", object.clone()),
+        )
+    }));
+    // Warm up the allocator and the name table before timing.
+    pio.to_text();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(std::hint::black_box(&pio).to_text());
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!(
+        "to_text with {LISTS} repeated objects of {PARAMS} unknown parameters: {elapsed:?} per \
+         iteration"
+    );
+}
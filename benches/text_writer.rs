@@ -0,0 +1,56 @@
+//! Times serializing many small parameter IOs to YAML with a single
+//! [`TextWriter`], which reuses its tree, against calling `to_text` on each.
+//!
+//! Run with `cargo bench --bench text_writer --features yaml`.
+use std::time::Instant;
+
+use roead::aamp::*;
+
+const DOCUMENTS: i32 = 1000;
+const ITERATIONS: u32 = 10;
+
+fn main() {
+    let pios: Vec<_> = (0..DOCUMENTS)
+        .map(|i| {
+            ParameterIO::new()
+                .with_object(
+                    "Root",
+                    ParameterObject::new()
+                        .with_parameter("Damage", Parameter::I32(i))
+                        .with_parameter("Speed", Parameter::F32(i as f32 / 10.0)),
+                )
+                .with_list(
+                    "Items",
+                    ParameterList::new().with_object(
+                        "Item_0",
+                        ParameterObject::new().with_parameter(
+                            "Name",
+                            Parameter::StringRef(format!("Item{i}").into()),
+                        ),
+                    ),
+                )
+        })
+        .collect();
+    let mut writer = TextWriter::default();
+    // Warm up the allocator and the name table before timing.
+    pios.iter().for_each(|pio| {
+        writer.write(pio);
+    });
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for pio in &pios {
+            std::hint::black_box(std::hint::black_box(pio).to_text());
+        }
+    }
+    let to_text = start.elapsed() / ITERATIONS;
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for pio in &pios {
+            std::hint::black_box(writer.write(std::hint::black_box(pio)));
+        }
+    }
+    let write = start.elapsed() / ITERATIONS;
+    println!("to_text for {DOCUMENTS} documents: {to_text:?} per iteration");
+    println!("TextWriter::write for {DOCUMENTS} documents: {write:?} per iteration");
+}
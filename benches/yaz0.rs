@@ -0,0 +1,22 @@
+//! Times Yaz0 decompression of a large compressed BYML document.
+//!
+//! Run with `cargo bench --bench yaz0 --features yaz0`.
+use std::time::Instant;
+
+const ITERATIONS: u32 = 50;
+
+fn main() {
+    let data = std::fs::read("test/yaz0/ActorInfo.product.sbyml").unwrap();
+    let size = roead::yaz0::get_header(&data).unwrap().uncompressed_size as f64;
+    // Warm up the allocator and caches before timing.
+    roead::yaz0::decompress(&data).unwrap();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(roead::yaz0::decompress(std::hint::black_box(&data)).unwrap());
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!(
+        "decompress ActorInfo.product.sbyml: {elapsed:?} per iteration ({:.1} MB/s)",
+        size / elapsed.as_secs_f64() / 1_000_000.0
+    );
+}
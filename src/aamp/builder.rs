@@ -0,0 +1,137 @@
+//! Path-based construction of parameter IOs.
+use join_str::jstr;
+
+use super::{path::parse_path, *};
+
+/// Builder for constructing a [`ParameterIO`] from slash-separated parameter
+/// paths, creating intermediate lists and objects as needed.
+///
+/// The last segment of a path is the parameter name, the one before it is the
+/// containing object, and any others are lists. Segments consisting only of
+/// digits are treated as hashes.
+///
+/// ```
+/// # use roead::aamp::*;
+/// let pio = ParameterIOBuilder::new()
+///     .with_data_type("xml")
+///     .set("AI/Root/Damage", Parameter::F32(10.0))?
+///     .set("AI/Root/Range", Parameter::F32(2.5))?
+///     .build();
+/// assert_eq!(pio.list("AI").unwrap().object("Root").unwrap().len(), 2);
+/// # Ok::<(), roead::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParameterIOBuilder {
+    pio: ParameterIO,
+}
+
+impl Default for ParameterIOBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ParameterIOBuilder {
+    /// Create a builder for an empty parameter IO, as created by
+    /// [`ParameterIO::new`].
+    pub fn new() -> Self {
+        Self {
+            pio: ParameterIO::new(),
+        }
+    }
+
+    /// Create a builder which adds to an existing parameter IO.
+    pub fn from_pio(pio: ParameterIO) -> Self {
+        Self { pio }
+    }
+
+    /// Set the data type of the parameter IO.
+    pub fn with_data_type(mut self, data_type: impl Into<String>) -> Self {
+        self.pio.data_type = data_type.into();
+        self
+    }
+
+    /// Set the data version of the parameter IO.
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.pio.version = version;
+        self
+    }
+
+    /// Insert a parameter at a path (e.g. `AI/Root/Damage`), replacing any
+    /// existing parameter there.
+    ///
+    /// Returns an error if the path has fewer than two segments, or if an
+    /// intermediate segment already exists as the wrong kind of structure
+    /// (e.g. an object where a list is needed).
+    pub fn set(mut self, path: &str, param: impl Into<Parameter>) -> Result<Self> {
+        let names = parse_path(path);
+        let [lists @ .., obj, name] = names.as_slice() else {
+            return Err(Error::InvalidDataD(jstr!(
+                "Parameter path `{path}` does not include an object"
+            )));
+        };
+        let mut list = &mut self.pio.param_root;
+        for segment in lists {
+            if !list.lists.contains_key(*segment) && list.objects.contains_key(*segment) {
+                return Err(Error::InvalidDataD(jstr!(
+                    "Cannot insert `{path}`: `{&segment.to_string()}` is an object, not a list"
+                )));
+            }
+            list = list.lists.entry(*segment).or_default();
+        }
+        if !list.objects.contains_key(*obj) && list.lists.contains_key(*obj) {
+            return Err(Error::InvalidDataD(jstr!(
+                "Cannot insert `{path}`: `{&obj.to_string()}` is a list, not an object"
+            )));
+        }
+        list.objects
+            .entry(*obj)
+            .or_default()
+            .insert(*name, param.into());
+        Ok(self)
+    }
+
+    /// Finish building and return the parameter IO.
+    pub fn build(self) -> ParameterIO {
+        self.pio
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_paths() {
+        let pio = ParameterIOBuilder::new()
+            .with_version(10)
+            .set("Flags/IsEnabled", true)
+            .unwrap()
+            .set("AI/Root/Damage", 10)
+            .unwrap()
+            .set("AI/Root/Damage", 20)
+            .unwrap()
+            .set("AI/Children/Child/Speed", 1.5f32)
+            .unwrap()
+            .build();
+        let expected = ParameterIO::new()
+            .with_version(10)
+            .with_object("Flags", params!("IsEnabled" => Parameter::Bool(true)))
+            .with_list(
+                "AI",
+                ParameterList::new()
+                    .with_object("Root", params!("Damage" => Parameter::I32(20)))
+                    .with_list(
+                        "Children",
+                        ParameterList::new()
+                            .with_object("Child", params!("Speed" => Parameter::F32(1.5))),
+                    ),
+            );
+        assert_eq!(pio, expected);
+
+        let builder = ParameterIOBuilder::from_pio(pio);
+        assert!(builder.clone().set("Damage", 1).is_err());
+        assert!(builder.clone().set("AI/Root/Damage/Extra", 1).is_err());
+        assert!(builder.set("AI/Children", 1).is_err());
+    }
+}
@@ -0,0 +1,229 @@
+//! Conversion between parameter archives and BYML documents.
+use join_str::jstr;
+
+use super::{
+    path::{name_to_segment, segment_to_name},
+    *,
+};
+use crate::byml::{Byml, Map};
+
+#[inline]
+fn is_leaf(node: &Byml) -> bool {
+    match node {
+        Byml::Array(arr) => arr.iter().all(|v| !matches!(v, Byml::Array(_) | Byml::Map(_))),
+        Byml::Map(_) | Byml::HashMap(_) | Byml::ValueHashMap(_) => false,
+        _ => true,
+    }
+}
+
+fn byml_to_parameter(node: &Byml, key: &str) -> Result<Parameter> {
+    Ok(match node {
+        Byml::Bool(b) => Parameter::Bool(*b),
+        Byml::I32(i) => Parameter::I32(*i),
+        Byml::U32(u) => Parameter::U32(*u),
+        Byml::Float(f) => Parameter::F32(*f),
+        Byml::String(s) => Parameter::StringRef(s.clone().into()),
+        Byml::BinaryData(data) => Parameter::BufferBinary(data.clone()),
+        Byml::Array(arr) => {
+            match arr.first() {
+                None | Some(Byml::I32(_)) => {
+                    Parameter::BufferInt(arr.iter().map(|v| v.as_i32()).collect::<Result<_>>()?)
+                }
+                Some(Byml::U32(_)) => {
+                    Parameter::BufferU32(arr.iter().map(|v| v.as_u32()).collect::<Result<_>>()?)
+                }
+                Some(Byml::Float(_)) => {
+                    Parameter::BufferF32(arr.iter().map(|v| v.as_float()).collect::<Result<_>>()?)
+                }
+                Some(other) => {
+                    return Err(Error::InvalidDataD(jstr!(
+                        "Cannot convert array of {&other.type_name()} at `{key}` to a buffer"
+                    )));
+                }
+            }
+        }
+        _ => {
+            return Err(Error::InvalidDataD(jstr!(
+                "BYML {&node.type_name()} node at `{key}` has no parameter equivalent"
+            )));
+        }
+    })
+}
+
+fn parameter_to_byml(param: &Parameter, key: &str) -> Result<Byml> {
+    fn floats(values: &[f32]) -> Byml {
+        values.iter().copied().map(Byml::Float).collect()
+    }
+
+    Ok(match param {
+        Parameter::Bool(b) => Byml::Bool(*b),
+        Parameter::F32(f) => Byml::Float(*f),
+        Parameter::I32(i) => Byml::I32(*i),
+        Parameter::U32(u) => Byml::U32(*u),
+        Parameter::Vec2(v) => floats(&[v.x, v.y]),
+        Parameter::Vec3(v) => floats(&[v.x, v.y, v.z]),
+        Parameter::Vec4(v) => floats(&[v.x, v.y, v.z, v.t]),
+        Parameter::Quat(q) => floats(&[q.a, q.b, q.c, q.d]),
+        Parameter::Color(c) => floats(&[c.r, c.g, c.b, c.a]),
+        Parameter::String32(_)
+        | Parameter::String64(_)
+        | Parameter::String256(_)
+        | Parameter::StringRef(_) => Byml::String(param.as_str()?.into()),
+        Parameter::BufferInt(v) => v.iter().copied().map(Byml::I32).collect(),
+        Parameter::BufferF32(v) => floats(v),
+        Parameter::BufferU32(v) => v.iter().copied().map(Byml::U32).collect(),
+        Parameter::BufferBinary(v) => Byml::BinaryData(v.clone()),
+        Parameter::Curve1(_)
+        | Parameter::Curve2(_)
+        | Parameter::Curve3(_)
+        | Parameter::Curve4(_)
+        | Parameter::StringRefRaw(_) => {
+            return Err(Error::InvalidDataD(jstr!(
+                "BYML has no equivalent of {&param.type_name()} parameter `{key}`"
+            )));
+        }
+    })
+}
+
+fn sorted_entries(map: &Map) -> Vec<(&String, &Byml)> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+fn read_object(map: &Map) -> Result<ParameterObject> {
+    sorted_entries(map)
+        .into_iter()
+        .map(|(key, node)| Ok((segment_to_name(key), byml_to_parameter(node, key)?)))
+        .collect::<Result<_>>()
+}
+
+fn read_list(map: &Map) -> Result<ParameterList> {
+    let mut list = ParameterList::new();
+    for (key, node) in sorted_entries(map) {
+        match node {
+            Byml::Map(child) if child.values().all(is_leaf) => {
+                list.objects.insert(segment_to_name(key), read_object(child)?);
+            }
+            Byml::Map(child) => {
+                list.lists.insert(segment_to_name(key), read_list(child)?);
+            }
+            _ => {
+                return Err(Error::InvalidDataD(jstr!(
+                    "Expected map for parameter structure `{key}`, found {&node.type_name()}"
+                )));
+            }
+        }
+    }
+    Ok(list)
+}
+
+fn write_object(obj: &ParameterObject, parent: Name) -> Result<Byml> {
+    obj.iter()
+        .enumerate()
+        .map(|(i, (name, param))| {
+            let key = name_to_segment(*name, i, parent);
+            let value = parameter_to_byml(param, &key)?;
+            Ok((key, value))
+        })
+        .collect::<Result<Map>>()
+        .map(Byml::Map)
+}
+
+fn write_list(list: &ParameterList, parent: Name) -> Result<Byml> {
+    let mut map = Map::default();
+    for (i, (name, obj)) in list.objects.iter().enumerate() {
+        map.insert(name_to_segment(*name, i, parent), write_object(obj, *name)?);
+    }
+    for (i, (name, child)) in list.lists.iter().enumerate() {
+        let key = name_to_segment(*name, i, parent);
+        if map.contains_key(&key) {
+            return Err(Error::InvalidDataD(jstr!(
+                "Parameter list `{&key}` has the same name as a sibling object"
+            )));
+        }
+        map.insert(key, write_list(child, *name)?);
+    }
+    Ok(Byml::Map(map))
+}
+
+impl ParameterIO {
+    /// Convert a BYML document to a parameter IO. This is intended for BYML
+    /// files which are conceptually parameter data. The mapping is as follows:
+    ///
+    /// - The root BYML node must be a map. It becomes the root parameter list.
+    /// - A map whose values are all scalars or arrays of scalars becomes a
+    ///   parameter object. Any other map becomes a parameter list, in which case
+    ///   all of its values must be maps, which are converted recursively. Empty
+    ///   maps are treated as objects.
+    /// - Map keys are hashed to get structure names. Keys consisting only of
+    ///   digits are parsed as hashes, matching the YAML format.
+    /// - `Bool`, `I32`, `U32`, and `Float` nodes become the corresponding
+    ///   parameters, and `String` nodes become `StringRef` parameters.
+    /// - Arrays of `I32`, `U32`, or `Float` nodes become `BufferInt`,
+    ///   `BufferU32`, and `BufferF32` parameters respectively. Empty arrays
+    ///   become `BufferInt`.
+    /// - `BinaryData` nodes become `BufferBinary` parameters.
+    ///
+    /// Any other BYML node type is an error. The data version and type of the
+    /// result are the defaults.
+    ///
+    /// This is only available with the `byml` feature.
+    pub fn from_byml(byml: &Byml) -> Result<ParameterIO> {
+        let root = byml.as_map()?;
+        Ok(ParameterIO::new().with_root(read_list(root)?))
+    }
+
+    /// Convert the parameter IO to a BYML document, following the same rules
+    /// as [`ParameterIO::from_byml`]. Vectors, quaternions, and colors become
+    /// arrays of floats, and the fixed-length string types become plain
+    /// strings, so these types will not survive a round trip unchanged. Curves
+    /// have no BYML equivalent and cannot be converted. The data version and
+    /// type are not preserved.
+    ///
+    /// This is only available with the `byml` feature.
+    pub fn to_byml(&self) -> Result<Byml> {
+        write_list(&self.param_root, ROOT_KEY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byml_roundtrip() {
+        let pio = ParameterIO::new()
+            .with_object(
+                "Flags",
+                params!(
+                    "IsEnabled" => Parameter::Bool(true),
+                    "Count" => Parameter::I32(-3),
+                    "Hash" => Parameter::U32(0xDEADBEEF),
+                    "Rate" => Parameter::F32(0.5),
+                    "Name" => Parameter::StringRef("Weapon_Sword_001".into())
+                ),
+            )
+            .with_list(
+                "Items",
+                ParameterList::new().with_object(
+                    "Item_0",
+                    params!(
+                        "Values" => Parameter::BufferF32(vec![1.0, 2.0]),
+                        "Ids" => Parameter::BufferU32(vec![1, 2, 3]),
+                        "Data" => Parameter::BufferBinary(vec![0, 1, 2, 3])
+                    ),
+                ),
+            );
+        let byml = pio.to_byml().unwrap();
+        assert_eq!(byml["Flags"]["Rate"].as_float().unwrap(), 0.5);
+        let new_pio = ParameterIO::from_byml(&byml).unwrap();
+        assert_eq!(pio, new_pio);
+
+        let curves = ParameterIO::new().with_object(
+            "Curves",
+            params!("Curve" => Parameter::Curve1(Box::new([Curve::default()]))),
+        );
+        assert!(curves.to_byml().is_err());
+    }
+}
@@ -0,0 +1,544 @@
+//! Structural comparison of parameter IOs.
+use join_str::jstr;
+use rustc_hash::FxHashSet;
+
+use super::{
+    path::{join, name_to_segment, parse_path},
+    *,
+};
+
+/// A single difference between two parameter IOs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difference {
+    /// The parameter only exists in the other parameter IO.
+    Added(Parameter),
+    /// The parameter only exists in the original parameter IO.
+    Removed(Parameter),
+    /// The parameter exists in both parameter IOs with different values.
+    Changed {
+        /// The original value.
+        old: Parameter,
+        /// The value in the other parameter IO.
+        new: Parameter,
+    },
+}
+
+/// The differences between two parameter IOs, keyed by parameter path (e.g.
+/// `AI/Root/Damage`), in traversal order. Names are resolved using the
+/// default name table where possible, and otherwise written as hashes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParameterIODiff {
+    /// Map of parameter paths to differences.
+    pub changes: IndexMap<String, Difference>,
+}
+
+impl ParameterIODiff {
+    /// Returns `true` if no differences were found.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the number of differences.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Iterate the differences.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Difference)> {
+        self.changes.iter()
+    }
+}
+
+/// Options for comparing parameter IOs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffOptions {
+    unordered_buffers: FxHashSet<Vec<Name>>,
+}
+
+impl DiffOptions {
+    /// Create the default diff options.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Treat the `BufferInt` or `BufferU32` parameter at the given path (e.g.
+    /// `AI/Root/Values`) as an unordered multiset, so that buffers with
+    /// the same values in a different order are considered equal.
+    pub fn add_unordered_buffer(&mut self, path: &str) {
+        self.unordered_buffers.insert(parse_path(path));
+    }
+
+    /// Builder-style method to treat the `BufferInt` or `BufferU32` parameter
+    /// at the given path as an unordered multiset.
+    #[inline]
+    pub fn with_unordered_buffer(mut self, path: &str) -> Self {
+        self.add_unordered_buffer(path);
+        self
+    }
+}
+
+/// Options for applying a diff with [`ParameterIO::apply_diff_with_options`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ApplyOptions {
+    /// Apply every change even if the parameter IO does not match the
+    /// original values recorded in the diff: changed and added parameters
+    /// are set to their new values and removed parameters are removed if
+    /// present. Defaults to `false` (stale diffs are an error).
+    pub force: bool,
+}
+
+struct DiffContext<'a> {
+    options: &'a DiffOptions,
+    hashes: Vec<Name>,
+    diff: ParameterIODiff,
+}
+
+impl DiffContext<'_> {
+    fn params_equal(&self, a: &Parameter, b: &Parameter) -> bool {
+        fn sorted<T: Ord + Clone>(buf: &[T]) -> Vec<T> {
+            let mut buf = buf.to_vec();
+            buf.sort_unstable();
+            buf
+        }
+
+        if !self.options.unordered_buffers.contains(&self.hashes) {
+            return a == b;
+        }
+        match (a, b) {
+            (Parameter::BufferInt(a), Parameter::BufferInt(b)) => {
+                a.len() == b.len() && sorted(a) == sorted(b)
+            }
+            (Parameter::BufferU32(a), Parameter::BufferU32(b)) => {
+                a.len() == b.len() && sorted(a) == sorted(b)
+            }
+            _ => a == b,
+        }
+    }
+
+    fn diff_object(
+        &mut self,
+        path: &str,
+        a: Option<&ParameterObject>,
+        b: Option<&ParameterObject>,
+        parent: Name,
+    ) {
+        let empty = ParameterObject::default();
+        let (a, b) = (a.unwrap_or(&empty), b.unwrap_or(&empty));
+        for (i, (name, old)) in a.iter().enumerate() {
+            let param_path = join(path, &name_to_segment(*name, i, parent));
+            self.hashes.push(*name);
+            match b.get(*name) {
+                Some(new) if self.params_equal(old, new) => (),
+                Some(new) => {
+                    self.diff.changes.insert(param_path, Difference::Changed {
+                        old: old.clone(),
+                        new: new.clone(),
+                    });
+                }
+                None => {
+                    self.diff
+                        .changes
+                        .insert(param_path, Difference::Removed(old.clone()));
+                }
+            }
+            self.hashes.pop();
+        }
+        for (i, (name, new)) in b.iter().enumerate() {
+            if !a.0.contains_key(name) {
+                let param_path = join(path, &name_to_segment(*name, i, parent));
+                self.diff
+                    .changes
+                    .insert(param_path, Difference::Added(new.clone()));
+            }
+        }
+    }
+
+    fn diff_list(
+        &mut self,
+        path: &str,
+        a: Option<&ParameterList>,
+        b: Option<&ParameterList>,
+        parent: Name,
+    ) {
+        let empty = ParameterList::default();
+        let (a, b) = (a.unwrap_or(&empty), b.unwrap_or(&empty));
+        let objects = a.objects.0.keys().enumerate().chain(
+            b.objects
+                .0
+                .keys()
+                .enumerate()
+                .filter(|(_, name)| !a.objects.0.contains_key(*name)),
+        );
+        for (i, name) in objects {
+            let obj_path = join(path, &name_to_segment(*name, i, parent));
+            self.hashes.push(*name);
+            self.diff_object(&obj_path, a.objects.get(*name), b.objects.get(*name), *name);
+            self.hashes.pop();
+        }
+        let lists = a.lists.0.keys().enumerate().chain(
+            b.lists
+                .0
+                .keys()
+                .enumerate()
+                .filter(|(_, name)| !a.lists.0.contains_key(*name)),
+        );
+        for (i, name) in lists {
+            let list_path = join(path, &name_to_segment(*name, i, parent));
+            self.hashes.push(*name);
+            self.diff_list(&list_path, a.lists.get(*name), b.lists.get(*name), *name);
+            self.hashes.pop();
+        }
+    }
+}
+
+impl ParameterIO {
+    /// Compare this parameter IO with another, returning every parameter which
+    /// was added, removed, or changed in `other`. The data version and type are
+    /// not compared.
+    pub fn diff(&self, other: &ParameterIO) -> ParameterIODiff {
+        self.diff_with_options(other, &DiffOptions::default())
+    }
+
+    /// Compare this parameter IO with another using the given options.
+    /// See [`ParameterIO::diff`].
+    pub fn diff_with_options(&self, other: &ParameterIO, options: &DiffOptions) -> ParameterIODiff {
+        let mut ctx = DiffContext {
+            options,
+            hashes: Vec::new(),
+            diff: ParameterIODiff::default(),
+        };
+        ctx.diff_list("", Some(&self.param_root), Some(&other.param_root), ROOT_KEY);
+        ctx.diff
+    }
+}
+
+/// Find the object containing the parameter at a path, creating any missing
+/// lists and objects on the way if `create` is set.
+fn object_for_path<'a>(
+    root: &'a mut ParameterList,
+    path: &str,
+    create: bool,
+) -> Result<Option<(&'a mut ParameterObject, Name)>> {
+    let names = parse_path(path);
+    let [lists @ .., obj, name] = names.as_slice() else {
+        return Err(Error::InvalidDataD(jstr!(
+            "Diff path `{path}` does not include an object"
+        )));
+    };
+    let mut list = root;
+    for segment in lists {
+        list = match (create, list.lists.0.contains_key(segment)) {
+            (_, true) => list.lists.get_mut(*segment).expect("List should exist"),
+            (true, false) => list.lists.entry(*segment).or_default(),
+            (false, false) => return Ok(None),
+        };
+    }
+    let obj = if create {
+        Some(list.objects.entry(*obj).or_default())
+    } else {
+        list.objects.get_mut(*obj)
+    };
+    Ok(obj.map(|obj| (obj, *name)))
+}
+
+fn stale(path: &str, expected: &str) -> Error {
+    Error::InvalidDataD(jstr!("Cannot apply diff: parameter `{path}` {expected}"))
+}
+
+impl ParameterIO {
+    /// Apply a diff produced by [`ParameterIO::diff`] to this parameter IO, so
+    /// that a diff between two versions of a file can be stored and applied
+    /// to the original later, as with a patch.
+    ///
+    /// Returns an error, leaving the parameter IO unmodified, if the diff does
+    /// not apply cleanly: if a changed or removed parameter does not have the
+    /// original value recorded in the diff, or if an added parameter already
+    /// exists with a different value. Use
+    /// [`ParameterIO::apply_diff_with_options`] to apply it anyway.
+    ///
+    /// **Note:** Diffs only record parameters, so objects whose parameters
+    /// were all removed are left in place, empty.
+    pub fn apply_diff(&mut self, diff: &ParameterIODiff) -> Result<()> {
+        self.apply_diff_with_options(diff, &ApplyOptions::default())
+    }
+
+    /// Apply a diff using the given options. See [`ParameterIO::apply_diff`].
+    pub fn apply_diff_with_options(
+        &mut self,
+        diff: &ParameterIODiff,
+        options: &ApplyOptions,
+    ) -> Result<()> {
+        let mut root = self.param_root.clone();
+        for (path, change) in diff.iter() {
+            match change {
+                Difference::Added(new) => {
+                    let (obj, name) =
+                        object_for_path(&mut root, path, true)?.expect("Object should be created");
+                    match obj.get(name) {
+                        Some(current) if current != new && !options.force => {
+                            return Err(stale(path, "already exists with a different value"));
+                        }
+                        _ => obj.insert(name, new.clone()),
+                    }
+                }
+                Difference::Removed(old) => match object_for_path(&mut root, path, false)? {
+                    Some((obj, name)) if options.force || obj.get(name) == Some(old) => {
+                        obj.0.shift_remove(&name);
+                    }
+                    _ if options.force => (),
+                    _ => return Err(stale(path, "does not have the removed value")),
+                },
+                Difference::Changed { old, new } => {
+                    let (obj, name) = object_for_path(&mut root, path, options.force)?
+                        .ok_or_else(|| stale(path, "does not exist"))?;
+                    if !options.force && obj.get(name) != Some(old) {
+                        return Err(stale(path, "does not have the original value"));
+                    }
+                    obj.insert(name, new.clone());
+                }
+            }
+        }
+        self.param_root = root;
+        Ok(())
+    }
+}
+
+impl ParameterList {
+    /// Compare this parameter list with another, returning the path (e.g.
+    /// `AI/Root/Damage`) and nature of the first difference found, or `None`
+    /// if they are equal. Paths are relative to this list. This is mainly
+    /// useful for making test failures readable, since printing two unequal
+    /// parameter lists is rarely helpful.
+    ///
+    /// ```
+    /// # use roead::aamp::*;
+    /// let a = ParameterList::new().with_object("Root", params!("Damage" => Parameter::F32(10.0)));
+    /// let b = ParameterList::new().with_object("Root", params!("Damage" => Parameter::F32(12.0)));
+    /// let (path, difference) = a.first_difference(&b).unwrap();
+    /// assert_eq!(path, "Root/Damage");
+    /// assert_eq!(difference, Difference::Changed {
+    ///     old: Parameter::F32(10.0),
+    ///     new: Parameter::F32(12.0),
+    /// });
+    /// ```
+    pub fn first_difference(&self, other: &ParameterList) -> Option<(String, Difference)> {
+        if self == other {
+            return None;
+        }
+        let options = DiffOptions::default();
+        let mut ctx = DiffContext {
+            options: &options,
+            hashes: Vec::new(),
+            diff: ParameterIODiff::default(),
+        };
+        ctx.diff_list("", Some(self), Some(other), ROOT_KEY);
+        ctx.diff.changes.shift_remove_index(0)
+    }
+
+    /// Merge another parameter list into this one. Objects and lists are
+    /// merged recursively by name, and parameters, objects, and lists which
+    /// only exist in `other` are added after the existing ones. Where a
+    /// parameter exists in both with different values, `on_conflict` is
+    /// called with its path (as with [`ParameterIO::diff`], relative to this
+    /// list), this list's value, and the other value, and the parameter is
+    /// set to the value it returns. Nothing is removed.
+    ///
+    /// Unlike a three-way merge, no common base version is needed, so the
+    /// callback decides how to resolve every clash: keeping this value,
+    /// taking the other, or combining them.
+    ///
+    /// ```
+    /// # use roead::aamp::*;
+    /// let mut ours = ParameterList::new().with_object("Root", params!("Damage" => Parameter::F32(10.0)));
+    /// let theirs = ParameterList::new().with_object("Root", params!("Damage" => Parameter::F32(20.0)));
+    /// ours.merge_with(&theirs, |_, mine, theirs| match (mine, theirs) {
+    ///     (Parameter::F32(a), Parameter::F32(b)) => Parameter::F32((a + b) / 2.0),
+    ///     _ => mine.clone(),
+    /// });
+    /// assert_eq!(ours.object("Root").unwrap()["Damage"], Parameter::F32(15.0));
+    /// ```
+    pub fn merge_with<F>(&mut self, other: &ParameterList, mut on_conflict: F)
+    where
+        F: FnMut(&str, &Parameter, &Parameter) -> Parameter,
+    {
+        merge_list("", self, other, ROOT_KEY, &mut on_conflict);
+    }
+}
+
+fn merge_list<F>(
+    path: &str,
+    list: &mut ParameterList,
+    other: &ParameterList,
+    parent: Name,
+    on_conflict: &mut F,
+) where
+    F: FnMut(&str, &Parameter, &Parameter) -> Parameter,
+{
+    for (name, theirs) in other.objects.iter() {
+        let index = list.objects.0.get_index_of(name);
+        let index = index.unwrap_or(list.objects.len());
+        let obj_path = join(path, &name_to_segment(*name, index, parent));
+        let obj = list.objects.entry(*name).or_default();
+        for (param_name, their_param) in theirs.iter() {
+            match obj.0.get_full_mut(param_name) {
+                Some((i, _, param)) if param != their_param => {
+                    let param_path = join(&obj_path, &name_to_segment(*param_name, i, *name));
+                    *param = on_conflict(&param_path, param, their_param);
+                }
+                Some(_) => (),
+                None => obj.insert(*param_name, their_param.clone()),
+            }
+        }
+    }
+    for (name, theirs) in other.lists.iter() {
+        let index = list.lists.0.get_index_of(name);
+        let index = index.unwrap_or(list.lists.len());
+        let list_path = join(path, &name_to_segment(*name, index, parent));
+        merge_list(
+            &list_path,
+            list.lists.entry(*name).or_default(),
+            theirs,
+            *name,
+            on_conflict,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_unordered_buffers() {
+        let a = ParameterIO::new().with_list(
+            "AI",
+            ParameterList::new().with_object(
+                "Root",
+                params!(
+                    "Values" => Parameter::BufferU32(vec![1, 2, 3, 3]),
+                    "Name" => Parameter::StringRef("Apple".into())
+                ),
+            ),
+        );
+        let mut b = a.clone();
+        let item = b.list_mut("AI").unwrap().object_mut("Root").unwrap();
+        item.insert("Values", Parameter::BufferU32(vec![3, 1, 3, 2]));
+        item.insert("Count", Parameter::I32(1));
+
+        let diff = a.diff(&b);
+        assert_eq!(diff.len(), 2);
+        assert!(matches!(
+            diff.changes.get("AI/Root/Values"),
+            Some(Difference::Changed { .. })
+        ));
+        assert!(matches!(
+            diff.changes.get("AI/Root/Count"),
+            Some(Difference::Added(Parameter::I32(1)))
+        ));
+
+        let options = DiffOptions::new().with_unordered_buffer("AI/Root/Values");
+        let diff = a.diff_with_options(&b, &options);
+        assert_eq!(diff.len(), 1);
+        assert!(!diff.changes.contains_key("AI/Root/Values"));
+
+        b.list_mut("AI").unwrap().object_mut("Root").unwrap()["Values"] =
+            Parameter::BufferU32(vec![3, 1, 2, 2]);
+        assert!(a.diff_with_options(&b, &options).changes.contains_key("AI/Root/Values"));
+    }
+
+    #[test]
+    fn first_difference() {
+        let a = ParameterList::new().with_list(
+            "AI",
+            ParameterList::new().with_object(
+                "Root",
+                params!(
+                    "Damage" => Parameter::F32(10.0),
+                    "Range" => Parameter::F32(2.0)
+                ),
+            ),
+        );
+        assert_eq!(a.first_difference(&a.clone()), None);
+        let mut b = a.clone();
+        let root = b.list_mut("AI").unwrap().object_mut("Root").unwrap();
+        root.insert("Range", Parameter::F32(3.0));
+        root.insert("Damage", Parameter::F32(12.0));
+        let (path, difference) = a.first_difference(&b).unwrap();
+        assert_eq!(path, "AI/Root/Damage");
+        assert_eq!(difference, Difference::Changed {
+            old: Parameter::F32(10.0),
+            new: Parameter::F32(12.0),
+        });
+    }
+
+    #[test]
+    fn apply_diff() {
+        let base = ParameterIO::new().with_list(
+            "AI",
+            ParameterList::new().with_object(
+                "Root",
+                params!(
+                    "Damage" => Parameter::F32(10.0),
+                    "Range" => Parameter::F32(2.0)
+                ),
+            ),
+        );
+        let mut edited = base.clone();
+        let root = edited.list_mut("AI").unwrap().object_mut("Root").unwrap();
+        root.insert("Damage", Parameter::F32(12.0));
+        root.0.shift_remove(&Name::from("Range"));
+        edited.set_object("Flags", params!("IsEnabled" => Parameter::Bool(true)));
+        let diff = base.diff(&edited);
+
+        let mut patched = base.clone();
+        patched.apply_diff(&diff).unwrap();
+        assert_eq!(patched.diff(&edited).len(), 0);
+
+        // The diff no longer applies once a changed parameter was edited.
+        let mut stale = edited.clone();
+        stale.list_mut("AI").unwrap().object_mut("Root").unwrap()["Damage"] = Parameter::F32(15.0);
+        let before = stale.clone();
+        assert!(stale.apply_diff(&diff).is_err());
+        assert_eq!(stale, before);
+        let options = ApplyOptions { force: true };
+        stale.apply_diff_with_options(&diff, &options).unwrap();
+        assert_eq!(stale.diff(&edited).len(), 0);
+    }
+
+    #[test]
+    fn merge_with() {
+        let mut ours = ParameterList::new()
+            .with_object(
+                "Root",
+                params!(
+                    "Damage" => Parameter::I32(10),
+                    "Range" => Parameter::F32(2.0)
+                ),
+            )
+            .with_list("AI", ParameterList::new());
+        let theirs = ParameterList::new()
+            .with_object(
+                "Root",
+                params!(
+                    "Damage" => Parameter::I32(20),
+                    "Range" => Parameter::F32(2.0),
+                    "Speed" => Parameter::F32(1.5)
+                ),
+            )
+            .with_list(
+                "AI",
+                ParameterList::new()
+                    .with_object("Flags", params!("IsEnabled" => Parameter::Bool(true))),
+            );
+        let mut conflicts = vec![];
+        ours.merge_with(&theirs, |path, _, theirs| {
+            conflicts.push(path.to_owned());
+            theirs.clone()
+        });
+        assert_eq!(conflicts, ["Root/Damage"]);
+        assert_eq!(ours, theirs);
+    }
+}
@@ -0,0 +1,154 @@
+//! Conversion between parameter IOs and flat maps of dotted paths.
+use std::collections::BTreeMap;
+
+use join_str::jstr;
+
+use super::{
+    path::{name_to_segment, segment_to_name},
+    *,
+};
+
+/// Escape a path segment so that it can be joined with dots.
+fn escape_segment(segment: &str) -> String {
+    let mut escaped = String::new();
+    for c in segment.chars() {
+        if c == '.' || c == '\\' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Split a dotted path into unescaped segments.
+fn split_dotted(path: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut segment = String::new();
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => segment.extend(chars.next()),
+            '.' => segments.push(std::mem::take(&mut segment)),
+            _ => segment.push(c),
+        }
+    }
+    segments.push(segment);
+    segments
+}
+
+fn join_dotted(path: &str, name: Name, index: usize, parent: Name) -> String {
+    let mut joined = String::from(path);
+    if !joined.is_empty() {
+        joined.push('.');
+    }
+    joined.push_str(&escape_segment(&name_to_segment(name, index, parent)));
+    joined
+}
+
+fn flatten_list(
+    list: &ParameterList,
+    path: &str,
+    parent: Name,
+    flat: &mut BTreeMap<String, Parameter>,
+) {
+    for (i, (name, obj)) in list.objects.iter().enumerate() {
+        let obj_path = join_dotted(path, *name, i, parent);
+        for (j, (param_name, param)) in obj.iter().enumerate() {
+            flat.insert(join_dotted(&obj_path, *param_name, j, *name), param.clone());
+        }
+    }
+    for (i, (name, child)) in list.lists.iter().enumerate() {
+        flatten_list(child, &join_dotted(path, *name, i, parent), *name, flat);
+    }
+}
+
+impl ParameterIO {
+    /// Flatten the parameter IO into a map of dotted parameter paths (e.g.
+    /// `AI.Root.Damage`) to parameters, for exporting to flat formats such as
+    /// TOML tables or environment-style configs.
+    ///
+    /// Names are resolved using the default name table where possible, and
+    /// otherwise written as hashes. Dots and backslashes in resolved names are
+    /// escaped with a backslash. The last segment of each path is the
+    /// parameter, the one before it is the containing object, and any others
+    /// are lists.
+    ///
+    /// The map is sorted by the resolved path string (byte-wise), not by the
+    /// order of structures in the document, so flattening the same document
+    /// always yields the same ordering and exports diff cleanly between
+    /// versions.
+    ///
+    /// Empty objects and lists, the data version and type, and the order of
+    /// structures are not preserved.
+    pub fn flatten(&self) -> BTreeMap<String, Parameter> {
+        let mut flat = BTreeMap::new();
+        flatten_list(&self.param_root, "", ROOT_KEY, &mut flat);
+        flat
+    }
+
+    /// Rebuild a parameter IO from a map of dotted parameter paths, as produced
+    /// by [`ParameterIO::flatten`]. Segments consisting only of digits are
+    /// treated as hashes. Every path must have at least two segments: an
+    /// object and a parameter.
+    pub fn unflatten<'a, I>(flat: I) -> Result<ParameterIO>
+    where
+        I: IntoIterator<Item = (&'a String, &'a Parameter)>,
+    {
+        let mut pio = ParameterIO::new();
+        for (path, param) in flat {
+            let segments = split_dotted(path);
+            let [lists @ .., obj, name] = segments.as_slice() else {
+                return Err(Error::InvalidDataD(jstr!(
+                    "Flattened path `{path}` does not include an object"
+                )));
+            };
+            let list = lists.iter().fold(&mut pio.param_root, |list, segment| {
+                list.lists.entry(segment_to_name(segment)).or_default()
+            });
+            list.objects
+                .entry(segment_to_name(obj))
+                .or_default()
+                .insert(segment_to_name(name), param.clone());
+        }
+        Ok(pio)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flatten_roundtrip() {
+        get_default_name_table().add_name("Odd.Name");
+        let pio = ParameterIO::new()
+            .with_object("Flags", params!("IsEnabled" => Parameter::Bool(true)))
+            .with_list(
+                "AI",
+                ParameterList::new()
+                    .with_object("Root", params!(
+                        "Damage" => Parameter::I32(10),
+                        "Odd.Name" => Parameter::F32(0.5),
+                        0xDEADBEEFu32 => Parameter::U32(1)
+                    ))
+                    .with_list(
+                        "Root",
+                        ParameterList::new()
+                            .with_object("Values", params!("Count" => Parameter::I32(2))),
+                    ),
+            );
+        let flat = pio.flatten();
+        assert_eq!(flat.len(), 5);
+        assert_eq!(flat["AI.Root.Damage"], Parameter::I32(10));
+        assert_eq!(flat["AI.Root.Values.Count"], Parameter::I32(2));
+        assert_eq!(flat["AI.Root.Odd\\.Name"], Parameter::F32(0.5));
+        assert!(flat.contains_key("AI.Root.3735928559"));
+        assert_eq!(flat.keys().next().unwrap(), "AI.Root.3735928559");
+        assert!(flat.keys().last().unwrap().starts_with("Flags."));
+        assert_eq!(ParameterIO::unflatten(&flat).unwrap(), pio);
+
+        let mut bad = BTreeMap::new();
+        bad.insert(String::from("Damage"), Parameter::I32(1));
+        assert!(ParameterIO::unflatten(&bad).is_err());
+    }
+}
@@ -0,0 +1,245 @@
+//! Shared storage for `StringRef` parameter values.
+use std::{borrow::Borrow, fmt, hash::Hash, ops::Deref, sync::Arc};
+
+use rustc_hash::FxHashSet;
+use smartstring::alias::String;
+
+/// The value of a [`Parameter::StringRef`](super::Parameter::StringRef).
+///
+/// This dereferences to `str` and converts from and to strings, so it can be
+/// used much like a `String`. Short strings are stored inline. Strings which
+/// were interned with a [`StringInterner`] share one allocation with every
+/// identical string from the same interner, and are copied on the first
+/// mutable access.
+#[derive(Clone)]
+pub struct SharedString(Repr);
+
+#[derive(Clone)]
+enum Repr {
+    Owned(String),
+    Shared(Arc<str>),
+}
+
+impl SharedString {
+    /// Create an empty string.
+    pub const fn new() -> Self {
+        Self(Repr::Owned(String::new_const()))
+    }
+
+    /// Get the string as a `&str`.
+    pub fn as_str(&self) -> &str {
+        match &self.0 {
+            Repr::Owned(s) => s,
+            Repr::Shared(s) => s,
+        }
+    }
+
+    /// Get a mutable reference to the string, copying it first if its
+    /// storage is shared.
+    pub fn to_mut(&mut self) -> &mut String {
+        if let Repr::Shared(s) = &self.0 {
+            self.0 = Repr::Owned(s.as_ref().into());
+        }
+        match &mut self.0 {
+            Repr::Owned(s) => s,
+            Repr::Shared(_) => unreachable!(),
+        }
+    }
+
+    /// Get the string as a `&mut str`, copying it first if its storage is
+    /// shared.
+    pub fn as_mut_str(&mut self) -> &mut str {
+        self.to_mut().as_mut_str()
+    }
+
+    /// Whether the string shares its storage with an interner.
+    pub fn is_shared(&self) -> bool {
+        matches!(self.0, Repr::Shared(_))
+    }
+}
+
+impl Default for SharedString {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for SharedString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SharedString {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for SharedString {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for SharedString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SharedString {}
+
+impl PartialEq<str> for SharedString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SharedString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialOrd for SharedString {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SharedString {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Hash for SharedString {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Debug for SharedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for SharedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl From<&str> for SharedString {
+    fn from(value: &str) -> Self {
+        Self(Repr::Owned(value.into()))
+    }
+}
+
+impl From<String> for SharedString {
+    fn from(value: String) -> Self {
+        Self(Repr::Owned(value))
+    }
+}
+
+impl From<std::string::String> for SharedString {
+    fn from(value: std::string::String) -> Self {
+        Self(Repr::Owned(value.into()))
+    }
+}
+
+impl From<Arc<str>> for SharedString {
+    fn from(value: Arc<str>) -> Self {
+        Self(Repr::Shared(value))
+    }
+}
+
+impl From<SharedString> for String {
+    fn from(value: SharedString) -> Self {
+        match value.0 {
+            Repr::Owned(s) => s,
+            Repr::Shared(s) => s.as_ref().into(),
+        }
+    }
+}
+
+impl From<SharedString> for std::string::String {
+    fn from(value: SharedString) -> Self {
+        value.as_str().into()
+    }
+}
+
+#[cfg(feature = "with-serde")]
+impl serde::Serialize for SharedString {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "with-serde")]
+impl<'de> serde::Deserialize<'de> for SharedString {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// Pool of strings for sharing the storage of identical `StringRef` values,
+/// as used by
+/// [`ParameterIO::from_binary_with_interner`](super::ParameterIO::from_binary_with_interner).
+///
+/// Reusing one interner for a batch of files shares strings across all of
+/// them. Strings short enough to be stored inline are not interned, since
+/// they take no heap memory either way.
+///
+/// ```
+/// # use roead::aamp::*;
+/// let mut interner = StringInterner::new();
+/// let a = interner.intern("Enemy_Lizalfos_Electric_Profile_Long");
+/// let b = interner.intern("Enemy_Lizalfos_Electric_Profile_Long");
+/// assert!(a.is_shared() && b.is_shared());
+/// assert_eq!(interner.len(), 1);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StringInterner {
+    strings: FxHashSet<Arc<str>>,
+}
+
+impl StringInterner {
+    /// Create an empty interner.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a string with the given value, sharing storage with any identical
+    /// string interned before.
+    pub fn intern(&mut self, value: &str) -> SharedString {
+        if value.len() <= smartstring::MAX_INLINE {
+            return value.into();
+        }
+        if let Some(shared) = self.strings.get(value) {
+            return shared.clone().into();
+        }
+        let shared: Arc<str> = value.into();
+        self.strings.insert(shared.clone());
+        shared.into()
+    }
+
+    /// Number of distinct strings in the interner.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// Whether the interner holds no strings.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Drop the strings which are no longer used by any parameter.
+    pub fn shrink(&mut self) {
+        self.strings.retain(|s| Arc::strong_count(s) > 1);
+    }
+}
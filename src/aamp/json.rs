@@ -0,0 +1,282 @@
+//! Loose conversion between parameters and JSON values.
+//!
+//! This is only available with the `json` feature.
+use join_str::jstr;
+use serde_json::{json, Value};
+
+use super::*;
+
+fn expected(value: &Value, what: &str, ty: ParameterType) -> Error {
+    let ty = format!("{ty:?}");
+    Error::InvalidDataD(jstr!(
+        "Expected {what} for {&ty} parameter, found `{&value.to_string()}`"
+    ))
+}
+
+fn to_f32(value: &Value, ty: ParameterType) -> Result<f32> {
+    match value {
+        Value::Number(n) => n.as_f64().map(|f| f as f32),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| expected(value, "a number", ty))
+}
+
+fn to_int<T: TryFrom<i64> + TryFrom<u64>>(value: &Value, ty: ParameterType) -> Result<T> {
+    match value {
+        Value::Number(n) => n
+            .as_u64()
+            .and_then(|u| T::try_from(u).ok())
+            .or_else(|| n.as_i64().and_then(|i| T::try_from(i).ok())),
+        Value::String(s) => {
+            let s = s.trim();
+            match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+                Some(hex) => u64::from_str_radix(hex, 16)
+                    .ok()
+                    .and_then(|u| T::try_from(u).ok()),
+                None => s.parse::<i64>().ok().and_then(|i| T::try_from(i).ok()),
+            }
+        }
+        _ => None,
+    }
+    .ok_or_else(|| expected(value, "an integer in range", ty))
+}
+
+fn to_array(value: &Value, ty: ParameterType) -> Result<&[Value]> {
+    value
+        .as_array()
+        .map(Vec::as_slice)
+        .ok_or_else(|| expected(value, "an array", ty))
+}
+
+fn to_floats<const N: usize>(value: &Value, ty: ParameterType) -> Result<[f32; N]> {
+    let values = to_array(value, ty)?;
+    if values.len() != N {
+        return Err(expected(
+            value,
+            &jstr!("an array of {&N.to_string()} numbers"),
+            ty,
+        ));
+    }
+    let mut floats = [0.0; N];
+    for (float, value) in floats.iter_mut().zip(values) {
+        *float = to_f32(value, ty)?;
+    }
+    Ok(floats)
+}
+
+fn to_fixed<const N: usize>(value: &Value, ty: ParameterType) -> Result<FixedSafeString<N>> {
+    let s = value
+        .as_str()
+        .ok_or_else(|| expected(value, "a string", ty))?;
+    FixedSafeString::try_from_str(s)
+}
+
+fn to_curves<const N: usize>(value: &Value, ty: ParameterType) -> Result<Box<[Curve; N]>> {
+    let values = to_array(value, ty)?;
+    if values.len() != N {
+        return Err(expected(
+            value,
+            &jstr!("an array of {&N.to_string()} curves"),
+            ty,
+        ));
+    }
+    let mut curves = Box::new(
+        [Curve {
+            a: 0,
+            b: 0,
+            floats: [0.0; 30],
+        }; N],
+    );
+    for (curve, value) in curves.iter_mut().zip(values) {
+        let field = |name: &str| {
+            value
+                .get(name)
+                .ok_or_else(|| expected(value, "a curve with `a`, `b`, and `floats`", ty))
+        };
+        curve.a = to_int(field("a")?, ty)?;
+        curve.b = to_int(field("b")?, ty)?;
+        let floats = to_array(field("floats")?, ty)?;
+        if floats.len() > curve.floats.len() {
+            return Err(expected(value, "at most 30 curve floats", ty));
+        }
+        for (float, value) in curve.floats.iter_mut().zip(floats) {
+            *float = to_f32(value, ty)?;
+        }
+    }
+    Ok(curves)
+}
+
+impl Parameter {
+    /// Convert the parameter to a JSON value, for scripting or passing to
+    /// other tools. Unlike the serde implementation (with the `with-serde`
+    /// feature), the value does not record the parameter type, so it must be
+    /// known to convert it back with [`Parameter::from_json_value`]. The
+    /// shape is:
+    ///
+    /// - Bools, numbers, and strings (of any type) as the same JSON types.
+    /// - Vectors, colors, and quaternions as arrays of their components.
+    /// - Curves as arrays of `{"a": .., "b": .., "floats": [..]}` objects.
+    /// - Buffers (including binary buffers) as arrays of numbers.
+    /// - Strings which are not valid UTF-8 as arrays of bytes.
+    ///
+    /// Floats are widened to `f64`, so converting back gives the same value.
+    ///
+    /// This is only available with the `json` feature.
+    pub fn to_json_value(&self) -> Value {
+        fn curves(curves: &[Curve]) -> Value {
+            curves
+                .iter()
+                .map(|c| json!({ "a": c.a, "b": c.b, "floats": c.floats.as_slice() }))
+                .collect()
+        }
+
+        match self {
+            Parameter::Bool(b) => json!(b),
+            Parameter::F32(f) => json!(f),
+            Parameter::I32(i) => json!(i),
+            Parameter::U32(u) => json!(u),
+            Parameter::Vec2(v) => json!([v.x, v.y]),
+            Parameter::Vec3(v) => json!([v.x, v.y, v.z]),
+            Parameter::Vec4(v) => json!([v.x, v.y, v.z, v.t]),
+            Parameter::Color(c) => json!([c.r, c.g, c.b, c.a]),
+            Parameter::Quat(q) => json!([q.a, q.b, q.c, q.d]),
+            Parameter::String32(s) => json!(s.as_str()),
+            Parameter::String64(s) => json!(s.as_str()),
+            Parameter::String256(s) => json!(s.as_str()),
+            Parameter::StringRef(s) => json!(s.as_str()),
+            Parameter::Curve1(c) => curves(c.as_slice()),
+            Parameter::Curve2(c) => curves(c.as_slice()),
+            Parameter::Curve3(c) => curves(c.as_slice()),
+            Parameter::Curve4(c) => curves(c.as_slice()),
+            Parameter::BufferInt(v) => json!(v),
+            Parameter::BufferF32(v) => json!(v),
+            Parameter::BufferU32(v) => json!(v),
+            Parameter::BufferBinary(v) | Parameter::StringRefRaw(v) => json!(v),
+        }
+    }
+
+    /// Convert a JSON value to a parameter of the given type, using the shape
+    /// described in [`Parameter::to_json_value`]. The conversion is loose, as
+    /// the type determines how to read the value: numbers may also be given
+    /// as strings (integers optionally in hexadecimal with `0x`), and any
+    /// number is accepted for a float.
+    ///
+    /// Returns an error if the value does not fit the type, including
+    /// integers out of range and strings which are too long for a
+    /// fixed-length string type (see
+    /// [`ParameterType::fixed_string_capacity`]).
+    ///
+    /// This is only available with the `json` feature.
+    pub fn from_json_value(value: &Value, expected_type: ParameterType) -> Result<Parameter> {
+        let ty = expected_type;
+        Ok(match ty {
+            ParameterType::Bool => Parameter::Bool(
+                value
+                    .as_bool()
+                    .ok_or_else(|| expected(value, "a bool", ty))?,
+            ),
+            ParameterType::F32 => Parameter::F32(to_f32(value, ty)?),
+            ParameterType::Int => Parameter::I32(to_int(value, ty)?),
+            ParameterType::U32 => Parameter::U32(to_int(value, ty)?),
+            ParameterType::Vec2 => {
+                let [x, y] = to_floats(value, ty)?;
+                Parameter::Vec2(Vector2f { x, y })
+            }
+            ParameterType::Vec3 => {
+                let [x, y, z] = to_floats(value, ty)?;
+                Parameter::Vec3(Vector3f { x, y, z })
+            }
+            ParameterType::Vec4 => {
+                let [x, y, z, t] = to_floats(value, ty)?;
+                Parameter::Vec4(Vector4f { x, y, z, t })
+            }
+            ParameterType::Color => {
+                let [r, g, b, a] = to_floats(value, ty)?;
+                Parameter::Color(Color { r, g, b, a })
+            }
+            ParameterType::Quat => {
+                let [a, b, c, d] = to_floats(value, ty)?;
+                Parameter::Quat(Quat { a, b, c, d })
+            }
+            ParameterType::String32 => Parameter::String32(to_fixed(value, ty)?),
+            ParameterType::String64 => Parameter::String64(Box::new(to_fixed(value, ty)?)),
+            ParameterType::String256 => Parameter::String256(Box::new(to_fixed(value, ty)?)),
+            ParameterType::StringRef => match value {
+                Value::String(s) => Parameter::StringRef(s.as_str().into()),
+                Value::Array(_) => {
+                    let bytes = to_array(value, ty)?
+                        .iter()
+                        .map(|b| to_int(b, ty))
+                        .collect::<Result<Vec<u8>>>()?;
+                    match std::string::String::from_utf8(bytes) {
+                        Ok(s) => Parameter::StringRef(s.into()),
+                        Err(e) => Parameter::StringRefRaw(e.into_bytes()),
+                    }
+                }
+                _ => return Err(expected(value, "a string", ty)),
+            },
+            ParameterType::Curve1 => Parameter::Curve1(to_curves(value, ty)?),
+            ParameterType::Curve2 => Parameter::Curve2(to_curves(value, ty)?),
+            ParameterType::Curve3 => Parameter::Curve3(to_curves(value, ty)?),
+            ParameterType::Curve4 => Parameter::Curve4(to_curves(value, ty)?),
+            ParameterType::BufferInt => Parameter::BufferInt(
+                to_array(value, ty)?
+                    .iter()
+                    .map(|v| to_int(v, ty))
+                    .collect::<Result<_>>()?,
+            ),
+            ParameterType::BufferF32 => Parameter::BufferF32(
+                to_array(value, ty)?
+                    .iter()
+                    .map(|v| to_f32(v, ty))
+                    .collect::<Result<_>>()?,
+            ),
+            ParameterType::BufferU32 => Parameter::BufferU32(
+                to_array(value, ty)?
+                    .iter()
+                    .map(|v| to_int(v, ty))
+                    .collect::<Result<_>>()?,
+            ),
+            ParameterType::BufferBinary => Parameter::BufferBinary(
+                to_array(value, ty)?
+                    .iter()
+                    .map(|v| to_int(v, ty))
+                    .collect::<Result<_>>()?,
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_roundtrip() {
+        let pio = ParameterIO::from_binary(std::fs::read("test/aamp/Lizalfos.bphysics").unwrap())
+            .unwrap();
+        for param in pio.flatten().values() {
+            let value = param.to_json_value();
+            assert_eq!(
+                &Parameter::from_json_value(&value, param.get_type()).unwrap(),
+                param
+            );
+        }
+
+        let ty = ParameterType::U32;
+        assert_eq!(
+            Parameter::from_json_value(&json!("0x10"), ty).unwrap(),
+            Parameter::U32(16)
+        );
+        assert!(Parameter::from_json_value(&json!(-1), ty).is_err());
+        let ty = ParameterType::F32;
+        assert_eq!(
+            Parameter::from_json_value(&json!(2), ty).unwrap(),
+            Parameter::F32(2.0)
+        );
+        let ty = ParameterType::String32;
+        assert!(Parameter::from_json_value(&json!("a".repeat(31)), ty).is_ok());
+        assert!(Parameter::from_json_value(&json!("a".repeat(32)), ty).is_err());
+    }
+}
@@ -31,14 +31,34 @@
 //! [`ParameterListMap`]) can take either a name or a hash for key-based
 //! operations, and likewise can be indexed by the same. As usual, indexing into
 //! a non-existent key will panic.
+mod builder;
+#[cfg(feature = "byml")]
+mod byml;
+mod diff;
+mod flat;
+mod intern;
+#[cfg(feature = "json")]
+mod json;
 mod names;
 mod parser;
+mod path;
+mod schema;
+mod stats;
 #[cfg(feature = "yaml")]
-mod text;
+pub mod text;
 mod writer;
 use binrw::binrw;
 use indexmap::IndexMap;
-pub use names::{get_default_name_table, NameTable};
+pub use builder::ParameterIOBuilder;
+pub use diff::{ApplyOptions, DiffOptions, Difference, ParameterIODiff};
+pub use intern::{SharedString, StringInterner};
+pub use names::{get_default_name_table, NameSource, NameTable};
+pub use parser::{DuplicateEntry, DuplicateValue, Limits};
+pub use schema::{ListSchema, ObjectSchema, ParamSchema, SchemaError};
+pub use stats::ParameterIOStats;
+#[cfg(feature = "yaml")]
+pub use text::{FormattedValue, ParseOptions, TextOptions, TextWriter};
+pub use writer::{LayoutEntry, LayoutKind, WriteOptions};
 use num_traits::AsPrimitive;
 #[cfg(feature = "with-serde")]
 use serde::{Deserialize, Serialize};
@@ -49,9 +69,20 @@ use crate::{types::*, util::u24, Error, Result};
 type ParameterStructureMap<V> =
     IndexMap<Name, V, std::hash::BuildHasherDefault<rustc_hash::FxHasher>>;
 
+/// The reflected CRC32 polynomial used for AAMP name hashes.
+pub const CRC32_POLYNOMIAL: u32 = 0xEDB88320;
+
 /// CRC hash function matching that used in BOTW.
 #[inline]
 pub const fn hash_name(name: &str) -> u32 {
+    hash_name_with(CRC32_POLYNOMIAL, name)
+}
+
+/// CRC32 hash function with a custom polynomial, in reflected (LSB-first)
+/// form, for formats related to AAMP which hash names differently. With
+/// [`CRC32_POLYNOMIAL`], this is the same as [`hash_name`].
+#[inline]
+pub const fn hash_name_with(poly: u32, name: &str) -> u32 {
     let mut crc = 0xFFFFFFFF;
     let mut i = 0;
     while i < name.len() {
@@ -59,7 +90,7 @@ pub const fn hash_name(name: &str) -> u32 {
         let mut j = 0;
         while j < 8 {
             if crc & 1 == 1 {
-                crc = (crc >> 1) ^ 0xEDB88320;
+                crc = (crc >> 1) ^ poly;
             } else {
                 crc >>= 1;
             }
@@ -70,6 +101,61 @@ pub const fn hash_name(name: &str) -> u32 {
     !crc
 }
 
+/// Table-driven CRC32 hasher with a custom polynomial, for hashing many
+/// names faster than [`hash_name_with`]. The table can be built at compile
+/// time.
+///
+/// ```
+/// # use roead::aamp::*;
+/// const HASHER: NameHasher = NameHasher::new(CRC32_POLYNOMIAL);
+/// assert_eq!(HASHER.hash("LinkTargets"), hash_name("LinkTargets"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameHasher {
+    table: [u32; 256],
+}
+
+impl Default for NameHasher {
+    fn default() -> Self {
+        Self::new(CRC32_POLYNOMIAL)
+    }
+}
+
+impl NameHasher {
+    /// Create a hasher for a polynomial in reflected (LSB-first) form.
+    pub const fn new(poly: u32) -> Self {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut j = 0;
+            while j < 8 {
+                if crc & 1 == 1 {
+                    crc = (crc >> 1) ^ poly;
+                } else {
+                    crc >>= 1;
+                }
+                j += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        Self { table }
+    }
+
+    /// Get the lookup table, where each entry is the CRC of its index byte.
+    pub fn table(&self) -> &[u32; 256] {
+        &self.table
+    }
+
+    /// Hash a name.
+    pub fn hash(&self, name: &str) -> u32 {
+        !name.bytes().fold(0xFFFFFFFF, |crc, byte| {
+            (crc >> 8) ^ self.table[((crc ^ byte as u32) & 0xFF) as usize]
+        })
+    }
+}
+
 /// A convenient macro for hashing AAMP names. This can help ensure they are
 /// hashed at compile time in contexts where the compiler may not otherwise
 /// realize it is an option.
@@ -96,36 +182,80 @@ fn check_hasher() {
     const HASHED: u32 = hash_name("The Abolition of Man");
     const HASH: u32 = 0x41afa934;
     assert_eq!(HASHED, HASH);
+    assert_eq!(hash_name_with(CRC32_POLYNOMIAL, "The Abolition of Man"), HASH);
+    assert_eq!(NameHasher::default().hash("The Abolition of Man"), HASH);
+    // CRC-32C (Castagnoli) check value
+    let castagnoli = NameHasher::new(0x82F63B78);
+    assert_eq!(castagnoli.hash("123456789"), 0xE3069283);
+    assert_eq!(hash_name_with(0x82F63B78, "123456789"), 0xE3069283);
 }
 
-#[derive(Debug)]
+/// Parameter type, as stored in binary parameter archives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[binrw::binrw]
 #[repr(u8)]
 #[brw(repr = u8)]
-enum Type {
+pub enum ParameterType {
+    /// Boolean.
     Bool = 0,
+    /// Float.
     F32,
+    /// Int.
     Int,
+    /// 2D vector.
     Vec2,
+    /// 3D vector.
     Vec3,
+    /// 4D vector.
     Vec4,
+    /// Color.
     Color,
+    /// String (max length 32 bytes).
     String32,
+    /// String (max length 64 bytes).
     String64,
+    /// A single curve.
     Curve1,
+    /// Two curves.
     Curve2,
+    /// Three curves.
     Curve3,
+    /// Four curves.
     Curve4,
+    /// Buffer of signed ints.
     BufferInt,
+    /// Buffer of floats.
     BufferF32,
+    /// String (max length 256 bytes).
     String256,
+    /// Quaternion.
     Quat,
+    /// Unsigned int.
     U32,
+    /// Buffer of unsigned ints.
     BufferU32,
+    /// Buffer of binary data.
     BufferBinary,
+    /// String (no length limit).
     StringRef,
 }
 
+impl ParameterType {
+    /// Returns the capacity in bytes of a fixed-length string type
+    /// (`String32`, `String64`, or `String256`), or `None` for any other
+    /// type. The capacity includes the null terminator, so at most
+    /// `capacity - 1` bytes of string data fit (see
+    /// [`FixedSafeString::try_from_str`]).
+    pub const fn fixed_string_capacity(&self) -> Option<usize> {
+        match self {
+            ParameterType::String32 => Some(32),
+            ParameterType::String64 => Some(64),
+            ParameterType::String256 => Some(256),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug)]
 #[binrw]
 #[brw(little, magic = b"AAMP")]
@@ -151,7 +281,7 @@ struct ResHeader {
 struct ResParameter {
     name: Name,
     data_rel_offset: u24,
-    type_: Type,
+    type_: ParameterType,
 }
 
 #[derive(Debug)]
@@ -179,9 +309,14 @@ struct ResParameterList {
 /// Note that unlike `agl::utl::Parameter` the name is not stored as part of
 /// the parameter class in order to make the parameter logic simpler and more
 /// efficient.
+///
+/// Parameters can be constructed from common Rust types with [`From`], e.g.
+/// `obj.insert("Damage", 10.into())`. Since AAMP integers are signed by
+/// default, `i32` becomes `I32` and only `u32` becomes `U32`. Strings become
+/// `StringRef`, and `[f32; N]` arrays become the vector types.
 #[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
 #[allow(clippy::derived_hash_with_manual_eq)]
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub enum Parameter {
     /// Boolean.
     Bool(bool),
@@ -223,8 +358,88 @@ pub enum Parameter {
     BufferU32(Vec<u32>),
     /// Buffer of binary data.
     BufferBinary(Vec<u8>),
-    /// String (no length limit).
-    StringRef(String),
+    /// String (no length limit). Identical values can share their storage
+    /// (see [`StringInterner`]).
+    StringRef(SharedString),
+    /// String (no length limit) which is not valid UTF-8, such as some
+    /// Shift-JIS strings in game files. This is written to binary as a
+    /// `StringRef` with the bytes preserved exactly.
+    StringRefRaw(Vec<u8>),
+}
+
+/// Number of buffer elements shown by the [`Debug`](std::fmt::Debug)
+/// implementation of [`Parameter`] before the rest are elided.
+const DEBUG_BUFFER_LIMIT: usize = 16;
+
+/// Formats an integer as hex in debug output.
+struct DebugHex<T>(T);
+
+impl<T: std::fmt::LowerHex> std::fmt::Debug for DebugHex<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+/// Formats a buffer as a list, eliding elements past [`DEBUG_BUFFER_LIMIT`].
+struct DebugBuffer<'a, T, F>(&'a [T], F);
+
+impl<T, D: std::fmt::Debug, F: Fn(&T) -> D> std::fmt::Debug for DebugBuffer<'_, T, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut list = f.debug_list();
+        list.entries(self.0.iter().take(DEBUG_BUFFER_LIMIT).map(&self.1));
+        if self.0.len() > DEBUG_BUFFER_LIMIT {
+            list.entry(&format_args!("... ({} items)", self.0.len()));
+        }
+        list.finish()
+    }
+}
+
+/// Unlike a derived implementation, this shows `U32` values and unsigned or
+/// binary buffers in hex, prints vectors, colors and quaternions as plain
+/// tuples (e.g. `Vec3(1.0, 2.0, 3.0)`), and only shows the first 16 elements
+/// of buffers followed by their total length.
+impl std::fmt::Debug for Parameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Parameter::Bool(v) => f.debug_tuple("Bool").field(v).finish(),
+            Parameter::F32(v) => f.debug_tuple("F32").field(v).finish(),
+            Parameter::I32(v) => f.debug_tuple("I32").field(v).finish(),
+            Parameter::U32(v) => f.debug_tuple("U32").field(&DebugHex(v)).finish(),
+            Parameter::Vec2(v) => write!(f, "Vec2({:?}, {:?})", v.x, v.y),
+            Parameter::Vec3(v) => write!(f, "Vec3({:?}, {:?}, {:?})", v.x, v.y, v.z),
+            Parameter::Vec4(v) => write!(f, "Vec4({:?}, {:?}, {:?}, {:?})", v.x, v.y, v.z, v.t),
+            Parameter::Color(v) => write!(f, "Color({:?}, {:?}, {:?}, {:?})", v.r, v.g, v.b, v.a),
+            Parameter::Quat(v) => write!(f, "Quat({:?}, {:?}, {:?}, {:?})", v.a, v.b, v.c, v.d),
+            Parameter::String32(v) => f.debug_tuple("String32").field(v).finish(),
+            Parameter::String64(v) => f.debug_tuple("String64").field(v).finish(),
+            Parameter::String256(v) => f.debug_tuple("String256").field(v).finish(),
+            Parameter::StringRef(v) => f.debug_tuple("StringRef").field(v).finish(),
+            Parameter::Curve1(v) => f.debug_tuple("Curve1").field(v).finish(),
+            Parameter::Curve2(v) => f.debug_tuple("Curve2").field(v).finish(),
+            Parameter::Curve3(v) => f.debug_tuple("Curve3").field(v).finish(),
+            Parameter::Curve4(v) => f.debug_tuple("Curve4").field(v).finish(),
+            Parameter::BufferInt(v) => f
+                .debug_tuple("BufferInt")
+                .field(&DebugBuffer(v, |v: &i32| *v))
+                .finish(),
+            Parameter::BufferF32(v) => f
+                .debug_tuple("BufferF32")
+                .field(&DebugBuffer(v, |v: &f32| *v))
+                .finish(),
+            Parameter::BufferU32(v) => f
+                .debug_tuple("BufferU32")
+                .field(&DebugBuffer(v, |v: &u32| DebugHex(*v)))
+                .finish(),
+            Parameter::BufferBinary(v) => f
+                .debug_tuple("BufferBinary")
+                .field(&DebugBuffer(v, |v: &u8| DebugHex(*v)))
+                .finish(),
+            Parameter::StringRefRaw(v) => f
+                .debug_tuple("StringRefRaw")
+                .field(&DebugBuffer(v, |v: &u8| DebugHex(*v)))
+                .finish(),
+        }
+    }
 }
 
 impl Parameter {
@@ -251,6 +466,7 @@ impl Parameter {
             Parameter::BufferU32(_) => "BufferU32".into(),
             Parameter::BufferBinary(_) => "BufferBinary".into(),
             Parameter::StringRef(_) => "StringRef".into(),
+            Parameter::StringRefRaw(_) => "StringRefRaw".into(),
         }
     }
 
@@ -736,6 +952,15 @@ impl Parameter {
         }
     }
 
+    /// Get a mutable reference to the inner u32 buffer as a `Vec`, so it
+    /// can be resized in place.
+    pub fn as_mut_buffer_u32_vec(&mut self) -> Result<&mut Vec<u32>> {
+        match self {
+            Parameter::BufferU32(value) => Ok(value),
+            _ => Err(Error::TypeError(self.type_name(), "Vec<u32>")),
+        }
+    }
+
     /// Extract the inner u32 buffer value.
     pub fn into_buffer_u32(self) -> Result<Vec<u32>> {
         match self {
@@ -760,6 +985,15 @@ impl Parameter {
         }
     }
 
+    /// Get a mutable reference to the inner binary buffer as a `Vec`, so it
+    /// can be resized in place.
+    pub fn as_mut_buffer_binary_vec(&mut self) -> Result<&mut Vec<u8>> {
+        match self {
+            Parameter::BufferBinary(value) => Ok(value),
+            _ => Err(Error::TypeError(self.type_name(), "Vec<u8>")),
+        }
+    }
+
     /// Extract the inner binary buffer value.
     pub fn into_buffer_binary(self) -> Result<Vec<u8>> {
         match self {
@@ -779,7 +1013,7 @@ impl Parameter {
     /// Get a mutable reference to the inner string.
     pub fn as_mut_string_ref(&mut self) -> Result<&mut str> {
         match self {
-            Parameter::StringRef(value) => Ok(value),
+            Parameter::StringRef(value) => Ok(value.as_mut_str()),
             _ => Err(Error::TypeError(self.type_name(), "string")),
         }
     }
@@ -787,7 +1021,7 @@ impl Parameter {
     /// Extract the inner string value.
     pub fn into_string_ref(self) -> Result<String> {
         match self {
-            Parameter::StringRef(value) => Ok(value),
+            Parameter::StringRef(value) => Ok(value.into()),
             _ => Err(Error::TypeError(self.type_name(), "string")),
         }
     }
@@ -836,6 +1070,8 @@ impl TryFrom<Parameter> for f32 {
 }
 
 impl From<i32> for Parameter {
+    /// Converts to an `I32` parameter. Note that an unsuffixed integer literal
+    /// is inferred as `i32`.
     fn from(value: i32) -> Self {
         Parameter::I32(value)
     }
@@ -1063,6 +1299,7 @@ impl TryFrom<Parameter> for Quat {
 }
 
 impl From<u32> for Parameter {
+    /// Converts to a `U32` parameter.
     fn from(value: u32) -> Self {
         Parameter::U32(value)
     }
@@ -1112,7 +1349,7 @@ impl TryFrom<Parameter> for Vec<u8> {
 
 impl From<String> for Parameter {
     fn from(value: String) -> Self {
-        Parameter::StringRef(value)
+        Parameter::StringRef(value.into())
     }
 }
 
@@ -1121,7 +1358,7 @@ impl TryFrom<Parameter> for String {
 
     fn try_from(value: Parameter) -> std::result::Result<Self, Self::Error> {
         match value {
-            Parameter::StringRef(v) => Ok(v),
+            Parameter::StringRef(v) => Ok(v.into()),
             Parameter::String32(v) => Ok(v.into()),
             Parameter::String64(v) => Ok(v.as_str().into()),
             Parameter::String256(v) => Ok(v.as_str().into()),
@@ -1130,6 +1367,40 @@ impl TryFrom<Parameter> for String {
     }
 }
 
+impl From<&str> for Parameter {
+    /// Converts to a `StringRef` parameter.
+    fn from(value: &str) -> Self {
+        Parameter::StringRef(value.into())
+    }
+}
+
+impl From<std::string::String> for Parameter {
+    /// Converts to a `StringRef` parameter.
+    fn from(value: std::string::String) -> Self {
+        Parameter::StringRef(value.into())
+    }
+}
+
+impl From<[f32; 2]> for Parameter {
+    fn from([x, y]: [f32; 2]) -> Self {
+        Parameter::Vec2(Vector2f { x, y })
+    }
+}
+
+impl From<[f32; 3]> for Parameter {
+    fn from([x, y, z]: [f32; 3]) -> Self {
+        Parameter::Vec3(Vector3f { x, y, z })
+    }
+}
+
+impl From<[f32; 4]> for Parameter {
+    /// Converts to a `Vec4` parameter. Use [`Quat`] or [`Color`] for the other
+    /// four-component types.
+    fn from([x, y, z, t]: [f32; 4]) -> Self {
+        Parameter::Vec4(Vector4f { x, y, z, t })
+    }
+}
+
 impl std::hash::Hash for Parameter {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         match self {
@@ -1162,6 +1433,7 @@ impl std::hash::Hash for Parameter {
             Parameter::BufferU32(v) => v.hash(state),
             Parameter::BufferBinary(v) => v.hash(state),
             Parameter::StringRef(s) => s.hash(state),
+            Parameter::StringRefRaw(s) => s.hash(state),
         }
     }
 }
@@ -1190,6 +1462,7 @@ impl PartialEq for Parameter {
             (Self::BufferU32(a), Self::BufferU32(b)) => a == b,
             (Self::BufferBinary(a), Self::BufferBinary(b)) => a == b,
             (Self::StringRef(a), Self::StringRef(b)) => a == b,
+            (Self::StringRefRaw(a), Self::StringRefRaw(b)) => a == b,
             _ => false,
         }
     }
@@ -1198,35 +1471,38 @@ impl PartialEq for Parameter {
 impl Eq for Parameter {}
 
 impl Parameter {
+    /// Returns the binary type of the parameter.
     #[inline(always)]
-    fn get_type(&self) -> Type {
-        match self {
-            Parameter::Bool(_) => Type::Bool,
-            Parameter::F32(_) => Type::F32,
-            Parameter::I32(_) => Type::Int,
-            Parameter::Vec2(_) => Type::Vec2,
-            Parameter::Vec3(_) => Type::Vec3,
-            Parameter::Vec4(_) => Type::Vec4,
-            Parameter::Color(_) => Type::Color,
-            Parameter::String32(_) => Type::String32,
-            Parameter::String64(_) => Type::String64,
-            Parameter::Curve1(_) => Type::Curve1,
-            Parameter::Curve2(_) => Type::Curve2,
-            Parameter::Curve3(_) => Type::Curve3,
-            Parameter::Curve4(_) => Type::Curve4,
-            Parameter::BufferInt(_) => Type::BufferInt,
-            Parameter::BufferF32(_) => Type::BufferF32,
-            Parameter::String256(_) => Type::String256,
-            Parameter::Quat(_) => Type::Quat,
-            Parameter::U32(_) => Type::U32,
-            Parameter::BufferU32(_) => Type::BufferU32,
-            Parameter::BufferBinary(_) => Type::BufferBinary,
-            Parameter::StringRef(_) => Type::StringRef,
-        }
-    }
-
+    pub fn get_type(&self) -> ParameterType {
+        match self {
+            Parameter::Bool(_) => ParameterType::Bool,
+            Parameter::F32(_) => ParameterType::F32,
+            Parameter::I32(_) => ParameterType::Int,
+            Parameter::Vec2(_) => ParameterType::Vec2,
+            Parameter::Vec3(_) => ParameterType::Vec3,
+            Parameter::Vec4(_) => ParameterType::Vec4,
+            Parameter::Color(_) => ParameterType::Color,
+            Parameter::String32(_) => ParameterType::String32,
+            Parameter::String64(_) => ParameterType::String64,
+            Parameter::Curve1(_) => ParameterType::Curve1,
+            Parameter::Curve2(_) => ParameterType::Curve2,
+            Parameter::Curve3(_) => ParameterType::Curve3,
+            Parameter::Curve4(_) => ParameterType::Curve4,
+            Parameter::BufferInt(_) => ParameterType::BufferInt,
+            Parameter::BufferF32(_) => ParameterType::BufferF32,
+            Parameter::String256(_) => ParameterType::String256,
+            Parameter::Quat(_) => ParameterType::Quat,
+            Parameter::U32(_) => ParameterType::U32,
+            Parameter::BufferU32(_) => ParameterType::BufferU32,
+            Parameter::BufferBinary(_) => ParameterType::BufferBinary,
+            Parameter::StringRef(_) | Parameter::StringRefRaw(_) => ParameterType::StringRef,
+        }
+    }
+
+    /// Returns `true` if the parameter is a buffer (`BufferInt`, `BufferF32`,
+    /// `BufferU32`, or `BufferBinary`).
     #[inline(always)]
-    fn is_buffer_type(&self) -> bool {
+    pub fn is_buffer(&self) -> bool {
         matches!(
             self,
             Parameter::BufferInt(_)
@@ -1236,14 +1512,49 @@ impl Parameter {
         )
     }
 
+    /// Returns `true` if the parameter is any string type, including
+    /// `StringRefRaw`.
     #[inline(always)]
-    fn is_string_type(&self) -> bool {
+    pub fn is_string(&self) -> bool {
         matches!(
             self,
             Parameter::String32(_)
                 | Parameter::String64(_)
                 | Parameter::String256(_)
                 | Parameter::StringRef(_)
+                | Parameter::StringRefRaw(_)
+        )
+    }
+
+    /// Returns `true` if the parameter is a single number (`F32`, `I32`, or
+    /// `U32`).
+    #[inline(always)]
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Parameter::F32(_) | Parameter::I32(_) | Parameter::U32(_)
+        )
+    }
+
+    /// Returns `true` if the parameter is a vector (`Vec2`, `Vec3`, or
+    /// `Vec4`). Quaternions and colors are not considered vectors.
+    #[inline(always)]
+    pub fn is_vector(&self) -> bool {
+        matches!(
+            self,
+            Parameter::Vec2(_) | Parameter::Vec3(_) | Parameter::Vec4(_)
+        )
+    }
+
+    /// Returns `true` if the parameter is a curve (`Curve1` to `Curve4`).
+    #[inline(always)]
+    pub fn is_curve(&self) -> bool {
+        matches!(
+            self,
+            Parameter::Curve1(_)
+                | Parameter::Curve2(_)
+                | Parameter::Curve3(_)
+                | Parameter::Curve4(_)
         )
     }
 
@@ -1257,11 +1568,318 @@ impl Parameter {
             _ => Err(Error::TypeError(self.type_name(), "a string type")),
         }
     }
+
+    /// Returns the bytes of the parameter if it is any string type, including
+    /// `StringRefRaw`.
+    pub fn as_string_bytes(&self) -> Result<&[u8]> {
+        match self {
+            Parameter::StringRefRaw(s) => Ok(s.as_slice()),
+            _ => self.as_str().map(str::as_bytes),
+        }
+    }
+
+    /// Create a fixed-length string parameter (`String32`, `String64`, or
+    /// `String256`, chosen by `capacity`, which must be 32, 64, or 256).
+    /// Unlike converting from a string, which silently truncates (possibly
+    /// in the middle of a multibyte character), this returns an error if the
+    /// UTF-8 encoded string does not fit in the capacity along with its null
+    /// terminator, so at most `capacity - 1` bytes are allowed.
+    ///
+    /// ```
+    /// # use roead::aamp::Parameter;
+    /// let name = Parameter::fixed_string("Ganon", 32).unwrap();
+    /// assert_eq!(name, Parameter::String32("Ganon".into()));
+    /// // 13 characters, but 39 bytes.
+    /// assert!(Parameter::fixed_string("ガノンドロフ・ドラグメイア", 32).is_err());
+    /// ```
+    pub fn fixed_string(s: &str, capacity: usize) -> Result<Parameter> {
+        let ty = [
+            ParameterType::String32,
+            ParameterType::String64,
+            ParameterType::String256,
+        ]
+        .into_iter()
+        .find(|ty| ty.fixed_string_capacity() == Some(capacity))
+        .ok_or_else(|| {
+            Error::InvalidDataD(format!(
+                "Invalid fixed string capacity {capacity} (expected 32, 64, or 256)"
+            ))
+        })?;
+        Ok(match ty {
+            ParameterType::String32 => Parameter::String32(FixedSafeString::try_from_str(s)?),
+            ParameterType::String64 => {
+                Parameter::String64(Box::new(FixedSafeString::try_from_str(s)?))
+            }
+            _ => Parameter::String256(Box::new(FixedSafeString::try_from_str(s)?)),
+        })
+    }
+
+    /// Set the value of a `Bool` parameter. Unlike assigning a new
+    /// [`Parameter`], this returns an error instead of changing the type if
+    /// the parameter is of a different type. The other `set_*` methods work
+    /// the same way.
+    pub fn set_bool(&mut self, value: bool) -> Result<()> {
+        *self.as_mut_bool()? = value;
+        Ok(())
+    }
+
+    /// Set the value of a `F32` parameter, keeping its type.
+    pub fn set_f32(&mut self, value: f32) -> Result<()> {
+        *self.as_mut_f32()? = value;
+        Ok(())
+    }
+
+    /// Set the value of a `I32` parameter, keeping its type.
+    pub fn set_i32(&mut self, value: i32) -> Result<()> {
+        *self.as_mut_i32()? = value;
+        Ok(())
+    }
+
+    /// Set the value of a `U32` parameter, keeping its type.
+    pub fn set_u32(&mut self, value: u32) -> Result<()> {
+        *self.as_mut_u32()? = value;
+        Ok(())
+    }
+
+    /// Set the value of a `Vec2` parameter, keeping its type.
+    pub fn set_vec2(&mut self, value: Vector2f) -> Result<()> {
+        *self.as_mut_vec2()? = value;
+        Ok(())
+    }
+
+    /// Set the value of a `Vec3` parameter, keeping its type.
+    pub fn set_vec3(&mut self, value: Vector3f) -> Result<()> {
+        *self.as_mut_vec3()? = value;
+        Ok(())
+    }
+
+    /// Set the value of a `Vec4` parameter, keeping its type.
+    pub fn set_vec4(&mut self, value: Vector4f) -> Result<()> {
+        *self.as_mut_vec4()? = value;
+        Ok(())
+    }
+
+    /// Set the value of a `Color` parameter, keeping its type.
+    pub fn set_color(&mut self, value: Color) -> Result<()> {
+        *self.as_mut_color()? = value;
+        Ok(())
+    }
+
+    /// Set the value of a `Quat` parameter, keeping its type.
+    pub fn set_quat(&mut self, value: Quat) -> Result<()> {
+        *self.as_mut_quat()? = value;
+        Ok(())
+    }
+
+    /// Set the value of a parameter of any string type, keeping its type. For
+    /// fixed-size strings, returns an error if the value does not fit.
+    pub fn set_str(&mut self, value: &str) -> Result<()> {
+        match self {
+            Parameter::String32(s) => *s = FixedSafeString::try_from_str(value)?,
+            Parameter::String64(s) => **s = FixedSafeString::try_from_str(value)?,
+            Parameter::String256(s) => **s = FixedSafeString::try_from_str(value)?,
+            Parameter::StringRef(s) => *s = value.into(),
+            Parameter::StringRefRaw(_) => *self = Parameter::StringRef(value.into()),
+            _ => return Err(Error::TypeError(self.type_name(), "a string type")),
+        }
+        Ok(())
+    }
+
+    fn buffer_to_floats<const N: usize>(&self) -> Result<[f32; N]> {
+        let buf = self.as_buffer_f32()?;
+        buf.try_into().map_err(|_| {
+            Error::InvalidDataD(format!(
+                "Cannot convert BufferF32 of length {} to a vector of length {}",
+                buf.len(),
+                N
+            ))
+        })
+    }
+
+    /// Convert a `BufferF32` of length 2 to a `Vec2` parameter, for fixing up
+    /// mis-typed data. Returns an error if the parameter is not a `BufferF32`
+    /// or has the wrong length.
+    pub fn buffer_to_vec2(&self) -> Result<Parameter> {
+        Ok(self.buffer_to_floats::<2>()?.into())
+    }
+
+    /// Convert a `BufferF32` of length 3 to a `Vec3` parameter. See
+    /// [`Parameter::buffer_to_vec2`].
+    pub fn buffer_to_vec3(&self) -> Result<Parameter> {
+        Ok(self.buffer_to_floats::<3>()?.into())
+    }
+
+    /// Convert a `BufferF32` of length 4 to a `Vec4` parameter. See
+    /// [`Parameter::buffer_to_vec2`].
+    pub fn buffer_to_vec4(&self) -> Result<Parameter> {
+        Ok(self.buffer_to_floats::<4>()?.into())
+    }
+
+    /// Convert a `Vec2`, `Vec3`, or `Vec4` parameter to a `BufferF32` of its
+    /// components. This is the reverse of [`Parameter::buffer_to_vec2`] and
+    /// friends.
+    pub fn vec_to_buffer(&self) -> Result<Parameter> {
+        Ok(Parameter::BufferF32(match self {
+            Parameter::Vec2(v) => vec![v.x, v.y],
+            Parameter::Vec3(v) => vec![v.x, v.y, v.z],
+            Parameter::Vec4(v) => vec![v.x, v.y, v.z, v.t],
+            _ => return Err(Error::TypeError(self.type_name(), "a vector type")),
+        }))
+    }
+
+    /// Convert the parameter to another type where the conversion is
+    /// meaningful, for normalizing loosely typed data against a schema. A
+    /// parameter which already has the target type is returned unchanged.
+    ///
+    /// The supported conversions are:
+    /// - `I32` to `U32` and back, if the value is in range.
+    /// - `I32` or `U32` to `F32`. Integers above 2^24 may lose precision.
+    /// - `F32` to `I32` or `U32`, rounding to the nearest integer (halfway
+    ///   cases away from zero), if the rounded value is in range.
+    /// - Any string type to any other, if the string fits in the target
+    ///   type. `StringRefRaw` must be valid UTF-8.
+    ///
+    /// Any other conversion (e.g. `Bool` to `Vec3`) returns an error.
+    pub fn coerce_to(&self, target: ParameterType) -> Result<Parameter> {
+        if self.get_type() == target && !matches!(self, Parameter::StringRefRaw(_)) {
+            return Ok(self.clone());
+        }
+        let out_of_range = |value: &dyn std::fmt::Display| {
+            Error::InvalidDataD(format!("{value} is out of range for {target:?}"))
+        };
+        match (self, target) {
+            (Parameter::I32(v), ParameterType::U32) => u32::try_from(*v)
+                .map(Parameter::U32)
+                .map_err(|_| out_of_range(v)),
+            (Parameter::U32(v), ParameterType::Int) => i32::try_from(*v)
+                .map(Parameter::I32)
+                .map_err(|_| out_of_range(v)),
+            (Parameter::I32(v), ParameterType::F32) => Ok(Parameter::F32(*v as f32)),
+            (Parameter::U32(v), ParameterType::F32) => Ok(Parameter::F32(*v as f32)),
+            (Parameter::F32(v), ParameterType::Int) => {
+                let rounded = v.round();
+                if (-2147483648.0..2147483648.0).contains(&rounded) {
+                    Ok(Parameter::I32(rounded as i32))
+                } else {
+                    Err(out_of_range(v))
+                }
+            }
+            (Parameter::F32(v), ParameterType::U32) => {
+                let rounded = v.round();
+                if (0.0..4294967296.0).contains(&rounded) {
+                    Ok(Parameter::U32(rounded as u32))
+                } else {
+                    Err(out_of_range(v))
+                }
+            }
+            (
+                _,
+                ParameterType::String32
+                | ParameterType::String64
+                | ParameterType::String256
+                | ParameterType::StringRef,
+            ) if self.is_string() => {
+                let value = std::str::from_utf8(self.as_string_bytes()?)?;
+                Ok(match target {
+                    ParameterType::String32 => {
+                        Parameter::String32(FixedSafeString::try_from_str(value)?)
+                    }
+                    ParameterType::String64 => {
+                        Parameter::String64(Box::new(FixedSafeString::try_from_str(value)?))
+                    }
+                    ParameterType::String256 => {
+                        Parameter::String256(Box::new(FixedSafeString::try_from_str(value)?))
+                    }
+                    _ => Parameter::StringRef(value.into()),
+                })
+            }
+            _ => Err(Error::InvalidDataD(format!(
+                "Cannot convert {} parameter to {target:?}",
+                self.type_name()
+            ))),
+        }
+    }
+
+    /// Render a `BufferBinary` parameter as a hex dump for display, with 16
+    /// bytes per line, each line starting with the offset and ending with the
+    /// printable ASCII characters (others are shown as `.`). Returns an error
+    /// for other parameter types.
+    pub fn hex_dump(&self) -> Result<std::string::String> {
+        use std::fmt::Write;
+
+        let data = self.as_buffer_binary()?;
+        let mut dump = std::string::String::with_capacity(data.len() * 5);
+        for (i, line) in data.chunks(16).enumerate() {
+            if i > 0 {
+                dump.push('\n');
+            }
+            let _ = write!(dump, "{:08x} ", i * 16);
+            for j in 0..16 {
+                match line.get(j) {
+                    Some(byte) => {
+                        let _ = write!(dump, " {byte:02x}");
+                    }
+                    None => dump.push_str("   "),
+                }
+            }
+            dump.push_str("  |");
+            dump.extend(line.iter().map(|&byte| {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                }
+            }));
+            dump.push('|');
+        }
+        Ok(dump)
+    }
+
+    /// Apply a function to every float stored in the parameter. This covers
+    /// `F32`, vectors, quaternions, colors, curves (their float values only),
+    /// and `BufferF32`. Other parameter types are left untouched.
+    pub fn map_floats(&mut self, mut f: impl FnMut(f32) -> f32) {
+        self.map_floats_with(&mut f)
+    }
+
+    fn map_floats_with<F: FnMut(f32) -> f32>(&mut self, f: &mut F) {
+        fn map_all<'a, F: FnMut(f32) -> f32>(
+            values: impl IntoIterator<Item = &'a mut f32>,
+            f: &mut F,
+        ) {
+            values.into_iter().for_each(|v| *v = f(*v));
+        }
+
+        fn map_curves<F: FnMut(f32) -> f32>(curves: &mut [Curve], f: &mut F) {
+            for curve in curves {
+                map_all(&mut curve.floats, f);
+            }
+        }
+
+        match self {
+            Parameter::F32(v) => *v = f(*v),
+            Parameter::Vec2(v) => map_all([&mut v.x, &mut v.y], f),
+            Parameter::Vec3(v) => map_all([&mut v.x, &mut v.y, &mut v.z], f),
+            Parameter::Vec4(v) => map_all([&mut v.x, &mut v.y, &mut v.z, &mut v.t], f),
+            Parameter::Quat(q) => map_all([&mut q.a, &mut q.b, &mut q.c, &mut q.d], f),
+            Parameter::Color(c) => map_all([&mut c.r, &mut c.g, &mut c.b, &mut c.a], f),
+            Parameter::Curve1(c) => map_curves(c.as_mut_slice(), f),
+            Parameter::Curve2(c) => map_curves(c.as_mut_slice(), f),
+            Parameter::Curve3(c) => map_curves(c.as_mut_slice(), f),
+            Parameter::Curve4(c) => map_curves(c.as_mut_slice(), f),
+            Parameter::BufferF32(v) => map_all(v.iter_mut(), f),
+            _ => (),
+        }
+    }
 }
 
 /// Parameter structure name. This is a wrapper around a CRC32 hash.
+///
+/// Names are ordered by their hash rather than by the name string, so a
+/// `BTreeMap<Name, _>` iterates in ascending hash order regardless of which
+/// names are known.
 #[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[binrw::binrw]
 #[brw(little)]
 pub struct Name(u32);
@@ -1335,6 +1953,24 @@ impl Name {
         self.0
     }
 
+    /// Look up the name string in the default name table.
+    ///
+    /// This only finds names which are already known to the table. Names
+    /// which can only be guessed from the index and parent of a structure
+    /// (e.g. `AI_35`) will not resolve this way unless they were previously
+    /// added to the table, for example by converting a parameter archive to
+    /// YAML. For those, use [`NameTable::get_name`].
+    ///
+    /// # Example
+    /// ```
+    /// # use roead::aamp::Name;
+    /// assert_eq!(Name::from_str("Damage").resolve(), Some("Damage"));
+    /// assert_eq!(Name::from(0xDEADBEEF).resolve(), None);
+    /// ```
+    pub fn resolve(&self) -> Option<&'static str> {
+        names::get_default_name_table().get_known_name(self.0)
+    }
+
     /// Const function to construct from a string.
     pub const fn from_str(s: &str) -> Self {
         Name(hash_name(s))
@@ -1356,6 +1992,19 @@ macro_rules! impl_map_wrapper {
                 self.0.is_empty()
             }
 
+            /// Returns `true` if the map contains an entry with the given
+            /// name or hash.
+            #[inline(always)]
+            pub fn contains_key<N: Into<Name>>(&self, key: N) -> bool {
+                self.0.contains_key(&key.into())
+            }
+
+            /// Iterate entry names in order.
+            #[inline(always)]
+            pub fn keys(&self) -> impl Iterator<Item = Name> + '_ {
+                self.0.keys().copied()
+            }
+
             /// Insert a new entry.
             #[inline(always)]
             pub fn insert<N: Into<Name>>(&mut self, key: N, value: $valtype) {
@@ -1364,8 +2013,8 @@ macro_rules! impl_map_wrapper {
 
             /// Insert multiple entries from an iterator.
             #[inline(always)]
-            pub fn extend<I: IntoIterator<Item = (Name, $valtype)>>(&mut self, iter: I) {
-                self.0.extend(iter);
+            pub fn extend<N: Into<Name>, I: IntoIterator<Item = (N, $valtype)>>(&mut self, iter: I) {
+                self.0.extend(iter.into_iter().map(|(k, v)| (k.into(), v)));
             }
 
             /// Get an entry value by name or hash.
@@ -1380,6 +2029,13 @@ macro_rules! impl_map_wrapper {
                 self.0.get_mut(&key.into())
             }
 
+            /// Remove an entry by name or hash, preserving the order of the
+            /// remaining entries, and return its value.
+            #[inline(always)]
+            pub fn remove<N: Into<Name>>(&mut self, key: N) -> Option<$valtype> {
+                self.0.shift_remove(&key.into())
+            }
+
             /// Get a full entry by name or hash.
             #[inline(always)]
             pub fn entry<N: Into<Name>>(&mut self, key: N) -> indexmap::map::Entry<Name, $valtype> {
@@ -1392,6 +2048,18 @@ macro_rules! impl_map_wrapper {
                 self.0.iter()
             }
 
+            /// Iterate entry values in order.
+            #[inline(always)]
+            pub fn values(&self) -> impl Iterator<Item = &$valtype> {
+                self.0.values()
+            }
+
+            /// Iterate entry values mutably in order.
+            #[inline(always)]
+            pub fn values_mut(&mut self) -> impl Iterator<Item = &mut $valtype> {
+                self.0.values_mut()
+            }
+
             /// Iterate entries mutably.
             #[inline(always)]
             pub fn iter_mut(&mut self) -> impl Iterator<Item = (&Name, &mut $valtype)> {
@@ -1434,6 +2102,21 @@ macro_rules! impl_map_wrapper {
             }
         }
 
+        impl<N: Into<Name>> Extend<(N, $valtype)> for $type {
+            fn extend<T: IntoIterator<Item = (N, $valtype)>>(&mut self, iter: T) {
+                self.0.extend(iter.into_iter().map(|(k, v)| (k.into(), v)));
+            }
+        }
+
+        impl<'a> IntoIterator for &'a $type {
+            type IntoIter = indexmap::map::Iter<'a, Name, $valtype>;
+            type Item = (&'a Name, &'a $valtype);
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter()
+            }
+        }
+
         impl<N: Into<Name>> std::ops::Index<N> for $type {
             type Output = $valtype;
 
@@ -1480,6 +2163,16 @@ impl ParameterObject {
         self.0.extend(iter.into_iter().map(|(k, v)| (k.into(), v)));
         self
     }
+
+    /// Apply a function to every float in the object's parameters. See
+    /// [`Parameter::map_floats`].
+    pub fn map_floats(&mut self, mut f: impl FnMut(f32) -> f32) {
+        self.map_floats_with(&mut f)
+    }
+
+    fn map_floats_with<F: FnMut(f32) -> f32>(&mut self, f: &mut F) {
+        self.0.values_mut().for_each(|param| param.map_floats_with(f));
+    }
 }
 
 /// Newtype map of parameter objects.
@@ -1536,10 +2229,8 @@ pub trait ParameterListing {
 #[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct ParameterList {
-    /// Map of child parameter objects.
-    pub objects: ParameterObjectMap,
-    /// Map of child parameter lists.
-    pub lists:   ParameterListMap,
+    objects: ParameterObjectMap,
+    lists:   ParameterListMap,
 }
 
 impl ParameterListing for ParameterList {
@@ -1564,12 +2255,78 @@ impl ParameterListing for ParameterList {
     }
 }
 
+/// Collects child objects into a parameter list without child lists.
+impl<N: Into<Name>> FromIterator<(N, ParameterObject)> for ParameterList {
+    fn from_iter<T: IntoIterator<Item = (N, ParameterObject)>>(iter: T) -> Self {
+        Self {
+            objects: iter.into_iter().collect(),
+            lists:   Default::default(),
+        }
+    }
+}
+
+/// Collects child lists into a parameter list without child objects.
+impl<N: Into<Name>> FromIterator<(N, ParameterList)> for ParameterList {
+    fn from_iter<T: IntoIterator<Item = (N, ParameterList)>>(iter: T) -> Self {
+        Self {
+            objects: Default::default(),
+            lists:   iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<N: Into<Name>> Extend<(N, ParameterObject)> for ParameterList {
+    fn extend<T: IntoIterator<Item = (N, ParameterObject)>>(&mut self, iter: T) {
+        self.objects.extend(iter);
+    }
+}
+
+impl<N: Into<Name>> Extend<(N, ParameterList)> for ParameterList {
+    fn extend<T: IntoIterator<Item = (N, ParameterList)>>(&mut self, iter: T) {
+        self.lists.extend(iter);
+    }
+}
+
 impl ParameterList {
     /// Create a new empty parameter list.
     pub fn new() -> Self {
         Default::default()
     }
 
+    /// Create a parameter list from maps of child objects and lists.
+    pub fn from_parts(objects: ParameterObjectMap, lists: ParameterListMap) -> Self {
+        Self { objects, lists }
+    }
+
+    /// Split the parameter list into its maps of child objects and lists.
+    pub fn into_parts(self) -> (ParameterObjectMap, ParameterListMap) {
+        (self.objects, self.lists)
+    }
+
+    /// Returns the map of child parameter objects.
+    #[inline(always)]
+    pub fn objects(&self) -> &ParameterObjectMap {
+        &self.objects
+    }
+
+    /// Returns the map of child parameter objects mutably.
+    #[inline(always)]
+    pub fn objects_mut(&mut self) -> &mut ParameterObjectMap {
+        &mut self.objects
+    }
+
+    /// Returns the map of child parameter lists.
+    #[inline(always)]
+    pub fn lists(&self) -> &ParameterListMap {
+        &self.lists
+    }
+
+    /// Returns the map of child parameter lists mutably.
+    #[inline(always)]
+    pub fn lists_mut(&mut self) -> &mut ParameterListMap {
+        &mut self.lists
+    }
+
     /// Builder-like method to add a new parameter object.
     pub fn with_object<N: Into<Name>>(mut self, name: N, object: ParameterObject) -> ParameterList {
         self.objects.insert(name.into(), object);
@@ -1601,8 +2358,80 @@ impl ParameterList {
             .extend(iter.into_iter().map(|(k, v)| (k.into(), v)));
         self
     }
+
+    /// Returns the total number of child objects and lists.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.objects.len() + self.lists.len()
+    }
+
+    /// Returns `true` if the list has no child objects or lists.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty() && self.lists.is_empty()
+    }
+
+    /// Iterate the names of all child objects followed by all child lists.
+    /// Use the [`objects`](ParameterList::objects) or
+    /// [`lists`](ParameterList::lists) maps to iterate only one kind.
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = Name> + '_ {
+        self.objects.keys().chain(self.lists.keys())
+    }
+
+    /// Returns `true` if the list has a child object or list with the given
+    /// name or hash.
+    #[inline]
+    pub fn contains_key<N: Into<Name>>(&self, key: N) -> bool {
+        let key = key.into();
+        self.objects.contains_key(key) || self.lists.contains_key(key)
+    }
+
+    /// Recursively apply a function to every float in the list's objects and
+    /// child lists. See [`Parameter::map_floats`].
+    pub fn map_floats(&mut self, mut f: impl FnMut(f32) -> f32) {
+        self.map_floats_with(&mut f)
+    }
+
+    /// Get a deep copy of the list or object at a slash-separated path (e.g.
+    /// `AI/Root`), relative to this list. A list is returned as is, while an
+    /// object is returned inside a new list under its own name. If a list and
+    /// an object share the final name, the list takes precedence. Segments
+    /// consisting only of digits are treated as hashes. An empty path returns
+    /// a copy of this list.
+    pub fn subtree(&self, path: &str) -> Option<ParameterList> {
+        let names = path::parse_path(path);
+        let Some((last, parents)) = names.split_last() else {
+            return Some(self.clone());
+        };
+        let parent = parents
+            .iter()
+            .try_fold(self, |list, name| list.lists.get(*name))?;
+        if let Some(list) = parent.lists.get(*last) {
+            Some(list.clone())
+        } else {
+            parent
+                .objects
+                .get(*last)
+                .map(|obj| ParameterList::new().with_object(*last, obj.clone()))
+        }
+    }
+
+    fn map_floats_with<F: FnMut(f32) -> f32>(&mut self, f: &mut F) {
+        self.objects
+            .0
+            .values_mut()
+            .for_each(|obj| obj.map_floats_with(f));
+        self.lists
+            .0
+            .values_mut()
+            .for_each(|list| list.map_floats_with(f));
+    }
 }
 
+/// Name of the root list of every parameter IO. Binary parameter archives
+/// must use this name, so it is also the parent of top-level lists and objects
+/// when guessing their names (see [`TextOptions::root_name`] to override it).
 const ROOT_KEY: Name = Name::from_str("param_root");
 
 /// [`Parameter`] IO. This is the root parameter list and the only structure
@@ -1697,6 +2526,154 @@ impl ParameterIO {
         self.param_root = list;
         self
     }
+
+    /// Returns `true` if the root list has no child objects or lists. An empty
+    /// parameter IO can still be serialized to text and binary.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.param_root.is_empty()
+    }
+
+    /// Apply a function to every float in the parameter IO, e.g. to rescale
+    /// all values. Integers, strings, and other non-float data are left
+    /// untouched. See [`Parameter::map_floats`] for the affected types.
+    pub fn map_floats(&mut self, f: impl FnMut(f32) -> f32) {
+        self.param_root.map_floats(f)
+    }
+
+    /// Extract the list or object at a slash-separated path into a new
+    /// parameter IO with the same version and data type. See
+    /// [`ParameterList::subtree`].
+    pub fn clone_subtree(&self, path: &str) -> Option<ParameterIO> {
+        Some(ParameterIO {
+            version: self.version,
+            data_type: self.data_type.clone(),
+            param_root: self.param_root.subtree(path)?,
+        })
+    }
+
+    /// Remove every parameter except those at the given slash-separated paths
+    /// (e.g. `AI/Root/Damage`), for producing minimal files which only
+    /// contain selected parameters. A path to an object or list keeps
+    /// everything inside it. Objects and lists left empty are removed, and
+    /// paths which do not exist are ignored. Segments consisting only of
+    /// digits are treated as hashes.
+    pub fn retain_paths<S: AsRef<str>>(&mut self, keep: impl IntoIterator<Item = S>) {
+        type PathSet = rustc_hash::FxHashSet<Vec<Name>>;
+
+        fn retain_list(list: &mut ParameterList, path: &mut Vec<Name>, keep: &PathSet) {
+            list.objects.0.retain(|name, obj| {
+                path.push(*name);
+                let kept = keep.contains(path);
+                if !kept {
+                    obj.0.retain(|param, _| {
+                        path.push(*param);
+                        let kept = keep.contains(path);
+                        path.pop();
+                        kept
+                    });
+                }
+                path.pop();
+                kept || !obj.is_empty()
+            });
+            list.lists.0.retain(|name, child| {
+                path.push(*name);
+                let kept = keep.contains(path);
+                if !kept {
+                    retain_list(child, path, keep);
+                }
+                path.pop();
+                kept || !child.is_empty()
+            });
+        }
+
+        let keep: PathSet = keep
+            .into_iter()
+            .map(|path| path::parse_path(path.as_ref()))
+            .collect();
+        if !keep.contains(&Vec::new()) {
+            retain_list(&mut self.param_root, &mut Vec::new(), &keep);
+        }
+    }
+
+    /// Compute a hash of the parameter IO's contents, for use as a cache key
+    /// or for cheap change detection. Entries are hashed in order of their
+    /// name hashes, so the result does not depend on insertion order.
+    ///
+    /// Floats are hashed by their bit patterns after mapping `-0.0` to `0.0`
+    /// and every NaN to the same NaN, so zeros of either sign fingerprint the
+    /// same way, as they compare equal. [`PartialEq`] also treats floats
+    /// which differ only in their last few bits as equal, which a hash cannot
+    /// follow, so such parameter IOs compare equal but have different
+    /// fingerprints. Identical parameter IOs always have the same
+    /// fingerprint.
+    ///
+    /// The fingerprint is deterministic across runs, but it is not guaranteed
+    /// to be the same on other platforms or in other versions of this library.
+    pub fn fingerprint(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        fn canonical_float(f: f32) -> f32 {
+            if f == 0.0 {
+                0.0
+            } else if f.is_nan() {
+                f32::NAN
+            } else {
+                f
+            }
+        }
+
+        fn hash_object(obj: &ParameterObject, state: &mut impl Hasher) {
+            let mut params: Vec<_> = obj.0.iter().collect();
+            params.sort_unstable_by_key(|(name, _)| name.0);
+            params.len().hash(state);
+            for (name, param) in params {
+                name.0.hash(state);
+                param.get_type().hash(state);
+                match param {
+                    Parameter::F32(_)
+                    | Parameter::Vec2(_)
+                    | Parameter::Vec3(_)
+                    | Parameter::Vec4(_)
+                    | Parameter::Color(_)
+                    | Parameter::Quat(_)
+                    | Parameter::Curve1(_)
+                    | Parameter::Curve2(_)
+                    | Parameter::Curve3(_)
+                    | Parameter::Curve4(_)
+                    | Parameter::BufferF32(_) => {
+                        let mut param = param.clone();
+                        param.map_floats(canonical_float);
+                        param.hash(state);
+                    }
+                    _ => param.hash(state),
+                }
+            }
+        }
+
+        fn hash_list(list: &ParameterList, state: &mut impl Hasher) {
+            let mut objects: Vec<_> = list.objects.0.iter().collect();
+            objects.sort_unstable_by_key(|(name, _)| name.0);
+            objects.len().hash(state);
+            for (name, obj) in objects {
+                name.0.hash(state);
+                hash_object(obj, state);
+            }
+            let mut lists: Vec<_> = list.lists.0.iter().collect();
+            lists.sort_unstable_by_key(|(name, _)| name.0);
+            lists.len().hash(state);
+            for (name, child) in lists {
+                name.0.hash(state);
+                hash_list(child, state);
+            }
+        }
+
+        let mut state = rustc_hash::FxHasher::default();
+        self.version.hash(&mut state);
+        self.data_type.hash(&mut state);
+        hash_list(&self.param_root, &mut state);
+        state.finish()
+    }
 }
 
 /// Convenience macro to construct a [`ParameterObject`] with map literal syntax.
@@ -1736,14 +2713,14 @@ pub use params;
 ///
 /// ```
 /// # use roead::aamp::*;
-/// let plist = ParameterList {
-///     objects: objs!(
+/// let plist = ParameterList::from_parts(
+///     objs!(
 ///         "someobj" => params!(
 ///             "someparam" => Parameter::Bool(true)
 ///         )
 ///     ),
-///     lists: Default::default()
-/// };
+///     Default::default(),
+/// );
 /// ```
 ///
 /// Adapted from https://github.com/bluss/maplit/blob/master/src/lib.rs
@@ -1773,12 +2750,12 @@ pub use objs;
 ///
 /// ```
 /// # use roead::aamp::*;
-/// let plist = ParameterList {
-///     objects: Default::default(),
-///     lists: lists!(
+/// let plist = ParameterList::from_parts(
+///     Default::default(),
+///     lists!(
 ///         "somelist" => ParameterList::new()
-///     )
-/// };
+///     ),
+/// );
 /// ```
 ///
 /// Adapted from https://github.com/bluss/maplit/blob/master/src/lib.rs
@@ -1821,3 +2798,475 @@ fn macros() {
     };
     dbg!(pio);
 }
+
+#[test]
+fn map_floats() {
+    let mut pio = ParameterIO::new()
+        .with_object(
+            "Root",
+            params!(
+                "F32" => Parameter::F32(1.5),
+                "Vec3" => Parameter::Vec3(Vector3f { x: 1.0, y: 2.0, z: 3.0 }),
+                "I32" => Parameter::I32(7)
+            ),
+        )
+        .with_list(
+            "AI",
+            ParameterList::new().with_object(
+                "Root",
+                params!(
+                    "Buffer" => Parameter::BufferF32(vec![0.5, 0.25]),
+                    "Curve" => Parameter::Curve1(Box::new([Curve {
+                        a: 3,
+                        b: 4,
+                        floats: [1.0; 30],
+                    }]))
+                ),
+            ),
+        );
+    pio.map_floats(|v| v * 100.0);
+    let root = pio.object("Root").unwrap();
+    assert_eq!(root["F32"], Parameter::F32(150.0));
+    assert_eq!(
+        root["Vec3"],
+        Parameter::Vec3(Vector3f {
+            x: 100.0,
+            y: 200.0,
+            z: 300.0,
+        })
+    );
+    assert_eq!(root["I32"], Parameter::I32(7));
+    let ai_root = pio.list("AI").unwrap().object("Root").unwrap();
+    assert_eq!(ai_root["Buffer"], Parameter::BufferF32(vec![50.0, 25.0]));
+    let curve = &ai_root["Curve"].as_curve1().unwrap()[0];
+    assert_eq!((curve.a, curve.b), (3, 4));
+    assert_eq!(curve.floats, [100.0; 30]);
+}
+
+#[test]
+fn introspection() {
+    let list = ParameterList::new()
+        .with_object("Root", params!("Damage" => Parameter::I32(1)))
+        .with_list("AI", ParameterList::new());
+    assert_eq!(list.len(), 2);
+    assert!(!list.is_empty());
+    assert!(ParameterList::new().is_empty());
+    assert_eq!(list.keys().collect::<Vec<_>>(), vec![
+        Name::from_str("Root"),
+        Name::from_str("AI")
+    ]);
+    assert!(list.contains_key("Root") && list.contains_key("AI"));
+    assert!(!list.contains_key("Damage"));
+
+    let obj = list.object("Root").unwrap();
+    assert_eq!(obj.len(), 1);
+    assert!(obj.contains_key("Damage"));
+    assert_eq!(obj.keys().next(), Some(Name::from_str("Damage")));
+    assert!(list.objects.contains_key("Root") && !list.lists.contains_key("Root"));
+}
+
+#[test]
+fn from_primitives() {
+    let mut obj = ParameterObject::new();
+    obj.insert("Rate", 1.0.into());
+    obj.insert("Count", 1.into());
+    obj.insert("Hash", 1u32.into());
+    obj.insert("IsEnabled", true.into());
+    obj.insert("Name", "Apple".into());
+    obj.insert("Pos", [1.0, 2.0, 3.0].into());
+    assert_eq!(obj["Rate"], Parameter::F32(1.0));
+    assert_eq!(obj["Count"], Parameter::I32(1));
+    assert_eq!(obj["Hash"], Parameter::U32(1));
+    assert_eq!(obj["IsEnabled"], Parameter::Bool(true));
+    assert_eq!(obj["Name"], Parameter::StringRef("Apple".into()));
+    assert_eq!(obj["Pos"], Parameter::Vec3(Vector3f { x: 1.0, y: 2.0, z: 3.0 }));
+    assert_eq!(
+        Parameter::from([0.0, 1.0, 2.0, 3.0]),
+        Parameter::Vec4(Vector4f { x: 0.0, y: 1.0, z: 2.0, t: 3.0 })
+    );
+}
+
+#[test]
+fn fingerprint() {
+    let pio = ParameterIO::new().with_object(
+        "Root",
+        params!("Damage" => Parameter::I32(1), "Rate" => Parameter::F32(0.5)),
+    );
+    let reordered = ParameterIO::new().with_object(
+        "Root",
+        params!("Rate" => Parameter::F32(0.5), "Damage" => Parameter::I32(1)),
+    );
+    assert_eq!(pio, reordered);
+    assert_eq!(pio.fingerprint(), reordered.fingerprint());
+
+    let changed = ParameterIO::new().with_object(
+        "Root",
+        params!("Damage" => Parameter::U32(1), "Rate" => Parameter::F32(0.5)),
+    );
+    assert_ne!(pio.fingerprint(), changed.fingerprint());
+
+    // Nearly equal floats compare equal, but are fingerprinted bit for bit.
+    let nearly = ParameterIO::new().with_object(
+        "Root",
+        params!(
+            "Damage" => Parameter::I32(1),
+            "Rate" => Parameter::F32(f32::from_bits(0.5f32.to_bits() + 1))
+        ),
+    );
+    assert_eq!(pio, nearly);
+    assert_ne!(pio.fingerprint(), nearly.fingerprint());
+
+    // Zeros of either sign compare equal, so they must fingerprint the same.
+    let zero = |z: f32| {
+        ParameterIO::new().with_object(
+            "Root",
+            params!(
+                "Rate" => Parameter::F32(z),
+                "Pos" => Parameter::Vec3(Vector3f { x: z, y: 1.0, z }),
+                "Values" => Parameter::BufferF32(vec![z, 2.0])
+            ),
+        )
+    };
+    assert_eq!(zero(0.0), zero(-0.0));
+    assert_eq!(zero(0.0).fingerprint(), zero(-0.0).fingerprint());
+    let nan = f32::from_bits(f32::NAN.to_bits() | 1);
+    let payload = ParameterIO::new().with_object("Root", params!("Rate" => Parameter::F32(nan)));
+    let plain = ParameterIO::new().with_object("Root", params!("Rate" => Parameter::F32(f32::NAN)));
+    assert_eq!(payload.fingerprint(), plain.fingerprint());
+}
+
+#[test]
+fn subtree() {
+    let pio = ParameterIO::new().with_list(
+        "AI",
+        ParameterList::new()
+            .with_object("Root", params!("Damage" => Parameter::I32(1)))
+            .with_list(
+                "Children",
+                ParameterList::new().with_object("Values", params!("Count" => Parameter::I32(2))),
+            ),
+    );
+    let children = pio.clone_subtree("AI/Children").unwrap();
+    assert_eq!(children.data_type, pio.data_type);
+    assert_eq!(children.object("Values").unwrap()["Count"], Parameter::I32(2));
+    let root = pio.param_root.subtree("AI/Root").unwrap();
+    assert_eq!(root.object("Root"), pio.list("AI").unwrap().object("Root"));
+    assert_eq!(pio.param_root.subtree(""), Some(pio.param_root.clone()));
+    assert!(pio.param_root.subtree("AI/Missing").is_none());
+    assert!(pio.param_root.subtree("AI/Root/Damage").is_none());
+}
+
+#[test]
+fn categories() {
+    let params = [
+        Parameter::BufferU32(vec![1]),
+        Parameter::StringRefRaw(vec![0x82, 0xA0]),
+        Parameter::String32("a".into()),
+        Parameter::U32(1),
+        Parameter::Vec3(Vector3f { x: 0.0, y: 0.0, z: 0.0 }),
+        Parameter::Curve2(Box::new([Curve::default(); 2])),
+        Parameter::Quat(Quat { a: 0.0, b: 0.0, c: 0.0, d: 1.0 }),
+    ];
+    let count = |f: fn(&Parameter) -> bool| params.iter().filter(|p| f(p)).count();
+    assert_eq!(count(Parameter::is_buffer), 1);
+    assert_eq!(count(Parameter::is_string), 2);
+    assert_eq!(count(Parameter::is_numeric), 1);
+    assert_eq!(count(Parameter::is_vector), 1);
+    assert_eq!(count(Parameter::is_curve), 1);
+}
+
+#[test]
+fn buffer_views() {
+    let mut param = Parameter::BufferF32(vec![0.5, 1.0]);
+    assert_eq!(param.as_buffer_f32().unwrap(), &[0.5, 1.0]);
+    assert!(param.as_buffer_int().is_err());
+    param.as_mut_buffer_f32().unwrap().push(2.0);
+    assert_eq!(param.as_buffer_f32().unwrap().len(), 3);
+
+    let mut param = Parameter::BufferU32(vec![1, 2]);
+    param.as_mut_buffer_u32().unwrap()[0] = 0;
+    param.as_mut_buffer_u32_vec().unwrap().push(3);
+    assert_eq!(param.as_buffer_u32().unwrap(), &[0, 2, 3]);
+
+    let mut param = Parameter::BufferBinary(vec![0xFF]);
+    param
+        .as_mut_buffer_binary_vec()
+        .unwrap()
+        .extend_from_slice(b"AB");
+    assert_eq!(param.as_buffer_binary().unwrap(), b"\xFFAB");
+    assert!(param.as_buffer_u32().is_err());
+}
+
+#[test]
+fn setters() {
+    let mut param = Parameter::F32(1.0);
+    param.set_f32(2.0).unwrap();
+    assert_eq!(param, Parameter::F32(2.0));
+    assert!(param.set_i32(2).is_err());
+    assert_eq!(param, Parameter::F32(2.0));
+
+    let mut param = Parameter::String32("Old".into());
+    param.set_str("New").unwrap();
+    assert_eq!(param, Parameter::String32("New".into()));
+    assert!(param.set_str(&"a".repeat(32)).is_err());
+    assert!(param.set_f32(1.0).is_err());
+    assert_eq!(param.get_type(), ParameterType::String32);
+}
+
+#[test]
+fn buffer_vectors() {
+    let buf = Parameter::BufferF32(vec![1.0, 2.0, 3.0]);
+    let vec = buf.buffer_to_vec3().unwrap();
+    assert_eq!(vec, Parameter::Vec3(Vector3f { x: 1.0, y: 2.0, z: 3.0 }));
+    assert_eq!(vec.vec_to_buffer().unwrap(), buf);
+    let err = buf.buffer_to_vec4().unwrap_err().to_string();
+    assert!(err.contains("length 3") && err.contains("length 4"));
+    assert!(Parameter::F32(1.0).buffer_to_vec2().is_err());
+    assert!(Parameter::F32(1.0).vec_to_buffer().is_err());
+}
+
+#[test]
+fn list_accessors() {
+    let mut list = ParameterList::new()
+        .with_object("A", params!("Value" => Parameter::I32(1)))
+        .with_object("B", ParameterObject::new())
+        .with_list("C", ParameterList::new());
+    assert_eq!(list.objects().len(), 2);
+    assert_eq!(list.lists().keys().collect::<Vec<_>>(), [Name::from_str("C")]);
+    assert!(list.objects_mut().remove("A").is_some());
+    assert_eq!(list.objects().keys().next(), Some(Name::from_str("B")));
+    for (_, list) in list.lists() {
+        assert!(list.objects().is_empty());
+    }
+    let (objects, lists) = list.clone().into_parts();
+    assert_eq!(ParameterList::from_parts(objects, lists), list);
+}
+
+#[test]
+fn coerce() {
+    assert_eq!(
+        Parameter::I32(5).coerce_to(ParameterType::U32).unwrap(),
+        Parameter::U32(5)
+    );
+    assert!(Parameter::I32(-1).coerce_to(ParameterType::U32).is_err());
+    assert!(
+        Parameter::U32(u32::MAX)
+            .coerce_to(ParameterType::Int)
+            .is_err()
+    );
+    assert_eq!(
+        Parameter::U32(7).coerce_to(ParameterType::F32).unwrap(),
+        Parameter::F32(7.0)
+    );
+    assert_eq!(
+        Parameter::F32(-2.5).coerce_to(ParameterType::Int).unwrap(),
+        Parameter::I32(-3)
+    );
+    assert_eq!(
+        Parameter::F32(1.4).coerce_to(ParameterType::U32).unwrap(),
+        Parameter::U32(1)
+    );
+    assert!(
+        Parameter::F32(f32::NAN)
+            .coerce_to(ParameterType::Int)
+            .is_err()
+    );
+    assert!(Parameter::F32(-1.0).coerce_to(ParameterType::U32).is_err());
+
+    let short = Parameter::StringRef("Enemy".into());
+    assert_eq!(
+        short.coerce_to(ParameterType::String32).unwrap(),
+        Parameter::String32("Enemy".into())
+    );
+    let long = Parameter::StringRef("A".repeat(40).into());
+    assert!(long.coerce_to(ParameterType::String32).is_err());
+    assert!(long.coerce_to(ParameterType::String64).is_ok());
+    assert_eq!(
+        Parameter::StringRefRaw(b"Raw".to_vec())
+            .coerce_to(ParameterType::StringRef)
+            .unwrap(),
+        Parameter::StringRef("Raw".into())
+    );
+    assert_eq!(short.coerce_to(ParameterType::StringRef).unwrap(), short);
+    assert!(Parameter::Bool(true).coerce_to(ParameterType::Vec3).is_err());
+}
+
+#[test]
+fn retain_paths() {
+    let mut pio = ParameterIO::new()
+        .with_object(
+            "Flags",
+            params!("IsEnabled" => Parameter::Bool(true), "IsHidden" => Parameter::Bool(false)),
+        )
+        .with_list(
+            "AI",
+            ParameterList::new()
+                .with_object(
+                    "Root",
+                    params!("Damage" => Parameter::I32(10), "Range" => Parameter::F32(2.0)),
+                )
+                .with_object("Empty", ParameterObject::new())
+                .with_list(
+                    "Children",
+                    ParameterList::new()
+                        .with_object("Child", params!("Speed" => Parameter::F32(1.5))),
+                ),
+        )
+        .with_list(
+            "Extra",
+            ParameterList::new().with_object("Values", params!("Count" => Parameter::I32(2))),
+        );
+    let keep: std::collections::HashSet<std::string::String> =
+        ["AI/Root/Damage", "AI/Empty", "Extra", "Missing/Object/Param"]
+            .into_iter()
+            .map(Into::into)
+            .collect();
+    let extra = pio.list("Extra").unwrap().clone();
+    pio.retain_paths(&keep);
+    let expected = ParameterIO::new()
+        .with_list(
+            "AI",
+            ParameterList::new()
+                .with_object("Root", params!("Damage" => Parameter::I32(10)))
+                .with_object("Empty", ParameterObject::new()),
+        )
+        .with_list("Extra", extra);
+    assert_eq!(pio, expected);
+
+    pio.retain_paths(["Flags/IsEnabled"]);
+    assert!(pio.param_root.is_empty());
+}
+
+#[test]
+fn hex_dump() {
+    let mut data = b"Hello, world!\x00\xff\x10".to_vec();
+    data.extend(b"More");
+    let dump = Parameter::BufferBinary(data).hex_dump().unwrap();
+    assert_eq!(
+        dump,
+        "00000000  48 65 6c 6c 6f 2c 20 77 6f 72 6c 64 21 00 ff 10  |Hello, world!...|\n\
+         00000010  4d 6f 72 65                                      |More|"
+    );
+    assert_eq!(Parameter::BufferBinary(vec![]).hex_dump().unwrap(), "");
+    assert!(Parameter::BufferInt(vec![1]).hex_dump().is_err());
+}
+
+#[test]
+fn debug_format() {
+    assert_eq!(format!("{:?}", Parameter::U32(0x12345678)), "U32(0x12345678)");
+    assert_eq!(
+        format!("{:?}", Parameter::from([1.0, 2.0, 3.5])),
+        "Vec3(1.0, 2.0, 3.5)"
+    );
+    assert_eq!(
+        format!("{:?}", Parameter::BufferU32(vec![1, 255])),
+        "BufferU32([0x1, 0xff])"
+    );
+    let buffer = format!("{:?}", Parameter::BufferInt((0..100).collect()));
+    assert!(buffer.starts_with("BufferInt([0, 1, 2,"));
+    assert!(buffer.ends_with("14, 15, ... (100 items)])"));
+    let pretty = format!("{:#?}", Parameter::BufferBinary(vec![0; 20]));
+    assert!(pretty.contains("    0x0,\n"));
+    assert!(pretty.contains("    ... (20 items),\n"));
+}
+
+#[test]
+fn curve_type() {
+    let mut curve = Curve {
+        a: 2,
+        b: 1,
+        ..Default::default()
+    };
+    assert_eq!(curve.curve_type(), CurveType::Hermit);
+    curve.set_curve_type(CurveType::Step2D);
+    assert_eq!(curve.b, 8);
+    let curve = curve.with_curve_type(CurveType::Unknown(100));
+    assert_eq!(curve.b, 100);
+    assert_eq!(curve.curve_type(), CurveType::Unknown(100));
+}
+
+#[test]
+fn collect_structures() {
+    let obj: ParameterObject = [("Damage", Parameter::I32(10)), ("Speed", Parameter::F32(1.5))]
+        .into_iter()
+        .collect();
+    assert_eq!(obj.len(), 2);
+    let mut list: ParameterList = [("Root", obj.clone())].into_iter().collect();
+    list.extend([("Child", ParameterList::new())]);
+    list.extend([(Name::from_str("Other"), obj.clone())]);
+    assert_eq!(
+        list,
+        ParameterList::new()
+            .with_object("Root", obj.clone())
+            .with_object("Other", obj.clone())
+            .with_list("Child", ParameterList::new())
+    );
+    let mut obj2 = ParameterObject::new();
+    Extend::extend(&mut obj2, [("Damage", Parameter::I32(10))]);
+    obj2.extend([("Speed", Parameter::F32(1.5))]);
+    assert_eq!(obj2, obj);
+    let lists: ParameterList = [("A", ParameterList::new())].into_iter().collect();
+    assert_eq!(lists.lists().len(), 1);
+}
+
+#[test]
+fn fixed_string_capacity() {
+    assert_eq!(ParameterType::String32.fixed_string_capacity(), Some(32));
+    assert_eq!(ParameterType::String256.fixed_string_capacity(), Some(256));
+    assert_eq!(ParameterType::StringRef.fixed_string_capacity(), None);
+    assert_eq!(ParameterType::Int.fixed_string_capacity(), None);
+    let capacity = ParameterType::String64.fixed_string_capacity().unwrap();
+    assert!(FixedSafeString::<64>::try_from_str(&"a".repeat(capacity)).is_err());
+    assert!(FixedSafeString::<64>::try_from_str(&"a".repeat(capacity - 1)).is_ok());
+}
+
+#[test]
+fn color_components() {
+    let color = Color::rgb(0.25, 0.5, 0.75);
+    assert_eq!(color.to_rgba(), (0.25, 0.5, 0.75, 1.0));
+    assert_eq!(Color::rgba(0.25, 0.5, 0.75, 0.0).to_rgb(), color.to_rgb());
+    assert_eq!(Parameter::from(color).as_color().unwrap().a, 1.0);
+}
+
+#[test]
+fn fixed_string() {
+    assert_eq!(
+        Parameter::fixed_string("Link", 64).unwrap(),
+        Parameter::String64(Box::new("Link".into()))
+    );
+    assert!(Parameter::fixed_string(&"a".repeat(255), 256).is_ok());
+    assert!(Parameter::fixed_string(&"a".repeat(256), 256).is_err());
+    // Each character is 3 bytes, so 11 of them need 34 bytes with the null.
+    let err = Parameter::fixed_string(&"ア".repeat(11), 32).unwrap_err();
+    assert!(err.to_string().contains("33 bytes"));
+    assert!(Parameter::fixed_string(&"ア".repeat(10), 32).is_ok());
+    assert!(Parameter::fixed_string("Link", 48).is_err());
+}
+
+#[test]
+fn name_order() {
+    let names: std::collections::BTreeMap<Name, i32> = [("Zebra", 0), ("Apple", 1), ("Mango", 2)]
+        .into_iter()
+        .map(|(name, value)| (Name::from(name), value))
+        .collect();
+    let hashes: Vec<u32> = names.keys().map(Name::hash).collect();
+    let mut sorted = hashes.clone();
+    sorted.sort_unstable();
+    assert_eq!(hashes, sorted);
+    assert!(Name::from(1u32) < Name::from(2u32));
+    assert_eq!(Name::from("Apple").cmp(&Name::from("Apple")), std::cmp::Ordering::Equal);
+}
+
+#[test]
+fn empty() {
+    let pio = ParameterIO::new().with_version(3).with_data_type("bxml");
+    assert!(pio.is_empty());
+    let with_object = ParameterIO::new().with_object("Root", ParameterObject::new());
+    assert!(!with_object.is_empty());
+
+    let data = pio.to_binary();
+    let new_pio = ParameterIO::from_binary(&data).unwrap();
+    assert!(new_pio.is_empty());
+    assert_eq!(new_pio, pio);
+    assert_eq!(new_pio.to_binary(), data);
+}
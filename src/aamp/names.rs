@@ -1,9 +1,4 @@
-use std::{
-    borrow::Cow,
-    collections::hash_map::{Entry, VacantEntry},
-    fmt::Write,
-    sync::Arc,
-};
+use std::{borrow::Cow, fmt::Write, sync::Arc};
 
 use once_cell::sync::Lazy;
 use parking_lot::RwLock;
@@ -98,6 +93,39 @@ macro_rules! free_cow {
     }};
 }
 
+/// Number of independently locked shards in each map of a [`NameTable`].
+const SHARDS: usize = 16;
+
+type NameMap<'a> = FxHashMap<u32, Cow<'a, str>>;
+
+/// Map of names split into shards by hash, each with its own lock, so that
+/// threads converting different documents at the same time rarely wait for
+/// each other, even while recording guessed names.
+#[derive(Debug, Default)]
+struct ShardedNames<'a> {
+    shards: [RwLock<NameMap<'a>>; SHARDS],
+}
+
+impl<'a> ShardedNames<'a> {
+    #[inline(always)]
+    fn shard(&self, hash: u32) -> &RwLock<NameMap<'a>> {
+        &self.shards[hash as usize % SHARDS]
+    }
+
+    /// Add many names, taking each shard's lock only once.
+    fn extend(&self, names: impl Iterator<Item = Cow<'a, str>>) {
+        let mut shards: Vec<_> = self.shards.iter().map(|shard| shard.write()).collect();
+        let additional = names.size_hint().0 / SHARDS;
+        for shard in shards.iter_mut() {
+            shard.reserve(additional);
+        }
+        for name in names {
+            let hash = hash_name(&name);
+            shards[hash as usize % SHARDS].entry(hash).or_insert(name);
+        }
+    }
+}
+
 /// A table of names that is used to recover original names in binary parameter
 /// archives which store only name hashes.
 ///
@@ -107,9 +135,14 @@ macro_rules! free_cow {
 ///
 /// When serializing to YAML, by default roead will use a table that contains
 /// strings from Breath of the Wild’s executable.
+///
+/// The table can be shared between threads. Its names are split into shards
+/// with separate locks, so converting many documents in parallel with the
+/// same table scales across cores instead of contending for a single lock.
 #[derive(Debug, Default)]
 pub struct NameTable<'a> {
-    names: RwLock<FxHashMap<u32, Cow<'a, str>>>,
+    names: ShardedNames<'a>,
+    guessed_names: ShardedNames<'a>,
     numbered_names: Vec<&'a str>,
 }
 
@@ -117,8 +150,11 @@ impl<'a> NameTable<'a> {
     /// Create a new name table, optionally including default BOTW strings.
     pub fn new(botw_strings: bool) -> NameTable<'a> {
         if botw_strings {
+            let names = ShardedNames::default();
+            names.extend(NAMES.lines().map(Cow::Borrowed));
             Self {
-                names: RwLock::new(NAMES.lines().map(|n| (hash_name(n), n.into())).collect()),
+                names,
+                guessed_names: Default::default(),
                 numbered_names: NUMBERED_NAMES.lines().collect(),
             }
         } else {
@@ -126,105 +162,233 @@ impl<'a> NameTable<'a> {
         }
     }
 
+    /// Create a new name table, optionally including default BOTW strings,
+    /// with room for at least `capacity` more names before reallocating.
+    pub fn with_capacity(botw_strings: bool, capacity: usize) -> NameTable<'a> {
+        let table = Self::new(botw_strings);
+        table.reserve(capacity);
+        table
+    }
+
+    /// Reserve room for at least `additional` more names.
+    pub fn reserve(&self, additional: usize) {
+        for shard in &self.names.shards {
+            shard.write().reserve(additional / SHARDS + 1);
+        }
+    }
+
     /// Add a known string to the name table.
     pub fn add_name(&self, name: impl Into<Cow<'a, str>>) {
         let name = name.into();
         let hash = hash_name(&name);
-        self.names.write().entry(hash).or_insert(name);
+        self.names.shard(hash).write().entry(hash).or_insert(name);
     }
 
     /// Add a known string to the name table if you already know the hash (to
     /// avoid computing it).
     pub fn add_name_with_hash(&self, name: impl Into<Cow<'a, str>>, hash: u32) {
         self.names
+            .shard(hash)
             .write()
             .entry(hash)
             .or_insert_with(|| name.into());
     }
 
+    /// Add many known strings to the name table at once. This only takes the
+    /// table's locks once, which is much faster than calling
+    /// [`NameTable::add_name`] for each name.
+    pub fn add_names<I, N>(&self, names: I)
+    where
+        I: IntoIterator<Item = N>,
+        N: Into<Cow<'a, str>>,
+    {
+        self.names.extend(names.into_iter().map(Into::into));
+    }
+
     /// Add a known string to the name table.
     pub fn add_name_str<'s: 'a>(&'a self, name: &'s str) {
         let hash = hash_name(name);
         self.names
+            .shard(hash)
             .write()
             .entry(hash)
             .or_insert_with(|| name.into());
     }
 
-    /// Tries to guess the name that is associated with the given hash and index
-    /// (of the parameter / object / list in its parent).
+    /// Returns the name associated with the given hash if it is already in the
+    /// table, either because it was added or because it was previously
+    /// guessed by [`NameTable::get_name`]. Unlike `get_name`, this never
+    /// guesses names based on the index or parent of a structure, so it
+    /// never modifies the table.
+    pub fn get_known_name(&self, hash: u32) -> Option<&str> {
+        self.get_added_name(hash).or_else(|| {
+            self.guessed_names
+                .shard(hash)
+                .read()
+                .get(&hash)
+                .map(|name| {
+                    let name: *const str = name.as_ref();
+                    // SAFETY: See `get_added_name`.
+                    unsafe { &*name }
+                })
+        })
+    }
+
+    /// Returns the name associated with the given hash only if it was added to
+    /// the table, ignoring previously guessed names.
+    pub(crate) fn get_added_name(&self, hash: u32) -> Option<&str> {
+        self.names.shard(hash).read().get(&hash).map(|name| {
+            let name: *const str = name.as_ref();
+            // SAFETY: Names are never removed from the table, and the string
+            // data is either borrowed for `'a` or owned on the heap, so it
+            // does not move when the map is resized.
+            unsafe { &*name }
+        })
+    }
+
+    /// Tries to guess the name that is associated with the given hash and
+    /// index (of the parameter / object / list in its parent), given the name
+    /// of the parent structure if it is known.
     ///
-    /// The table is automatically updated with any newly found names if an
-    /// indice-based guess was necessary.
-    pub fn get_name(&self, hash: u32, index: usize, parent_hash: u32) -> Option<&Cow<'_, str>> {
-        fn test_names<'a: 'b, 'b, 'c>(
-            entry: VacantEntry<'b, u32, Cow<'a, str>>,
-            hash: u32,
-            index: usize,
-            prefix: &str,
-            buf: &'c mut std::string::String,
-        ) -> std::result::Result<&'b Cow<'a, str>, VacantEntry<'b, u32, Cow<'a, str>>> {
-            for i in index..(index + 1) {
-                for guess_hash in ChildFormatIterator::new(prefix, i, buf) {
-                    if guess_hash == hash {
-                        let name = entry.insert(buf.to_string().into());
-                        return Ok(free_cow!(name, 'a));
-                    }
-                }
-            }
-            Err(entry)
+    /// Unlike [`NameTable::get_name`], this neither uses nor records
+    /// previously guessed names, so the result depends only on the arguments
+    /// and the names which were added to the table.
+    pub fn guess_name(
+        &self,
+        hash: u32,
+        index: usize,
+        parent_name: Option<&str>,
+    ) -> Option<std::string::String> {
+        let mut buf = std::string::String::with_capacity(256);
+        self.guess_into(hash, index, parent_name, &mut buf)
+            .map(|_| buf)
+    }
+
+    fn guess_into(
+        &self,
+        hash: u32,
+        index: usize,
+        parent_name: Option<&str>,
+        buf: &mut std::string::String,
+    ) -> Option<NameSource> {
+        // On success, the buffer is left holding the matching name.
+        fn test_names(hash: u32, index: usize, prefix: &str, buf: &mut std::string::String) -> bool {
+            ChildFormatIterator::new(prefix, index, buf).any(|guess_hash| guess_hash == hash)
         }
 
-        let mut names = self.names.write();
-        let parent_name = names.get(&parent_hash).map(|c| free_cow!(c, 'a));
-        match names.entry(hash) {
-            Entry::Occupied(entry) => Some(free_cow!(entry.get(), 'a)),
-            Entry::Vacant(entry) => {
-                let mut entry = entry;
-                let mut guess_buffer = std::string::String::with_capacity(256);
-                if let Some(parent_name) = parent_name
-                // Try to guess the name from the parent structure if possible.
-                {
-                    let guess = test_names(entry, hash, index, parent_name, &mut guess_buffer)
-                        .or_else(|entry| {
-                            test_names(entry, hash, index, "Children", &mut guess_buffer)
-                        })
-                        .or_else(|entry| test_names(entry, hash, index, "Child", &mut guess_buffer))
-                        .or_else(|mut entry| {
-                            // Sometimes the parent name is plural and the object names are
-                            // singular.
-                            for suffix in ["s", "es", "List"] {
-                                if let Some(singular) = parent_name.strip_suffix(suffix) {
-                                    let guess =
-                                        test_names(entry, hash, index, singular, &mut guess_buffer);
-                                    match guess {
-                                        Ok(found) => return Ok(found),
-                                        Err(ret_entry) => entry = ret_entry,
-                                    }
-                                }
-                            }
-                            Err(entry)
-                        });
-                    match guess {
-                        Ok(found) => return Some(free_cow!(found, 'a)),
-                        Err(ret_entry) => {
-                            entry = ret_entry;
-                        }
+        // Try to guess the name from the parent structure if possible.
+        if let Some(parent_name) = parent_name {
+            if test_names(hash, index, parent_name, buf)
+                || test_names(hash, index, "Children", buf)
+                || test_names(hash, index, "Child", buf)
+            {
+                return Some(NameSource::Parent);
+            }
+            // Sometimes the parent name is plural and the object names are
+            // singular.
+            for suffix in ["s", "es", "List"] {
+                if let Some(singular) = parent_name.strip_suffix(suffix) {
+                    if test_names(hash, index, singular, buf) {
+                        return Some(NameSource::SingularParent);
                     }
                 }
-                // Last resort: test all numbered names.
-                for format in &self.numbered_names {
-                    for i in 0..(index + 2) {
-                        format_numbered_name(format, i, &mut guess_buffer);
-                        if hash_name(&guess_buffer) == hash {
-                            let name = entry.insert(guess_buffer.to_string().into());
-                            return Some(free_cow!(name, 'a));
-                        }
-                    }
+            }
+        }
+        // Last resort: test all numbered names.
+        for format in &self.numbered_names {
+            for i in 0..(index + 2) {
+                format_numbered_name(format, i, buf);
+                if hash_name(buf) == hash {
+                    return Some(NameSource::Numbered);
                 }
-                None
             }
         }
+        None
+    }
+
+    /// Tries to guess the name that is associated with the given hash and index
+    /// (of the parameter / object / list in its parent).
+    ///
+    /// The table is automatically updated with any newly found names if an
+    /// indice-based guess was necessary. Names which are already known are
+    /// looked up with a shared read lock, and recording a guess only locks
+    /// the shard of the table which holds its hash.
+    pub fn get_name(&self, hash: u32, index: usize, parent_hash: u32) -> Option<&Cow<'_, str>> {
+        self.get_name_with_source(hash, index, parent_hash).0
+    }
+
+    /// Resolves a name like [`NameTable::get_name`], also returning how it
+    /// was resolved. This is useful for finding names which are only ever
+    /// guessed and could be added to a dictionary.
+    pub fn get_name_with_source(
+        &self,
+        hash: u32,
+        index: usize,
+        parent_hash: u32,
+    ) -> (Option<&Cow<'_, str>>, NameSource) {
+        // Most lookups are for names which are already known, so check with a
+        // shared lock first to avoid serializing concurrent readers.
+        if let Some(name) = self.names.shard(hash).read().get(&hash) {
+            return (Some(free_cow!(name, 'a)), NameSource::Known);
+        }
+        if let Some(name) = self.guessed_names.shard(hash).read().get(&hash) {
+            return (Some(free_cow!(name, 'a)), NameSource::PreviouslyGuessed);
+        }
+
+        let mut guess_buffer = std::string::String::with_capacity(256);
+        let parent_name = self.get_known_name(parent_hash);
+        let Some(source) = self.guess_into(hash, index, parent_name, &mut guess_buffer) else {
+            return (None, NameSource::Unresolved);
+        };
+        let mut guessed_names = self.guessed_names.shard(hash).write();
+        let name = guessed_names
+            .entry(hash)
+            .or_insert_with(|| guess_buffer.into());
+        (Some(free_cow!(name, 'a)), source)
+    }
+}
+
+/// How a name was resolved by [`NameTable::get_name_with_source`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NameSource {
+    /// The name was added to the table.
+    Known,
+    /// The name was guessed by an earlier lookup and recorded in the table.
+    PreviouslyGuessed,
+    /// The name was guessed from the name of the parent structure (or the
+    /// generic `Children` or `Child`) and the index.
+    Parent,
+    /// The name was guessed from the name of the parent structure without a
+    /// plural suffix (`s`, `es`, or `List`) and the index.
+    SingularParent,
+    /// The name was found by trying every numbered name format.
+    Numbered,
+    /// The name could not be resolved.
+    Unresolved,
+}
+
+/// Memoizes resolved names for a single conversion, keyed by the hash, the
+/// index in the parent structure, and the parent. Documents often repeat the
+/// same structures many times, and names which cannot be resolved are not
+/// recorded in the name table, so without this every occurrence of an
+/// unknown name would run the full guessing loop again.
+#[cfg(feature = "yaml")]
+#[derive(Debug, Default)]
+pub(crate) struct NameCache<'a> {
+    names: FxHashMap<(u32, usize, u32, bool), Option<Cow<'a, str>>>,
+}
+
+#[cfg(feature = "yaml")]
+impl<'a> NameCache<'a> {
+    /// Get the cached name for a key, or resolve and cache it. The flag is
+    /// for anything else the resolution depends on, such as whether the
+    /// parent name is known.
+    pub(crate) fn get_or_resolve(
+        &mut self,
+        key: (u32, usize, u32, bool),
+        resolve: impl FnOnce() -> Option<Cow<'a, str>>,
+    ) -> Option<Cow<'a, str>> {
+        self.names.entry(key).or_insert_with(resolve).clone()
     }
 }
 
@@ -237,3 +401,94 @@ static DEFAULT_NAME_TABLE: Lazy<Arc<NameTable<'static>>> =
 pub fn get_default_name_table() -> &'static Lazy<Arc<NameTable<'static>>> {
     &DEFAULT_NAME_TABLE
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn name_cache() {
+        let mut cache = NameCache::default();
+        let mut calls = 0;
+        for _ in 0..3 {
+            let name = cache.get_or_resolve((1, 2, 3, false), || {
+                calls += 1;
+                None
+            });
+            assert_eq!(name, None);
+        }
+        let name = cache.get_or_resolve((1, 2, 3, true), || Some("Found".into()));
+        assert_eq!(name.as_deref(), Some("Found"));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn concurrent_guesses() {
+        let table = NameTable::new(false);
+        table.add_names(["Parent"]);
+        let parent = hash_name("Parent");
+        std::thread::scope(|scope| {
+            for thread in 0..8 {
+                let table = &table;
+                scope.spawn(move || {
+                    for i in 0..64 {
+                        let name = format!("Parent_{i}");
+                        let hash = hash_name(&name);
+                        let guessed = table.get_name(hash, i, parent).map(|n| n.to_string());
+                        assert_eq!(guessed.as_deref(), Some(name.as_str()), "thread {thread}");
+                    }
+                });
+            }
+        });
+        assert_eq!(table.get_known_name(hash_name("Parent_63")), Some("Parent_63"));
+    }
+
+    #[test]
+    fn known_names_read_lock() {
+        let table = Arc::new(NameTable::new(false));
+        table.add_names(["Parent"]);
+        let (parent, child) = (hash_name("Parent"), hash_name("Parent_2"));
+        assert!(table.get_name(child, 2, parent).is_some());
+        // Shared locks on both maps block any writer, so the lookups below can
+        // only finish if they never take a write lock.
+        let _names = table.names.shard(parent).read();
+        let _guessed = table.guessed_names.shard(child).read();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let thread_table = table.clone();
+        std::thread::spawn(move || {
+            let known = thread_table.get_name(parent, 0, 0).map(|n| n.to_string());
+            let guessed = thread_table.get_name(child, 2, parent).map(|n| n.to_string());
+            sender.send((known, guessed)).unwrap();
+        });
+        let (known, guessed) = receiver
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .expect("Looking up a known name waited for a write lock");
+        assert_eq!(known.as_deref(), Some("Parent"));
+        assert_eq!(guessed.as_deref(), Some("Parent_2"));
+    }
+
+    #[test]
+    fn add_names() {
+        let table = NameTable::with_capacity(false, 100);
+        table.add_names((0..100).map(|i| format!("Name{i}")));
+        table.add_names(["Apple", "Banana"]);
+        assert_eq!(table.get_known_name(hash_name("Name42")), Some("Name42"));
+        assert_eq!(table.get_known_name(hash_name("Banana")), Some("Banana"));
+        assert_eq!(table.get_known_name(hash_name("Cherry")), None);
+    }
+
+    #[test]
+    fn name_source() {
+        let table = NameTable::new(false);
+        table.add_names(["Parent", "Items"]);
+        let (parent, items) = (hash_name("Parent"), hash_name("Items"));
+        let source =
+            |hash, index, parent_hash| table.get_name_with_source(hash, index, parent_hash).1;
+        assert_eq!(source(parent, 0, 0), NameSource::Known);
+        assert_eq!(source(hash_name("Parent_3"), 3, parent), NameSource::Parent);
+        assert_eq!(source(hash_name("Parent_3"), 3, parent), NameSource::PreviouslyGuessed);
+        assert_eq!(source(hash_name("Item_1"), 1, items), NameSource::SingularParent);
+        assert_eq!(source(hash_name("Unknown"), 0, parent), NameSource::Unresolved);
+    }
+}
@@ -1,4 +1,7 @@
-use std::io::{Read, Seek};
+use std::{
+    io::{Read, Seek},
+    ops::ControlFlow,
+};
 
 use binrw::prelude::*;
 
@@ -27,20 +30,248 @@ impl ParameterIO {
         }
         Parser::new(std::io::Cursor::new(data.as_ref()))?.parse()
     }
+
+    /// Load a parameter archive from a file by memory mapping it, rather than
+    /// reading the whole file into memory first. This is mainly useful for
+    /// scanning large numbers of files. The returned parameter IO is owned and
+    /// does not borrow from the mapping.
+    ///
+    /// **Note**: The file must not be modified (by this or any other process)
+    /// while it is being parsed. Doing so can cause anything from garbage
+    /// parameters to a crash, as the mapped bytes change underneath the
+    /// parser. Yaz0 compressed files are decompressed as with
+    /// [`ParameterIO::from_binary`], which requires a copy.
+    ///
+    /// This function is only available with the `mmap` feature.
+    #[cfg(feature = "mmap")]
+    pub fn from_mmap(path: impl AsRef<std::path::Path>) -> Result<ParameterIO> {
+        let file = std::fs::File::open(path)?;
+        // SAFETY: The mapping is only read for the duration of this function,
+        // and callers are told not to modify the file in the meantime.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Self::from_binary(&map[..])
+    }
+
+    /// Load a parameter archive from binary data, reporting progress as it is
+    /// parsed. The callback is invoked after each top-level parameter list
+    /// and object with the number of bytes processed so far and the total
+    /// size of the archive. Returning [`ControlFlow::Break`] stops parsing
+    /// and returns [`Error::Aborted`].
+    ///
+    /// **Note**: If and only if the `yaz0` feature is enabled, this function
+    /// automatically decompresses the data when necessary. Progress is then
+    /// relative to the decompressed size.
+    pub fn from_binary_with_progress(
+        data: impl AsRef<[u8]>,
+        mut progress: impl FnMut(usize, usize) -> ControlFlow<()>,
+    ) -> Result<ParameterIO> {
+        #[cfg(feature = "yaz0")]
+        {
+            if data.as_ref().starts_with(b"Yaz0") {
+                return Parser::new(std::io::Cursor::new(crate::yaz0::decompress(
+                    data.as_ref(),
+                )?))?
+                .parse_with_progress(&mut progress);
+            }
+        }
+        Parser::new(std::io::Cursor::new(data.as_ref()))?.parse_with_progress(&mut progress)
+    }
+
+    /// Load a parameter archive from binary data, also returning any entries
+    /// which could not be stored because another entry in the same parent has
+    /// the same name hash.
+    ///
+    /// The binary format allows such duplicates, but the maps in
+    /// [`ParameterList`] and [`ParameterObject`] hold one value per name, so
+    /// [`ParameterIO::from_binary`] keeps the last entry with each name and
+    /// silently drops the others. This reports the dropped entries instead, so
+    /// that the rare files with colliding names can be detected and handled
+    /// without losing data. Pass them to
+    /// [`ParameterIO::to_binary_with_duplicates`] to write them back.
+    ///
+    /// **Note**: If and only if the `yaz0` feature is enabled, this function
+    /// automatically decompresses the data when necessary.
+    pub fn from_binary_with_duplicates(
+        data: impl AsRef<[u8]>,
+    ) -> Result<(ParameterIO, Vec<DuplicateEntry>)> {
+        #[cfg(feature = "yaz0")]
+        {
+            if data.as_ref().starts_with(b"Yaz0") {
+                return Self::from_binary_with_duplicates(crate::yaz0::decompress(data.as_ref())?);
+            }
+        }
+        let mut parser = Parser::new(std::io::Cursor::new(data.as_ref()))?;
+        parser.duplicates = Some(Vec::new());
+        let pio = parser.parse()?;
+        Ok((pio, parser.duplicates.unwrap_or_default()))
+    }
+
+    /// Load a parameter archive from untrusted binary data, bounding the
+    /// resources used to parse it. Parsing stops with
+    /// [`Error::LimitExceeded`] as soon as any of the limits is exceeded.
+    ///
+    /// Unlike [`ParameterIO::from_binary`], this never decompresses Yaz0
+    /// data, since the decompressed size is not bounded by the limits.
+    pub fn from_binary_with_limits(data: impl AsRef<[u8]>, limits: Limits) -> Result<ParameterIO> {
+        let mut parser = Parser::new(std::io::Cursor::new(data.as_ref()))?;
+        parser.limits = Some(limits);
+        parser.parse()
+    }
+
+    /// Load a parameter archive from binary data, keeping the full fixed-size
+    /// slot of `String32`, `String64`, and `String256` parameters.
+    ///
+    /// By default, fixed-size strings are read up to their null terminator
+    /// and any bytes after it are discarded. Some files store these strings in
+    /// full 32, 64, or 256 byte slots with leftover data after the
+    /// terminator. This keeps those bytes (see
+    /// [`FixedSafeString::as_raw_bytes`]), and writing with
+    /// [`WriteOptions::fixed_string_slots`] then reproduces the original
+    /// slots. The text format only includes the string up to the terminator.
+    ///
+    /// A string only has a full slot if the file leaves room for one before
+    /// the next string or the end of the string section. Strings which are
+    /// stored tightly, as in files from the games, are read as usual, so the
+    /// result is equal to the output of [`ParameterIO::from_binary`] for such
+    /// files. The kept bytes do not affect comparisons in any case.
+    ///
+    /// **Note**: If and only if the `yaz0` feature is enabled, this function
+    /// automatically decompresses the data when necessary.
+    pub fn from_binary_with_fixed_string_slots(data: impl AsRef<[u8]>) -> Result<ParameterIO> {
+        #[cfg(feature = "yaz0")]
+        {
+            if data.as_ref().starts_with(b"Yaz0") {
+                return Self::from_binary_with_fixed_string_slots(crate::yaz0::decompress(
+                    data.as_ref(),
+                )?);
+            }
+        }
+        let mut parser = Parser::new(std::io::Cursor::new(data.as_ref()))?;
+        parser.string_offsets = Some(parser.read_string_offsets()?);
+        parser.parse()
+    }
+
+    /// Load a parameter archive from binary data, sharing the storage of
+    /// `StringRef` values with identical strings in the interner.
+    ///
+    /// Actor parameter files repeat the same strings, such as profile and
+    /// asset names, many times. Loading a batch of files with one interner
+    /// keeps a single copy of each such string. The parsed parameter IO is
+    /// equal to the output of [`ParameterIO::from_binary`].
+    ///
+    /// **Note**: If and only if the `yaz0` feature is enabled, this function
+    /// automatically decompresses the data when necessary.
+    pub fn from_binary_with_interner(
+        data: impl AsRef<[u8]>,
+        interner: &mut StringInterner,
+    ) -> Result<ParameterIO> {
+        #[cfg(feature = "yaz0")]
+        {
+            if data.as_ref().starts_with(b"Yaz0") {
+                return Self::from_binary_with_interner(
+                    crate::yaz0::decompress(data.as_ref())?,
+                    interner,
+                );
+            }
+        }
+        let mut parser = Parser::new(std::io::Cursor::new(data.as_ref()))?;
+        parser.interner = Some(std::mem::take(interner));
+        let result = parser.parse();
+        *interner = parser.interner.take().unwrap_or_default();
+        result
+    }
+}
+
+/// Limits for parsing untrusted parameter archives with
+/// [`ParameterIO::from_binary_with_limits`]. The defaults are generous
+/// enough for any archive from the games.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum number of lists, objects, and parameters, including the root
+    /// list. Defaults to 1,000,000.
+    pub max_nodes: usize,
+    /// Maximum nesting depth of lists, where the root list has a depth of 1.
+    /// Defaults to 64.
+    pub max_depth: usize,
+    /// Maximum total length in bytes of all string parameters. Defaults to
+    /// 16 MiB.
+    pub max_total_string_bytes: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_nodes: 1_000_000,
+            max_depth: 64,
+            max_total_string_bytes: 16 << 20,
+        }
+    }
+}
+
+/// The value of a [`DuplicateEntry`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DuplicateValue {
+    /// A duplicate parameter list.
+    List(ParameterList),
+    /// A duplicate parameter object.
+    Object(ParameterObject),
+    /// A duplicate parameter.
+    Parameter(Parameter),
+}
+
+/// An entry which was dropped while parsing because another entry in the same
+/// parent has the same name hash. See
+/// [`ParameterIO::from_binary_with_duplicates`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateEntry {
+    /// Names of the containing lists and object, followed by the name of the
+    /// entry itself, not including the root list.
+    pub path:  Vec<Name>,
+    /// The dropped value.
+    pub value: DuplicateValue,
 }
 
 struct Parser<R: Read + Seek> {
     reader: R,
+    len: u64,
     header: ResHeader,
-    endian: binrw::Endian,
+    /// Names of the structures being parsed, starting with the root list.
+    path: Vec<Name>,
+    duplicates: Option<Vec<DuplicateEntry>>,
+    limits: Option<Limits>,
+    /// Number of lists, objects, and parameters parsed, for the limits.
+    nodes: usize,
+    /// Total length of the string parameters parsed, for the limits.
+    string_bytes: usize,
+    /// Sorted offsets of the strings in the string section, followed by the
+    /// end of the section, when reading fixed-size strings as full slots.
+    string_offsets: Option<Vec<u32>>,
+    /// Interner for `StringRef` values, if their storage should be shared.
+    interner: Option<StringInterner>,
 }
 
 impl<R: Read + Seek> Parser<R> {
     fn new(mut reader: R) -> Result<Self> {
-        if SeekShim::stream_len(&mut reader)? < 0x30 {
-            return Err(Error::InvalidData("Incomplete parameter archive"));
+        let len = SeekShim::stream_len(&mut reader)?;
+        if len < 0x30 {
+            return Err(Error::InvalidDataD(format!(
+                "AAMP file too small: {len} bytes, expected at least 48"
+            )));
         }
         let header = ResHeader::read(&mut reader)?;
+        let tables_end = 0x30
+            + header.pio_offset as u64
+            + header.list_count as u64 * 0xC
+            + header.object_count as u64 * 0x8
+            + header.param_count as u64 * 0x8
+            + header.data_section_size as u64
+            + header.string_section_size as u64;
+        // The padding after the last section is not always written out.
+        if tables_end > len + (4 - len % 4) % 4 {
+            return Err(Error::InvalidDataD(format!(
+                "AAMP file too small: {len} bytes, but the header requires {tables_end}"
+            )));
+        }
         if header.version != 2 {
             return Err(Error::InvalidData(
                 "Only version 2 parameter archives are supported",
@@ -58,13 +289,123 @@ impl<R: Read + Seek> Parser<R> {
         }
         Ok(Self {
             reader,
+            len,
             header,
-            endian: binrw::Endian::Little,
+            path: Vec::new(),
+            duplicates: None,
+            limits: None,
+            nodes: 0,
+            string_bytes: 0,
+            string_offsets: None,
+            interner: None,
         })
     }
 
+    /// Count a parsed list, object, or parameter against the limits. Lists
+    /// are counted before being pushed to the path.
+    fn check_node(&mut self, is_list: bool) -> Result<()> {
+        let Some(limits) = self.limits else {
+            return Ok(());
+        };
+        self.nodes += 1;
+        if self.nodes > limits.max_nodes {
+            return Err(Error::LimitExceeded("nodes", limits.max_nodes));
+        }
+        if is_list && self.path.len() >= limits.max_depth {
+            return Err(Error::LimitExceeded(
+                "levels of nested lists",
+                limits.max_depth,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Count a parsed string parameter against the limits.
+    fn check_string(&mut self, param: &Parameter) -> Result<()> {
+        let Some(limits) = self.limits else {
+            return Ok(());
+        };
+        if let Ok(bytes) = param.as_string_bytes() {
+            self.string_bytes += bytes.len();
+            if self.string_bytes > limits.max_total_string_bytes {
+                return Err(Error::LimitExceeded(
+                    "bytes of strings",
+                    limits.max_total_string_bytes,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Insert a parsed entry into its parent map, recording the value it
+    /// replaces (if any) when duplicates are being collected.
+    fn insert<V>(
+        &mut self,
+        map: &mut ParameterStructureMap<V>,
+        name: Name,
+        value: V,
+        wrap: fn(V) -> DuplicateValue,
+    ) {
+        if let Some(old) = map.insert(name, value) {
+            if let Some(duplicates) = self.duplicates.as_mut() {
+                let mut path = self.path.get(1..).unwrap_or_default().to_vec();
+                path.push(name);
+                duplicates.push(DuplicateEntry {
+                    path,
+                    value: wrap(old),
+                });
+            }
+        }
+    }
+
     fn parse(&mut self) -> Result<ParameterIO> {
         let (root_name, param_root) = self.parse_list(self.header.pio_offset + 0x30)?;
+        self.finish(root_name, param_root)
+    }
+
+    /// Same as [`Parser::parse`], except that the children of the root list
+    /// are parsed one at a time so progress can be reported between them.
+    fn parse_with_progress(
+        &mut self,
+        progress: &mut dyn FnMut(usize, usize) -> ControlFlow<()>,
+    ) -> Result<ParameterIO> {
+        let offset = self.header.pio_offset + 0x30;
+        self.seek(offset)?;
+        let info: ResParameterList = self.read()?;
+        self.check_node(true)?;
+        let lists_offset = info.lists_rel_offset as u32 * 4 + offset;
+        let objects_offset = info.objects_rel_offset as u32 * 4 + offset;
+        let total = self.header.file_size as usize;
+        let mut processed = 0;
+        let mut report = |parser: &mut Self| -> Result<()> {
+            processed = processed.max(parser.reader.stream_position()? as usize);
+            match progress(processed.min(total), total) {
+                ControlFlow::Continue(()) => Ok(()),
+                ControlFlow::Break(()) => Err(Error::Aborted),
+            }
+        };
+        let mut param_root = ParameterList::new();
+        self.path.push(info.name);
+        for i in 0..info.list_count {
+            let (name, list) = self.parse_list(lists_offset + 0xC * i as u32)?;
+            self.insert(&mut param_root.lists.0, name, list, DuplicateValue::List);
+            report(self)?;
+        }
+        for i in 0..info.object_count {
+            let (name, object) = self.parse_object(objects_offset + 0x8 * i as u32)?;
+            self.insert(
+                &mut param_root.objects.0,
+                name,
+                object,
+                DuplicateValue::Object,
+            );
+            report(self)?;
+        }
+        self.path.pop();
+        self.finish(info.name, param_root)
+    }
+
+    fn finish(&mut self, root_name: Name, param_root: ParameterList) -> Result<ParameterIO> {
         if root_name != ROOT_KEY {
             Err(Error::InvalidData(
                 "No param root found in parameter archive",
@@ -74,7 +415,7 @@ impl<R: Read + Seek> Parser<R> {
                 version: self.header.pio_version,
                 data_type: {
                     self.seek(0x30)?;
-                    self.read_null_string()?
+                    std::str::from_utf8(&self.read_null_string()?)?.into()
                 },
                 param_root,
             })
@@ -93,16 +434,66 @@ impl<R: Read + Seek> Parser<R> {
     }
 
     #[inline]
-    fn read_null_string(&mut self) -> Result<String> {
-        let mut string_ = [0u8; 0x256];
+    fn read_null_string(&mut self) -> Result<Vec<u8>> {
+        let mut string_ = Vec::new();
         let mut c: u8 = self.read()?;
-        let mut len = 0;
         while c != 0 {
-            string_[len] = c;
-            len += 1;
+            string_.push(c);
             c = self.read()?;
         }
-        Ok(unsafe { std::str::from_utf8_unchecked(&string_[..len]) }.into())
+        Ok(string_)
+    }
+
+    /// Collect the offsets of all string parameters in the string section
+    /// from the parameter table, followed by the end of the section.
+    fn read_string_offsets(&mut self) -> Result<Vec<u32>> {
+        let params_offset = 0x30
+            + self.header.pio_offset
+            + self.header.list_count * 0xC
+            + self.header.object_count * 0x8;
+        let section_begin = params_offset
+            + self.header.param_count * 0x8
+            + self.header.data_section_size;
+        let section_end = section_begin + self.header.string_section_size;
+        let mut offsets = Vec::new();
+        for i in 0..self.header.param_count {
+            let offset = params_offset + i * 0x8;
+            self.seek(offset)?;
+            let info: ResParameter = self.read()?;
+            let data_offset = info.data_rel_offset.as_u32() * 4 + offset;
+            let is_string = matches!(
+                info.type_,
+                ParameterType::String32
+                    | ParameterType::String64
+                    | ParameterType::String256
+                    | ParameterType::StringRef
+            );
+            if is_string && (section_begin..section_end).contains(&data_offset) {
+                offsets.push(data_offset);
+            }
+        }
+        offsets.sort_unstable();
+        offsets.dedup();
+        offsets.push(section_end);
+        Ok(offsets)
+    }
+
+    /// Read a fixed-size string, keeping its full slot if the file reserves
+    /// one, i.e. if the next string or the end of the string section is at
+    /// least `N` bytes away.
+    fn read_string_slot<const N: usize>(&mut self) -> Result<FixedSafeString<N>> {
+        let pos = self.reader.stream_position()? as u32;
+        let offsets = self.string_offsets.as_deref().unwrap_or_default();
+        let has_slot = match offsets.binary_search(&pos) {
+            Ok(i) => offsets.get(i + 1).is_some_and(|end| end - pos >= N as u32),
+            Err(_) => false,
+        };
+        if !has_slot {
+            return self.read();
+        }
+        let mut data = [0; N];
+        self.reader.read_exact(&mut data)?;
+        FixedSafeString::from_raw_bytes(data)
     }
 
     #[inline]
@@ -114,23 +505,28 @@ impl<R: Read + Seek> Parser<R> {
         Ok(val)
     }
 
+    /// Check that a buffer fits in the rest of the data before allocating it,
+    /// so that a corrupt size cannot exhaust memory.
+    fn check_buffer_size(&mut self, count: u32, item_size: usize) -> Result<()> {
+        let remaining = self.len.saturating_sub(self.reader.stream_position()?);
+        if count as u64 * item_size as u64 > remaining {
+            return Err(Error::InvalidDataD(format!(
+                "Buffer of {count} items does not fit in the remaining {remaining} bytes"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Read a buffer parameter, whose item count is stored just before its
+    /// data. The count is checked against the remaining data so the buffer
+    /// can be allocated up front at its exact size.
     fn read_buffer<T>(&mut self, offset: u32) -> Result<Vec<T>>
     where
-        T: for<'a> BinRead<Args<'a> = ()> + Clone + 'static,
+        T: for<'a> BinRead<Args<'a> = ()>,
     {
         let size = self.read_at::<u32>(offset - 4)?;
-        let buf = binrw::BinRead::read_options(
-            &mut self.reader,
-            self.endian,
-            binrw::VecArgs::builder().count(size as usize).finalize(),
-        )?;
-        Ok(buf)
-    }
-
-    #[inline]
-    fn read_float_buffer(&mut self, offset: u32) -> Result<Vec<f32>> {
-        let size = self.read_at::<u32>(offset - 4)?;
-        let mut buf = Vec::<f32>::with_capacity(size as usize);
+        self.check_buffer_size(size, std::mem::size_of::<T>())?;
+        let mut buf = Vec::with_capacity(size as usize);
         for _ in 0..size {
             buf.push(self.read()?);
         }
@@ -140,57 +536,90 @@ impl<R: Read + Seek> Parser<R> {
     fn parse_parameter(&mut self, offset: u32) -> Result<(Name, Parameter)> {
         self.seek(offset)?;
         let info: ResParameter = self.read()?;
+        self.check_node(false)?;
         let data_offset = info.data_rel_offset.as_u32() * 4 + offset;
         self.seek(data_offset)?;
         let value = match info.type_ {
-            Type::Bool => Parameter::Bool(self.read::<u32>()? != 0),
-            Type::F32 => Parameter::F32(self.read::<f32>()?),
-            Type::Int => Parameter::I32(self.read()?),
-            Type::Vec2 => Parameter::Vec2(self.read()?),
-            Type::Vec3 => Parameter::Vec3(self.read()?),
-            Type::Vec4 => Parameter::Vec4(self.read()?),
-            Type::Quat => Parameter::Quat(self.read()?),
-            Type::Color => Parameter::Color(self.read()?),
-            Type::U32 => Parameter::U32(self.read()?),
-            Type::Curve1 => Parameter::Curve1(self.read()?),
-            Type::Curve2 => Parameter::Curve2(self.read()?),
-            Type::Curve3 => Parameter::Curve3(self.read()?),
-            Type::Curve4 => Parameter::Curve4(self.read()?),
-            Type::String32 => Parameter::String32(self.read()?),
-            Type::String64 => Parameter::String64(self.read()?),
-            Type::String256 => Parameter::String256(self.read()?),
-            Type::StringRef => Parameter::StringRef(self.read_null_string()?),
-            Type::BufferInt => Parameter::BufferInt(self.read_buffer::<i32>(data_offset)?),
-            Type::BufferU32 => Parameter::BufferU32(self.read_buffer::<u32>(data_offset)?),
-            Type::BufferF32 => Parameter::BufferF32(self.read_float_buffer(offset)?),
-            Type::BufferBinary => Parameter::BufferBinary(self.read_buffer::<u8>(data_offset)?),
+            ParameterType::Bool => Parameter::Bool(self.read::<u32>()? != 0),
+            ParameterType::F32 => Parameter::F32(self.read::<f32>()?),
+            ParameterType::Int => Parameter::I32(self.read()?),
+            ParameterType::Vec2 => Parameter::Vec2(self.read()?),
+            ParameterType::Vec3 => Parameter::Vec3(self.read()?),
+            ParameterType::Vec4 => Parameter::Vec4(self.read()?),
+            ParameterType::Quat => Parameter::Quat(self.read()?),
+            ParameterType::Color => Parameter::Color(self.read()?),
+            ParameterType::U32 => Parameter::U32(self.read()?),
+            ParameterType::Curve1 => Parameter::Curve1(self.read()?),
+            ParameterType::Curve2 => Parameter::Curve2(self.read()?),
+            ParameterType::Curve3 => Parameter::Curve3(self.read()?),
+            ParameterType::Curve4 => Parameter::Curve4(self.read()?),
+            ParameterType::String32 if self.string_offsets.is_some() => {
+                Parameter::String32(self.read_string_slot()?)
+            }
+            ParameterType::String64 if self.string_offsets.is_some() => {
+                Parameter::String64(Box::new(self.read_string_slot()?))
+            }
+            ParameterType::String256 if self.string_offsets.is_some() => {
+                Parameter::String256(Box::new(self.read_string_slot()?))
+            }
+            ParameterType::String32 => Parameter::String32(self.read()?),
+            ParameterType::String64 => Parameter::String64(self.read()?),
+            ParameterType::String256 => Parameter::String256(self.read()?),
+            ParameterType::StringRef => {
+                // Keep strings which are not valid UTF-8 as raw bytes.
+                let string_ = self.read_null_string()?;
+                match std::str::from_utf8(&string_) {
+                    Ok(s) => match self.interner.as_mut() {
+                        Some(interner) => Parameter::StringRef(interner.intern(s)),
+                        None => Parameter::StringRef(s.into()),
+                    },
+                    Err(_) => Parameter::StringRefRaw(string_),
+                }
+            }
+            ParameterType::BufferInt => Parameter::BufferInt(self.read_buffer::<i32>(data_offset)?),
+            ParameterType::BufferU32 => Parameter::BufferU32(self.read_buffer::<u32>(data_offset)?),
+            ParameterType::BufferF32 => Parameter::BufferF32(self.read_buffer::<f32>(data_offset)?),
+            ParameterType::BufferBinary => Parameter::BufferBinary(self.read_buffer::<u8>(data_offset)?),
         };
+        self.check_string(&value)?;
         Ok((info.name, value))
     }
 
     fn parse_object(&mut self, offset: u32) -> Result<(Name, ParameterObject)> {
         self.seek(offset)?;
         let info: ResParameterObj = self.read()?;
+        self.check_node(false)?;
         let offset = info.params_rel_offset as u32 * 4 + offset;
-        let params = (0..info.param_count)
-            .map(|i| self.parse_parameter(offset + 0x8 * i as u32))
-            .collect::<Result<_>>()?;
+        let mut params = ParameterObject::default();
+        params.0.reserve(info.param_count as usize);
+        self.path.push(info.name);
+        for i in 0..info.param_count {
+            let (name, param) = self.parse_parameter(offset + 0x8 * i as u32)?;
+            self.insert(&mut params.0, name, param, DuplicateValue::Parameter);
+        }
+        self.path.pop();
         Ok((info.name, params))
     }
 
     fn parse_list(&mut self, offset: u32) -> Result<(Name, ParameterList)> {
         self.seek(offset)?;
         let info: ResParameterList = self.read()?;
+        self.check_node(true)?;
         let lists_offset = info.lists_rel_offset as u32 * 4 + offset;
         let objects_offset = info.objects_rel_offset as u32 * 4 + offset;
-        let plist = ParameterList {
-            lists:   (0..info.list_count)
-                .map(|i| self.parse_list(lists_offset + 0xC * i as u32))
-                .collect::<Result<_>>()?,
-            objects: (0..info.object_count)
-                .map(|i| self.parse_object(objects_offset + 0x8 * i as u32))
-                .collect::<Result<_>>()?,
-        };
+        let mut plist = ParameterList::default();
+        plist.lists.0.reserve(info.list_count as usize);
+        plist.objects.0.reserve(info.object_count as usize);
+        self.path.push(info.name);
+        for i in 0..info.list_count {
+            let (name, list) = self.parse_list(lists_offset + 0xC * i as u32)?;
+            self.insert(&mut plist.lists.0, name, list, DuplicateValue::List);
+        }
+        for i in 0..info.object_count {
+            let (name, object) = self.parse_object(objects_offset + 0x8 * i as u32)?;
+            self.insert(&mut plist.objects.0, name, object, DuplicateValue::Object);
+        }
+        self.path.pop();
         Ok((info.name, plist))
     }
 }
@@ -214,4 +643,232 @@ mod tests {
             ParameterIO::from_binary(data).unwrap();
         }
     }
+
+    #[test]
+    fn fixed_string_slots() {
+        // Strings are stored tightly, so the 256 byte slot of `Name` would
+        // overlap the strings after it.
+        let pio = ParameterIO::new().with_object(
+            "Root",
+            params!(
+                "Name" => Parameter::String256(Box::new("Link".into())),
+                "Title" => Parameter::String32("Hero".into()),
+                "Place" => Parameter::StringRef("Hyrule".into())
+            ),
+        );
+        let data = pio.to_binary();
+        let new_pio = ParameterIO::from_binary_with_fixed_string_slots(&data).unwrap();
+        assert_eq!(new_pio, pio);
+        let Parameter::String256(name) = &new_pio.object("Root").unwrap()["Name"] else {
+            panic!("Name should be a String256");
+        };
+        assert!(name.as_raw_bytes()[4..].iter().all(|&c| c == 0));
+        let options = WriteOptions {
+            fixed_string_slots: true,
+            ..Default::default()
+        };
+        assert_eq!(new_pio.to_binary_with_options(options), data);
+
+        for file in jwalk::WalkDir::new("test/aamp")
+            .into_iter()
+            .filter_map(|f| {
+                f.ok().and_then(|f| {
+                    (f.file_type().is_file() && !f.file_name().to_str().unwrap().ends_with("yml"))
+                        .then(|| f.path())
+                })
+            })
+        {
+            let data = std::fs::read(&file).unwrap();
+            let pio = ParameterIO::from_binary_with_fixed_string_slots(&data).unwrap();
+            assert_eq!(pio, ParameterIO::from_binary(&data).unwrap());
+            assert_eq!(pio.to_binary_with_options(options), pio.to_binary());
+        }
+    }
+
+    #[test]
+    fn interner() {
+        let long = "Enemy_Lizalfos_Electric_Profile";
+        let pio = ParameterIO::new().with_object(
+            "Root",
+            params!(
+                "Profile" => Parameter::StringRef(long.into()),
+                "Name" => Parameter::StringRef("Lizalfos".into())
+            ),
+        );
+        let data = pio.to_binary();
+        let mut interner = StringInterner::new();
+        let first = ParameterIO::from_binary_with_interner(&data, &mut interner).unwrap();
+        let second = ParameterIO::from_binary_with_interner(&data, &mut interner).unwrap();
+        assert_eq!(first, pio);
+        assert_eq!(second, pio);
+        // Only the string which is too long to be stored inline is interned.
+        assert_eq!(interner.len(), 1);
+        let string = |pio: &ParameterIO, name: &str| match &pio.object("Root").unwrap()[name] {
+            Parameter::StringRef(s) => s.clone(),
+            _ => panic!("{name} should be a StringRef"),
+        };
+        assert!(string(&first, "Profile").is_shared());
+        assert!(!string(&first, "Name").is_shared());
+        assert_eq!(
+            string(&first, "Profile").as_ptr(),
+            string(&second, "Profile").as_ptr()
+        );
+
+        let mut first = first;
+        first.object_mut("Root").unwrap()["Profile"]
+            .as_mut_string_ref()
+            .unwrap()
+            .make_ascii_uppercase();
+        assert_eq!(
+            string(&first, "Profile"),
+            long.to_ascii_uppercase().as_str()
+        );
+        assert_eq!(string(&second, "Profile"), long);
+        drop(first);
+        drop(second);
+        interner.shrink();
+        assert!(interner.is_empty());
+    }
+
+    #[test]
+    fn buffers() {
+        let pio = ParameterIO::new().with_object(
+            "Buffers",
+            params!(
+                "Floats" => Parameter::BufferF32(vec![0.5, 1.0, -2.25]),
+                "Bytes" => Parameter::BufferBinary((0..=255).collect()),
+                "Ints" => Parameter::BufferInt(vec![]),
+            ),
+        );
+        let pio2 = ParameterIO::from_binary(pio.to_binary()).unwrap();
+        assert_eq!(pio, pio2);
+        match &pio2.object("Buffers").unwrap()["Bytes"] {
+            Parameter::BufferBinary(bytes) => assert_eq!(bytes.capacity(), 256),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn truncated() {
+        let err = ParameterIO::from_binary(b"AAMP").unwrap_err();
+        assert!(err.to_string().contains("AAMP file too small: 4 bytes"));
+        let data = std::fs::read("test/aamp/Lizalfos.bphysics").unwrap();
+        let err = ParameterIO::from_binary(&data[..data.len() / 2]).unwrap_err();
+        assert!(err.to_string().starts_with("AAMP file too small"));
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn from_mmap() {
+        let path = "test/aamp/Lizalfos.bphysics";
+        let pio = ParameterIO::from_mmap(path).unwrap();
+        let data = std::fs::read(path).unwrap();
+        assert_eq!(pio, ParameterIO::from_binary(data).unwrap());
+    }
+
+    #[test]
+    fn parse_with_progress() {
+        let data = std::fs::read("test/aamp/Lizalfos.bphysics").unwrap();
+        let pio = ParameterIO::from_binary(&data).unwrap();
+        let mut reports = vec![];
+        let pio2 = ParameterIO::from_binary_with_progress(&data, |processed, total| {
+            reports.push((processed, total));
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+        assert_eq!(pio, pio2);
+        assert_eq!(
+            reports.len(),
+            pio.param_root.lists.len() + pio.param_root.objects.len()
+        );
+        assert!(reports.windows(2).all(|w| w[0].0 <= w[1].0));
+        assert!(reports.iter().all(|&(p, t)| p <= t && t == data.len()));
+
+        let mut calls = 0;
+        let result = ParameterIO::from_binary_with_progress(&data, |_, _| {
+            calls += 1;
+            ControlFlow::Break(())
+        });
+        assert!(matches!(result, Err(Error::Aborted)));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn limits() {
+        let pio = ParameterIO::new()
+            .with_object(
+                "Root",
+                params!("Name" => Parameter::StringRef("Bokoblin".into())),
+            )
+            .with_list(
+                "AI",
+                ParameterList::new().with_list(
+                    "Child",
+                    ParameterList::new()
+                        .with_object("Values", params!("Count" => Parameter::I32(2))),
+                ),
+            );
+        let data = pio.to_binary();
+        let limits = Limits::default();
+        assert_eq!(ParameterIO::from_binary_with_limits(&data, limits).unwrap(), pio);
+
+        // Root list, two child lists, two objects, and two parameters.
+        let nodes = Limits {
+            max_nodes: 6,
+            ..limits
+        };
+        let err = ParameterIO::from_binary_with_limits(&data, nodes).unwrap_err();
+        assert!(matches!(err, Error::LimitExceeded("nodes", 6)));
+        let depth = Limits {
+            max_depth: 2,
+            ..limits
+        };
+        let err = ParameterIO::from_binary_with_limits(&data, depth).unwrap_err();
+        assert!(matches!(err, Error::LimitExceeded(_, 2)));
+        let strings = Limits {
+            max_total_string_bytes: 7,
+            ..limits
+        };
+        let err = ParameterIO::from_binary_with_limits(&data, strings).unwrap_err();
+        assert!(matches!(err, Error::LimitExceeded("bytes of strings", 7)));
+        let exact = Limits {
+            max_nodes: 7,
+            max_depth: 3,
+            max_total_string_bytes: 8,
+        };
+        assert!(ParameterIO::from_binary_with_limits(&data, exact).is_ok());
+    }
+
+    #[test]
+    fn duplicates() {
+        let pio = ParameterIO::new().with_list(
+            "AI",
+            ParameterList::new().with_object(
+                "Root",
+                params!("A" => Parameter::I32(1), "B" => Parameter::I32(2)),
+            ),
+        );
+        let mut data = pio.to_binary();
+        let (new_pio, duplicates) = ParameterIO::from_binary_with_duplicates(&data).unwrap();
+        assert_eq!(new_pio, pio);
+        assert!(duplicates.is_empty());
+
+        // Give `B` the same name hash as `A`.
+        let layout = pio.binary_layout();
+        let b = layout
+            .iter()
+            .find(|entry| entry.name == Name::from_str("B"))
+            .unwrap();
+        data[b.offset as usize..][..4].copy_from_slice(&Name::from_str("A").0.to_le_bytes());
+        let (new_pio, duplicates) = ParameterIO::from_binary_with_duplicates(&data).unwrap();
+        assert_eq!(new_pio, ParameterIO::from_binary(&data).unwrap());
+        assert_eq!(duplicates, [DuplicateEntry {
+            path:  vec![
+                Name::from_str("AI"),
+                Name::from_str("Root"),
+                Name::from_str("A")
+            ],
+            value: DuplicateValue::Parameter(Parameter::I32(1)),
+        }]);
+    }
 }
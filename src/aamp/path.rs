@@ -0,0 +1,46 @@
+//! Helpers for slash-separated parameter paths like `AI/Root/Damage`.
+//!
+//! Each segment is a structure name. Segments consisting only of digits are
+//! treated as hashes, matching the way unknown names are written to YAML.
+use super::*;
+
+/// Convert a single path segment to a name.
+pub(super) fn segment_to_name(segment: &str) -> Name {
+    match segment.parse::<u32>() {
+        Ok(hash) => Name(hash),
+        Err(_) => Name::from(segment),
+    }
+}
+
+/// Split a path into names.
+pub(super) fn parse_path(path: &str) -> Vec<Name> {
+    path.split('/')
+        .filter(|s| !s.is_empty())
+        .map(segment_to_name)
+        .collect()
+}
+
+/// Get the path segment for a name, resolving it from the default name table
+/// if possible, or falling back to the hash.
+pub(super) fn name_to_segment(name: Name, index: usize, parent: Name) -> String {
+    match get_default_name_table().get_name(name.0, index, parent.0) {
+        Some(name) => name.as_ref().into(),
+        None => {
+            let mut segment = String::new();
+            let _ = std::fmt::Write::write_fmt(&mut segment, format_args!("{}", name.0));
+            segment
+        }
+    }
+}
+
+/// Append a segment to a path.
+pub(super) fn join(path: &str, segment: &str) -> String {
+    if path.is_empty() {
+        segment.into()
+    } else {
+        let mut joined = String::from(path);
+        joined.push('/');
+        joined.push_str(segment);
+        joined
+    }
+}
@@ -0,0 +1,415 @@
+//! Schemas for checking the structure of parameter IOs.
+use super::{
+    path::{join, name_to_segment},
+    *,
+};
+
+/// An error found while validating a parameter IO against a [`ParamSchema`].
+/// Each error includes the path of the offending structure or parameter (e.g.
+/// `AI/Root/Damage`).
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SchemaError {
+    /// A required parameter, object, or list is missing.
+    #[error("Missing required entry `{path}`")]
+    Missing {
+        /// Path of the missing entry.
+        path: String,
+    },
+    /// A parameter has the wrong type.
+    #[error("Parameter `{path}` has type {found:?}, expected {expected:?}")]
+    TypeMismatch {
+        /// Path of the parameter.
+        path:     String,
+        /// Type declared by the schema.
+        expected: ParameterType,
+        /// Actual type of the parameter.
+        found:    ParameterType,
+    },
+    /// A parameter, object, or list is not declared by the schema.
+    #[error("Unexpected entry `{path}`")]
+    Unexpected {
+        /// Path of the unexpected entry.
+        path: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SchemaEntry<T> {
+    name:     String,
+    required: bool,
+    schema:   T,
+}
+
+type SchemaMap<T> = ParameterStructureMap<SchemaEntry<T>>;
+
+fn insert_entry<T>(map: &mut SchemaMap<T>, name: &str, required: bool, schema: T) {
+    map.insert(Name::from(name), SchemaEntry {
+        name: name.into(),
+        required,
+        schema,
+    });
+}
+
+/// Expected shape of a [`ParameterObject`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ObjectSchema {
+    params: SchemaMap<ParameterType>,
+    allow_unknown: bool,
+}
+
+impl ObjectSchema {
+    /// Create a new empty object schema.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Builder-like method to declare a required parameter.
+    pub fn with_param(mut self, name: &str, param_type: ParameterType) -> Self {
+        insert_entry(&mut self.params, name, true, param_type);
+        self
+    }
+
+    /// Builder-like method to declare an optional parameter.
+    pub fn with_optional_param(mut self, name: &str, param_type: ParameterType) -> Self {
+        insert_entry(&mut self.params, name, false, param_type);
+        self
+    }
+
+    /// Builder-like method to set whether parameters which are not declared
+    /// are allowed. By default they are reported as errors.
+    pub fn with_unknown_allowed(mut self, value: bool) -> Self {
+        self.allow_unknown = value;
+        self
+    }
+}
+
+/// Expected shape of a [`ParameterList`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ListSchema {
+    objects: SchemaMap<ObjectSchema>,
+    lists: SchemaMap<ListSchema>,
+    allow_unknown: bool,
+}
+
+impl ListSchema {
+    /// Create a new empty list schema.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Builder-like method to declare a required parameter object.
+    pub fn with_object(mut self, name: &str, schema: ObjectSchema) -> Self {
+        insert_entry(&mut self.objects, name, true, schema);
+        self
+    }
+
+    /// Builder-like method to declare an optional parameter object.
+    pub fn with_optional_object(mut self, name: &str, schema: ObjectSchema) -> Self {
+        insert_entry(&mut self.objects, name, false, schema);
+        self
+    }
+
+    /// Builder-like method to declare a required parameter list.
+    pub fn with_list(mut self, name: &str, schema: ListSchema) -> Self {
+        insert_entry(&mut self.lists, name, true, schema);
+        self
+    }
+
+    /// Builder-like method to declare an optional parameter list.
+    pub fn with_optional_list(mut self, name: &str, schema: ListSchema) -> Self {
+        insert_entry(&mut self.lists, name, false, schema);
+        self
+    }
+
+    /// Builder-like method to set whether objects and lists which are not
+    /// declared are allowed. By default they are reported as errors.
+    pub fn with_unknown_allowed(mut self, value: bool) -> Self {
+        self.allow_unknown = value;
+        self
+    }
+}
+
+/// Expected shape of a [`ParameterIO`], for use with
+/// [`ParameterIO::validate`].
+///
+/// # Example
+/// ```
+/// # use roead::aamp::*;
+/// let schema = ParamSchema::new(
+///     ListSchema::new().with_object(
+///         "Root",
+///         ObjectSchema::new()
+///             .with_param("Damage", ParameterType::Int)
+///             .with_optional_param("Speed", ParameterType::F32),
+///     ),
+/// );
+/// let pio = ParameterIO::new().with_object("Root", params!("Damage" => Parameter::I32(10)));
+/// assert!(pio.validate(&schema).is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ParamSchema {
+    /// Schema for the root parameter list.
+    pub root: ListSchema,
+}
+
+impl ParamSchema {
+    /// Create a new schema with the given root list schema.
+    pub fn new(root: ListSchema) -> Self {
+        Self { root }
+    }
+}
+
+impl From<ListSchema> for ParamSchema {
+    fn from(root: ListSchema) -> Self {
+        Self::new(root)
+    }
+}
+
+fn check_unknown<V>(
+    errors: &mut Vec<SchemaError>,
+    path: &str,
+    parent: Name,
+    entries: &ParameterStructureMap<V>,
+    schema_entries: &ParameterStructureMap<impl Sized>,
+) {
+    for (i, name) in entries.keys().enumerate() {
+        if !schema_entries.contains_key(name) {
+            errors.push(SchemaError::Unexpected {
+                path: join(path, &name_to_segment(*name, i, parent)),
+            });
+        }
+    }
+}
+
+fn validate_object(
+    errors: &mut Vec<SchemaError>,
+    path: &str,
+    parent: Name,
+    obj: &ParameterObject,
+    schema: &ObjectSchema,
+) {
+    for (name, entry) in &schema.params {
+        let param_path = join(path, &entry.name);
+        match obj.get(*name) {
+            Some(param) if param.get_type() != entry.schema => {
+                errors.push(SchemaError::TypeMismatch {
+                    path:     param_path,
+                    expected: entry.schema,
+                    found:    param.get_type(),
+                })
+            }
+            Some(_) => (),
+            None if entry.required => errors.push(SchemaError::Missing { path: param_path }),
+            None => (),
+        }
+    }
+    if !schema.allow_unknown {
+        check_unknown(errors, path, parent, &obj.0, &schema.params);
+    }
+}
+
+fn validate_list(
+    errors: &mut Vec<SchemaError>,
+    path: &str,
+    parent: Name,
+    list: &ParameterList,
+    schema: &ListSchema,
+) {
+    for (name, entry) in &schema.objects {
+        let obj_path = join(path, &entry.name);
+        match list.objects.get(*name) {
+            Some(obj) => validate_object(errors, &obj_path, *name, obj, &entry.schema),
+            None if entry.required => errors.push(SchemaError::Missing { path: obj_path }),
+            None => (),
+        }
+    }
+    for (name, entry) in &schema.lists {
+        let list_path = join(path, &entry.name);
+        match list.lists.get(*name) {
+            Some(child) => validate_list(errors, &list_path, *name, child, &entry.schema),
+            None if entry.required => errors.push(SchemaError::Missing { path: list_path }),
+            None => (),
+        }
+    }
+    if !schema.allow_unknown {
+        check_unknown(errors, path, parent, &list.objects.0, &schema.objects);
+        check_unknown(errors, path, parent, &list.lists.0, &schema.lists);
+    }
+}
+
+fn apply_object(path: &str, obj: &mut ParameterObject, schema: &ObjectSchema) -> Result<()> {
+    fn fixed<const N: usize>(path: &str, value: &str) -> Result<FixedSafeString<N>> {
+        FixedSafeString::try_from_str(value).map_err(|_| {
+            Error::InvalidDataD(format!(
+                "String parameter `{}` is {} bytes long, but the schema allows at most {}",
+                path,
+                value.len(),
+                FixedSafeString::<N>::MAX_LEN
+            ))
+        })
+    }
+
+    for (name, entry) in &schema.params {
+        if let Some(param @ Parameter::StringRef(_)) = obj.get_mut(*name) {
+            let param_path = join(path, &entry.name);
+            let value = param.as_str()?;
+            *param = match entry.schema {
+                ParameterType::String32 => Parameter::String32(fixed(&param_path, value)?),
+                ParameterType::String64 => {
+                    Parameter::String64(Box::new(fixed(&param_path, value)?))
+                }
+                ParameterType::String256 => {
+                    Parameter::String256(Box::new(fixed(&param_path, value)?))
+                }
+                _ => continue,
+            };
+        }
+    }
+    Ok(())
+}
+
+fn apply_list(path: &str, list: &mut ParameterList, schema: &ListSchema) -> Result<()> {
+    for (name, entry) in &schema.objects {
+        if let Some(obj) = list.objects.get_mut(*name) {
+            apply_object(&join(path, &entry.name), obj, &entry.schema)?;
+        }
+    }
+    for (name, entry) in &schema.lists {
+        if let Some(child) = list.lists.get_mut(*name) {
+            apply_list(&join(path, &entry.name), child, &entry.schema)?;
+        }
+    }
+    Ok(())
+}
+
+impl ParameterIO {
+    /// Convert string parameters to the fixed-length string types declared by
+    /// a schema. This is useful for text documents in which the `!str32`,
+    /// `!str64`, and `!str256` tags were omitted, which are otherwise read as
+    /// `StringRef` parameters. Only `StringRef` parameters are converted, and
+    /// parameters not declared by the schema are left as they are.
+    ///
+    /// Returns an error if a string is too long for its declared type rather
+    /// than truncating it or choosing a larger type.
+    pub fn apply_schema(&mut self, schema: &ParamSchema) -> Result<()> {
+        apply_list("", &mut self.param_root, &schema.root)
+    }
+
+    /// Parse a parameter IO from YAML text, using a schema to infer the types
+    /// of untagged fixed-length strings. See [`ParameterIO::apply_schema`].
+    ///
+    /// This is only available with the `yaml` feature.
+    #[cfg(feature = "yaml")]
+    pub fn from_text_with_schema(text: impl AsRef<str>, schema: &ParamSchema) -> Result<Self> {
+        let mut pio = Self::from_text(text)?;
+        pio.apply_schema(schema)?;
+        Ok(pio)
+    }
+
+    /// Check that the parameter IO conforms to a schema, returning every
+    /// violation found (missing required entries, parameters with the wrong
+    /// type, and unexpected entries).
+    pub fn validate(&self, schema: &ParamSchema) -> std::result::Result<(), Vec<SchemaError>> {
+        let mut errors = Vec::new();
+        validate_list(&mut errors, "", ROOT_KEY, &self.param_root, &schema.root);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate() {
+        let schema = ParamSchema::new(
+            ListSchema::new()
+                .with_object(
+                    "Root",
+                    ObjectSchema::new()
+                        .with_param("Damage", ParameterType::Int)
+                        .with_optional_param("Speed", ParameterType::F32),
+                )
+                .with_optional_list(
+                    "AI",
+                    ListSchema::new().with_object(
+                        "Root",
+                        ObjectSchema::new().with_param("Name", ParameterType::StringRef),
+                    ),
+                ),
+        );
+        let pio = ParameterIO::new()
+            .with_object("Root", params!("Damage" => Parameter::I32(10)))
+            .with_list(
+                "AI",
+                ParameterList::new().with_object(
+                    "Root",
+                    params!("Name" => Parameter::StringRef("Attack".into())),
+                ),
+            );
+        assert_eq!(pio.validate(&schema), Ok(()));
+
+        let pio = ParameterIO::new()
+            .with_object(
+                "Root",
+                params!(
+                    "Speed" => Parameter::I32(1),
+                    "Values" => Parameter::Bool(true)
+                ),
+            )
+            .with_list("AI", ParameterList::new());
+        let errors = pio.validate(&schema).unwrap_err();
+        assert_eq!(errors, vec![
+            SchemaError::Missing {
+                path: "Root/Damage".into(),
+            },
+            SchemaError::TypeMismatch {
+                path:     "Root/Speed".into(),
+                expected: ParameterType::F32,
+                found:    ParameterType::Int,
+            },
+            SchemaError::Unexpected {
+                path: "Root/Values".into(),
+            },
+            SchemaError::Missing {
+                path: "AI/Root".into(),
+            },
+        ]);
+    }
+
+    #[test]
+    fn apply_schema() {
+        let schema = ParamSchema::new(ListSchema::new().with_object(
+            "Root",
+            ObjectSchema::new()
+                .with_param("Name", ParameterType::String32)
+                .with_optional_param("Tags", ParameterType::String64),
+        ));
+        let mut pio = ParameterIO::new().with_object(
+            "Root",
+            params!(
+                "Name" => Parameter::StringRef("Weapon_Sword_001".into()),
+                "Tags" => Parameter::String256(Box::new("Sword".into()))
+            ),
+        );
+        pio.apply_schema(&schema).unwrap();
+        let root = pio.object("Root").unwrap();
+        assert_eq!(root["Name"], Parameter::String32("Weapon_Sword_001".into()));
+        assert_eq!(root["Tags"], Parameter::String256(Box::new("Sword".into())));
+        assert_eq!(pio.validate(&schema).unwrap_err().len(), 1);
+
+        let mut pio = ParameterIO::new().with_object(
+            "Root",
+            params!("Name" => Parameter::StringRef("a".repeat(32).into())),
+        );
+        assert!(pio.apply_schema(&schema).is_err());
+        assert!(matches!(
+            pio.object("Root").unwrap()["Name"],
+            Parameter::StringRef(_)
+        ));
+    }
+}
@@ -0,0 +1,103 @@
+//! Structural statistics for parameter IOs.
+use rustc_hash::FxHashMap;
+
+use super::*;
+
+/// Structural statistics for a parameter IO, as returned by
+/// [`ParameterIO::stats`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParameterIOStats {
+    /// Total number of parameters.
+    pub parameters: usize,
+    /// Number of parameters of each type which occurs in the document.
+    pub parameters_by_type: FxHashMap<ParameterType, usize>,
+    /// Number of parameter objects.
+    pub objects: usize,
+    /// Number of parameter lists, not including the root list.
+    pub lists: usize,
+    /// Maximum nesting depth of lists, where the root list has a depth of 1
+    /// (as with [`Limits::max_depth`]).
+    pub max_depth: usize,
+}
+
+impl ParameterIOStats {
+    /// Total number of lists, objects, and parameters, including the root
+    /// list (as with [`Limits::max_nodes`]).
+    pub fn nodes(&self) -> usize {
+        1 + self.lists + self.objects + self.parameters
+    }
+
+    fn add_list(&mut self, list: &ParameterList, depth: usize) {
+        self.max_depth = self.max_depth.max(depth);
+        self.objects += list.objects.len();
+        for obj in list.objects.0.values() {
+            self.parameters += obj.len();
+            for param in obj.0.values() {
+                *self.parameters_by_type.entry(param.get_type()).or_default() += 1;
+            }
+        }
+        self.lists += list.lists.len();
+        for child in list.lists.0.values() {
+            self.add_list(child, depth + 1);
+        }
+    }
+}
+
+impl ParameterIO {
+    /// Compute structural statistics for the parameter IO in a single pass:
+    /// the number of parameters (in total and by type), objects, and lists,
+    /// and the maximum nesting depth. This is useful for summarizing large
+    /// files and spotting anomalies such as unexpectedly deep nesting.
+    pub fn stats(&self) -> ParameterIOStats {
+        let mut stats = ParameterIOStats::default();
+        stats.add_list(&self.param_root, 1);
+        stats
+    }
+
+    /// Count the parameters in every object of the parameter IO.
+    pub fn count_parameters(&self) -> usize {
+        fn count(list: &ParameterList) -> usize {
+            let params: usize = list.objects.0.values().map(ParameterObject::len).sum();
+            params + list.lists.0.values().map(count).sum::<usize>()
+        }
+        count(&self.param_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let pio = ParameterIO::new()
+            .with_object("Root", params!("Damage" => Parameter::I32(20)))
+            .with_list(
+                "AI",
+                ParameterList::new().with_list(
+                    "Child",
+                    ParameterList::new().with_object(
+                        "Values",
+                        params!(
+                            "Speed" => Parameter::F32(1.5),
+                            "Range" => Parameter::F32(2.0)
+                        ),
+                    ),
+                ),
+            );
+        let stats = pio.stats();
+        assert_eq!(stats.parameters, 3);
+        assert_eq!(stats.parameters, pio.count_parameters());
+        assert_eq!(stats.parameters_by_type[&ParameterType::F32], 2);
+        assert_eq!(stats.parameters_by_type[&ParameterType::Int], 1);
+        assert_eq!(stats.objects, 2);
+        assert_eq!(stats.lists, 2);
+        assert_eq!(stats.max_depth, 3);
+        assert_eq!(stats.nodes(), 8);
+        assert_eq!(ParameterIO::new().stats().max_depth, 1);
+
+        let data = std::fs::read("test/aamp/Lizalfos.bphysics").unwrap();
+        let pio = ParameterIO::from_binary(data).unwrap();
+        assert_eq!(pio.stats().parameters, pio.flatten().len());
+    }
+}
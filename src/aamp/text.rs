@@ -1,259 +1,626 @@
-use lexical::{FromLexical, FromLexicalWithOptions, ToLexical, ToLexicalWithOptions};
+//! Conversion between parameter IOs and YAML text.
+//!
+//! This is only available with the `yaml` feature.
+use std::borrow::Cow;
+
+use base64::Engine;
+use join_str::jstr;
+use lexical::{ToLexical, ToLexicalWithOptions};
+use rustc_hash::FxHashMap;
 use ryml::*;
 
-use super::*;
+use self::reader::{
+    parse_parameter, read_parameter_io, read_parameter_io_all_errors,
+    read_parameter_io_float_formats, read_parameter_io_with_options, read_parameter_list,
+};
+use super::{names::NameCache, *};
 use crate::{types::*, yaml::*, Error, Result};
 
+pub mod reader;
+
 impl ParameterIO {
-    /// Parse ParameterIO from YAML text.
+    /// Parse ParameterIO from YAML text. CRLF line endings and consistent tab
+    /// indentation are accepted.
     pub fn from_text(text: impl AsRef<str>) -> Result<Self> {
-        let tree = Tree::parse(text.as_ref())?;
+        let tree = Tree::parse(normalize_text(text.as_ref()))?;
         let root_ref = tree.root_ref()?;
         read_parameter_io(&root_ref)
     }
 
-    /// Serialize the parameter IO to YAML.
+    /// Parse ParameterIO from YAML text like [`ParameterIO::from_text`], using
+    /// the given options.
+    pub fn from_text_with_options(text: impl AsRef<str>, options: &ParseOptions) -> Result<Self> {
+        let tree = Tree::parse(normalize_text(text.as_ref()))?;
+        let root_ref = tree.root_ref()?;
+        read_parameter_io_with_options(&root_ref, options)
+    }
+
+    /// Parse ParameterIO from YAML text like [`ParameterIO::from_text`], but
+    /// instead of stopping at the first invalid parameter or structure,
+    /// continue reading the rest of the document and return every error
+    /// found. Each error message includes the path of the node it occurred
+    /// in.
+    ///
+    /// Invalid YAML syntax cannot be recovered from and is returned as a
+    /// single error.
+    pub fn from_text_all_errors(text: impl AsRef<str>) -> std::result::Result<Self, Vec<Error>> {
+        let tree = Tree::parse(normalize_text(text.as_ref())).map_err(|e| vec![e.into()])?;
+        let root_ref = tree.root_ref().map_err(|e| vec![e.into()])?;
+        read_parameter_io_all_errors(&root_ref)
+    }
+
+    /// Parse ParameterIO from YAML text like [`ParameterIO::from_text`], also
+    /// returning the original text of every float which roead would format
+    /// differently, such as `1.50` or `2e3`. The floats are grouped by
+    /// parameter, keyed by parameter path (e.g. `AI/Root/Speed`), and each
+    /// entry holds the text of every float in the parameter in order.
+    ///
+    /// Passing the result as [`TextOptions::float_formats`] writes unchanged
+    /// floats exactly as they were in the source text, for lossless round
+    /// trips where only edited values change.
+    pub fn from_text_with_float_formats(
+        text: impl AsRef<str>,
+    ) -> Result<(Self, FxHashMap<String, Vec<std::string::String>>)> {
+        let tree = Tree::parse(normalize_text(text.as_ref()))?;
+        let root_ref = tree.root_ref()?;
+        read_parameter_io_float_formats(&root_ref)
+    }
+
+    /// Parse every parameter IO from YAML text containing multiple documents
+    /// separated by `---`. Empty documents are skipped. Text with a single
+    /// document yields a single parameter IO.
+    pub fn from_multidoc_text(text: impl AsRef<str>) -> Result<Vec<Self>> {
+        let tree = Tree::parse(normalize_text(text.as_ref()))?;
+        let root_ref = tree.root_ref()?;
+        if !root_ref.is_stream()? {
+            return Ok(vec![read_parameter_io(&root_ref)?]);
+        }
+        let mut pios = Vec::with_capacity(root_ref.num_children()?);
+        for (i, doc) in root_ref.iter()?.enumerate() {
+            if doc.num_children()? == 0 {
+                continue;
+            }
+            let pio = read_parameter_io(&doc)
+                .map_err(|e| Error::InvalidDataD(format!("Invalid document {i}: {e}")))?;
+            pios.push(pio);
+        }
+        Ok(pios)
+    }
+
+    /// Serialize the parameter IO to YAML, with LF line endings and no byte
+    /// order mark. See [`ParameterIO::to_text_with_options`].
     pub fn to_text(&self) -> std::string::String {
+        self.to_text_with_options(&TextOptions::default())
+    }
+
+    /// Serialize the parameter IO to YAML using the given options.
+    ///
+    /// The output is always UTF-8 without a byte order mark and uses LF line
+    /// endings on every platform, so it can be checksummed and committed to
+    /// version control without line ending churn. Carriage returns in string
+    /// values are kept, written as `\r` escapes in double-quoted strings.
+    pub fn to_text_with_options(&self, options: &TextOptions) -> std::string::String {
+        self.to_text_with_name_table(options, get_default_name_table())
+    }
+
+    /// Serialize the parameter IO to YAML using the given options, resolving
+    /// names with the given name table instead of the default one. This
+    /// allows a conversion job, such as converting every parameter archive
+    /// in a SARC, to use its own isolated set of names (for example, names
+    /// learned from the archive's own contents), without being affected by
+    /// or adding guesses to the table other conversions share.
+    ///
+    /// ```
+    /// # use roead::{aamp::*, params};
+    /// let table = NameTable::new(false);
+    /// table.add_name("Modded");
+    /// let pio = ParameterIO::new().with_object("Modded", params!("Damage" => Parameter::I32(10)));
+    /// let text = pio.to_text_with_name_table(&TextOptions::default(), &table);
+    /// assert!(text.contains("Modded:"));
+    /// // The table has no BOTW strings, so `Damage` is written as its hash.
+    /// assert!(!text.contains("Damage"));
+    /// ```
+    pub fn to_text_with_name_table(
+        &self,
+        options: &TextOptions,
+        table: &NameTable,
+    ) -> std::string::String {
+        let mut tree = Tree::default();
+        tree.reserve(10000);
+        write_text(&mut tree, self, options, table, None)
+            .expect("ParameterIO should serialize to YAML without error")
+            .0
+    }
+
+    /// Serialize the parameter IO to YAML using the given options, also
+    /// returning the hashes of every list, object, and parameter name which
+    /// could not be resolved and was written as a number, sorted and without
+    /// duplicates. This helps with building name dictionaries: convert a
+    /// file, look up the unresolved names, add them to the name table, and
+    /// repeat.
+    ///
+    /// ```
+    /// # use roead::{aamp::*, params};
+    /// let pio = ParameterIO::new().with_object(
+    ///     "Root",
+    ///     params!("Damage" => Parameter::I32(10), 0x12345678u32 => Parameter::I32(5)),
+    /// );
+    /// let (text, unresolved) = pio.to_text_with_unresolved(&TextOptions::default());
+    /// assert!(text.contains("305419896:"));
+    /// assert_eq!(unresolved, [0x12345678]);
+    /// ```
+    pub fn to_text_with_unresolved(
+        &self,
+        options: &TextOptions,
+    ) -> (std::string::String, Vec<u32>) {
         let mut tree = Tree::default();
         tree.reserve(10000);
-        write_parameter_io(&mut tree, self)
-            .expect("ParameterIO should serialize to YAML without error");
-        tree.emit()
+        write_text(&mut tree, self, options, get_default_name_table(), None)
             .expect("ParameterIO should serialize to YAML without error")
     }
+
+    /// Serialize the parameter IO to YAML using the given options, letting
+    /// `formatter` override how individual parameters are written. It is
+    /// called with the path of each parameter (as with
+    /// [`TextOptions::annotations`]) and the parameter, and returns `None` to
+    /// use the default formatting. This allows context-aware exports, such
+    /// as showing the name of an enum value for a known `U32` field.
+    ///
+    /// Returns an error if the formatter returns a
+    /// [`FormattedValue::Scalar`] which does not parse back to the original
+    /// parameter.
+    ///
+    /// ```
+    /// # use roead::{aamp::*, params};
+    /// let pio = ParameterIO::new().with_object("Root", params!("Element" => Parameter::U32(2)));
+    /// let text = pio
+    ///     .to_text_with_formatter(&TextOptions::default(), |path, param| {
+    ///         match (path, param) {
+    ///             ("Root/Element", Parameter::U32(2)) => {
+    ///                 Some(FormattedValue::Display("Fire".into()))
+    ///             }
+    ///             _ => None,
+    ///         }
+    ///     })
+    ///     .unwrap();
+    /// assert!(text.contains("Element: Fire"));
+    /// ```
+    pub fn to_text_with_formatter<F>(
+        &self,
+        options: &TextOptions,
+        mut formatter: F,
+    ) -> Result<std::string::String>
+    where
+        F: FnMut(&str, &Parameter) -> Option<FormattedValue>,
+    {
+        let mut tree = Tree::default();
+        tree.reserve(10000);
+        write_text(
+            &mut tree,
+            self,
+            options,
+            get_default_name_table(),
+            Some(&mut formatter),
+        )
+        .map(|(text, _)| text)
+    }
 }
 
-#[inline(always)]
-fn recognize_tag(tag: &str) -> Option<TagBasedType> {
-    match tag {
-        "!str32" | "!str64" | "!str256" => Some(TagBasedType::Str),
-        "!u" => Some(TagBasedType::Int),
-        _ => None,
+/// A custom value for a parameter, returned by the formatter passed to
+/// [`ParameterIO::to_text_with_formatter`].
+///
+/// This is only available with the `yaml` feature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormattedValue {
+    /// A scalar value with an optional tag (e.g. `!u`), which must parse
+    /// back to the original parameter, so the output is still a valid
+    /// parameter IO. For example, a `U32` may be written in decimal as `2`
+    /// with the tag `!u`.
+    Scalar {
+        /// The scalar value.
+        value: std::string::String,
+        /// The tag, if any.
+        tag:   Option<std::string::String>,
+    },
+    /// A value for display only, written without a tag and not checked, so
+    /// the output may no longer parse back to the same parameter IO.
+    Display(std::string::String),
+}
+
+type Formatter<'f> = &'f mut dyn FnMut(&str, &Parameter) -> Option<FormattedValue>;
+
+/// Writer for serializing many parameter IOs to YAML, which reuses the same
+/// YAML tree for each document instead of allocating a new one every time
+/// as [`ParameterIO::to_text_with_options`] does. The output is identical.
+///
+/// This is only available with the `yaml` feature.
+///
+/// ```
+/// # use roead::{aamp::*, params};
+/// let mut writer = TextWriter::new(TextOptions::default());
+/// let pio = ParameterIO::new().with_object("Root", params!("Damage" => Parameter::I32(10)));
+/// for _ in 0..3 {
+///     assert_eq!(writer.write(&pio), pio.to_text());
+/// }
+/// ```
+pub struct TextWriter {
+    tree: Tree<'static>,
+    options: TextOptions,
+}
+
+impl std::fmt::Debug for TextWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextWriter")
+            .field("options", &self.options)
+            .finish_non_exhaustive()
     }
 }
 
-fn scalar_to_value(tag: &str, scalar: Scalar) -> Result<Parameter> {
-    Ok(match scalar {
-        Scalar::String(s) => {
-            match tag {
-                "!str32" => Parameter::String32(s.into()),
-                "!str64" => Parameter::String64(Box::new(s.into())),
-                "!str256" => Parameter::String256(Box::new(s.into())),
-                _ => Parameter::StringRef(s),
-            }
-        }
-        Scalar::Int(i) => {
-            if tag == "!u" {
-                Parameter::U32(i as u32)
-            } else {
-                Parameter::I32(i as i32)
-            }
-        }
-        Scalar::Float(f) => Parameter::F32(f as f32),
-        Scalar::Bool(b) => Parameter::Bool(b),
-        Scalar::Null => {
-            match tag {
-                "!str32" => Parameter::String32(Default::default()),
-                "!str64" => Parameter::String64(Default::default()),
-                "!str256" => Parameter::String256(Default::default()),
-                _ => Parameter::StringRef(Default::default()),
-            }
-        }
-    })
+impl Default for TextWriter {
+    fn default() -> Self {
+        Self::new(TextOptions::default())
+    }
 }
 
-#[inline(always)]
-fn parse_num<'a, 't, T>(node: &NodeRef<'a, 't, '_, &'t Tree<'a>>) -> Result<T>
-where
-    T: FromLexicalWithOptions + FromLexical,
-{
-    let val = node.val()?;
-    match T::from_lexical(val.as_bytes()) {
-        Ok(v) => Ok(v),
-        Err(_) => {
-            let opts = T::Options::default();
-            Ok(T::from_lexical_with_options::<
-                { lexical::NumberFormatBuilder::hexadecimal() },
-            >(
-                val.trim_start_matches("0x").as_bytes(), &opts
-            )?)
-        }
+impl TextWriter {
+    /// Create a writer which serializes with the given options.
+    pub fn new(options: TextOptions) -> Self {
+        let mut tree = Tree::default();
+        tree.reserve(10000);
+        Self { tree, options }
+    }
+
+    /// Get the options used by the writer.
+    pub fn options(&self) -> &TextOptions {
+        &self.options
+    }
+
+    /// Get mutable access to the options used by the writer.
+    pub fn options_mut(&mut self) -> &mut TextOptions {
+        &mut self.options
+    }
+
+    /// Serialize a parameter IO to YAML, as with
+    /// [`ParameterIO::to_text_with_options`].
+    pub fn write(&mut self, pio: &ParameterIO) -> std::string::String {
+        self.write_with_name_table(pio, get_default_name_table())
+    }
+
+    /// Serialize a parameter IO to YAML using the given name table, as with
+    /// [`ParameterIO::to_text_with_name_table`].
+    pub fn write_with_name_table(
+        &mut self,
+        pio: &ParameterIO,
+        table: &NameTable,
+    ) -> std::string::String {
+        self.tree.clear();
+        self.tree.clear_arena();
+        write_text(&mut self.tree, pio, &self.options, table, None)
+            .expect("ParameterIO should serialize to YAML without error")
+            .0
     }
 }
 
-macro_rules! impl_from_node_for_struct {
-    ($type:tt, $($field:tt),+) => {
-        impl<'a, 't, 'k, 'r> TryFrom<&'r NodeRef<'a, 't, 'k, &'t Tree<'a>>> for $type {
-            type Error = Error;
-            fn try_from(node: &'r NodeRef<'a, 't, 'k, &'t Tree<'a>>) -> Result<Self>
-            {
-                let mut iter = node.iter()?;
-                let result = $type {
-                    $(
-                        $field: parse_num(
-                            &iter.next()
-                                .ok_or(Error::InvalidData(concat!(stringify!($type), " missing field", stringify!($field))))?
-                        )?,
-                    )+
-                };
-                Ok(result)
-            }
-        }
+/// Serializes a parameter IO into an empty tree and emits it as text, along
+/// with the hashes of the names which could not be resolved.
+fn write_text<'o>(
+    tree: &mut Tree<'_>,
+    pio: &ParameterIO,
+    options: &'o TextOptions,
+    table: &'o NameTable,
+    formatter: Option<Formatter<'o>>,
+) -> Result<(std::string::String, Vec<u32>)> {
+    let (hex_dumps, mut unresolved) = write_parameter_io(tree, pio, options, table, formatter)?;
+    unresolved.sort_unstable();
+    unresolved.dedup();
+    let text = normalize_output(tree.emit()?);
+    let text = match options.buffer_items_per_line {
+        Some(per_line) => wrap_buffers(&text, per_line),
+        None => text,
     };
+    let mut annotations = Cow::Borrowed(&options.annotations);
+    for (path, dump) in hex_dumps {
+        annotations
+            .to_mut()
+            .entry(path)
+            .and_modify(|comment| {
+                comment.push('\n');
+                comment.push_str(&dump);
+            })
+            .or_insert(dump);
+    }
+    if annotations.is_empty() {
+        Ok((text, unresolved))
+    } else {
+        Ok((
+            insert_annotations(&text, &annotations, options.fragment),
+            unresolved,
+        ))
+    }
 }
-impl_from_node_for_struct!(Vector2f, x, y);
-impl_from_node_for_struct!(Vector3f, x, y, z);
-impl_from_node_for_struct!(Vector4f, x, y, z, t);
-impl_from_node_for_struct!(Quat, a, b, c, d);
-impl_from_node_for_struct!(Color, r, g, b, a);
-
-fn read_curves<'a, 't, const N: usize>(
-    node: &NodeRef<'a, 't, '_, &'t Tree<'a>>,
-) -> Result<[Curve; N]> {
-    let mut iter = node.iter()?;
-    let mut curves = [Curve::default(); N];
-    for curve in &mut curves {
-        curve.a = parse_num(
-            &iter
-                .next()
-                .ok_or(Error::InvalidData("YAML curve missing a"))?,
-        )?;
-        curve.b = parse_num(
-            &iter
-                .next()
-                .ok_or(Error::InvalidData("YAML curve missing a"))?,
-        )?;
-        for f in &mut curve.floats {
-            *f = parse_num(
-                &iter
-                    .next()
-                    .ok_or(Error::InvalidData("YAML curve missing a float"))?,
-            )?;
-        }
+
+impl ParameterList {
+    /// Parse a parameter list from YAML text, such as a fragment written with
+    /// [`TextOptions::fragment`]. The document is read like the `param_root`
+    /// of a full parameter IO.
+    pub fn from_text(text: impl AsRef<str>) -> Result<Self> {
+        let tree = Tree::parse(normalize_text(text.as_ref()))?;
+        let root_ref = tree.root_ref()?;
+        read_parameter_list(&root_ref)
     }
-    Ok(curves)
 }
 
-#[inline(always)]
-fn read_buf<'a, 't, T: FromLexical + FromLexicalWithOptions>(
-    node: &NodeRef<'a, 't, '_, &'t Tree<'a>>,
-) -> Result<Vec<T>> {
-    node.iter()?
-        .map(|node| parse_num(&node))
-        .collect::<Result<_>>()
+/// Options for serializing parameter IOs to YAML with
+/// [`ParameterIO::to_text_with_options`].
+///
+/// This is only available with the `yaml` feature.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextOptions {
+    /// Resolve names using only the names which were added to the default
+    /// name table and guesses made for this document, ignoring names which
+    /// the table guessed while handling other documents. The output then
+    /// does not depend on what else the process has serialized, which is
+    /// useful for reproducible diffs. Guessed names are not recorded in the
+    /// table. Defaults to `false`.
+    pub deterministic_names: bool,
+    /// Only write names which were added to the default name table, and
+    /// write every other name as its hash, without guessing names from the
+    /// index or parent of a structure. This avoids plausible but wrong
+    /// guesses in the output. Takes precedence over
+    /// [`deterministic_names`](Self::deterministic_names). Defaults to
+    /// `false`.
+    pub known_names_only: bool,
+    /// Write `U32` parameters below this value in decimal rather than
+    /// hexadecimal, which is easier to read for counts and indices. They are
+    /// still tagged `!u`, so they parse back as `U32`. Buffers are always
+    /// written in hexadecimal. Defaults to `None` (always hexadecimal).
+    pub decimal_u32_below: Option<u32>,
+    /// Comments to write above parameters, objects, or lists, keyed by path
+    /// (e.g. `AI/Root/Damage`). Paths use the names as they are written in
+    /// the output, so unknown names are written as hashes. Comments may span
+    /// multiple lines. They are ignored when the text is parsed again.
+    pub annotations: FxHashMap<String, std::string::String>,
+    /// Write only the contents of `param_root` as a `!list` document,
+    /// omitting the `!io` header with the version and data type. This is
+    /// useful for fragments embedded in other documents, which can be read
+    /// back with [`ParameterList::from_text`]. Defaults to `false`.
+    pub fragment: bool,
+    /// Write a hex dump with offsets and ASCII (see [`Parameter::hex_dump`])
+    /// as a comment above each `BufferBinary` parameter, after any
+    /// annotation for it. The buffer itself is still written as a list of
+    /// bytes, so the dump is for display only and the text parses the same.
+    /// Defaults to `false`.
+    pub hex_dump_binary: bool,
+    /// Wrap buffers (and raw strings) after this many elements per line,
+    /// keeping them as flow sequences in brackets. Large buffers are then
+    /// still compact, but readable and diffable. Wrapped buffers parse back
+    /// to the same values. Defaults to `None` (never wrap).
+    pub buffer_items_per_line: Option<usize>,
+    /// Write `BufferBinary` parameters as a single base64 string tagged
+    /// `!buffer_binary_b64` instead of a list of bytes, which is far more
+    /// compact for large blobs. Both forms are always accepted when parsing.
+    /// Defaults to `false`.
+    pub base64_binary: bool,
+    /// The name of the root list, which is used as the parent name when
+    /// guessing the names of top-level lists and objects. Binary parameter
+    /// archives always name it `param_root`, but when writing a
+    /// [`fragment`](Self::fragment) which was taken from further down a
+    /// document, setting this to the name of the list it came from (e.g.
+    /// `Children`) lets its children be named the same way as in the source
+    /// file. Defaults to `None` (`param_root`).
+    pub root_name: Option<String>,
+    /// The original text of floats in parameters, keyed by path (as with
+    /// [`annotations`](Self::annotations)), as returned by
+    /// [`ParameterIO::from_text_with_float_formats`]. Each float which still
+    /// has the value its original text parses to is written as that text
+    /// instead of roead's canonical form (e.g. `1.50` instead of `1.5`), so
+    /// editing a document only changes the lines which were edited. Defaults
+    /// to empty.
+    pub float_formats: FxHashMap<String, Vec<std::string::String>>,
 }
 
-fn parse_parameter<'a, 't>(node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>) -> Result<Parameter> {
-    if !node.is_valid() {
-        return Err(Error::InvalidData("Invalid YAML node for parameter"));
-    }
-    let tag = node.val_tag().unwrap_or("");
-    let param = if node.is_seq()? {
-        match tag {
-            "!vec2" => Vector2f::try_from(node)?.into(),
-            "!vec3" => Vector3f::try_from(node)?.into(),
-            "!vec4" => Vector4f::try_from(node)?.into(),
-            "!quat" => Quat::try_from(node)?.into(),
-            "!color" => Color::try_from(node)?.into(),
-            "!curve" => {
-                match node.num_children()? {
-                    32 => read_curves::<1>(node)?.into(),
-                    64 => read_curves::<2>(node)?.into(),
-                    96 => read_curves::<3>(node)?.into(),
-                    128 => read_curves::<4>(node)?.into(),
-                    _ => return Err(Error::InvalidData("Invalid curve: wrong number of values")),
-                }
-            }
-            "!buffer_int" => read_buf::<i32>(node)?.into(),
-            "!buffer_f32" => read_buf::<f32>(node)?.into(),
-            "!buffer_u32" => read_buf::<u32>(node)?.into(),
-            "!buffer_binary" => read_buf::<u8>(node)?.into(),
-            _ => {
-                return Err(Error::InvalidData(
-                    "Invalid parameter: sequence without known tag",
-                ));
+/// Options for parsing parameter IOs from YAML with
+/// [`ParameterIO::from_text_with_options`].
+///
+/// This is only available with the `yaml` feature.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Also accept `True`, `TRUE`, `yes`, `Yes`, and `YES` (and the
+    /// equivalent spellings of `false`) as booleans, as written by some other
+    /// tools. Quoted values are always strings. Note that unquoted strings
+    /// with these values, which roead writes without quotes, are then read
+    /// as booleans. Defaults to `false` (only `true` and `false`).
+    pub lenient_bools: bool,
+    /// Return an error naming the tag for any parameter with an unrecognized
+    /// tag, such as `!mytag foo`. By default, unknown tags on scalars are
+    /// ignored and the type is inferred from the value as if it were
+    /// untagged. Sequences have no untagged form, so an unknown tag on a
+    /// sequence is always an error. Defaults to `false`.
+    pub strict_tags: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+    Root,
+    List,
+    Objects,
+    Lists,
+    Object,
+    Other,
+}
+
+/// Returns `true` if the text has an unterminated double-quoted string.
+fn has_open_quote(text: &str) -> bool {
+    let mut open = false;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if open => {
+                chars.next();
             }
+            '"' => open = !open,
+            _ => (),
         }
+    }
+    open
+}
+
+/// Splits a block mapping line into its key and the remainder after the
+/// colon, or returns `None` if the line has no key.
+fn split_key(line: &str) -> Option<(&str, &str)> {
+    if let Some(quoted) = line.strip_prefix('"') {
+        let end = quoted.find('"')?;
+        let rest = quoted[end + 1..].strip_prefix(':')?;
+        Some((&quoted[..end], rest))
     } else {
-        let tag_type = recognize_tag(tag).or_else(|| get_tag_based_type(tag));
-        scalar_to_value(tag, parse_scalar(tag_type, node.val()?, node.is_quoted()?)?)?
-    };
-    Ok(param)
+        let end = line
+            .match_indices(':')
+            .map(|(i, _)| i)
+            .find(|i| matches!(line.as_bytes().get(i + 1), None | Some(b' ')))?;
+        Some((&line[..end], &line[end + 1..]))
+    }
 }
 
-#[rustfmt::skip]
-macro_rules! read_map {
-    ($node:expr, $m:expr, $fn:expr) => {
-        if !$node.is_map()? {
-            return Err(Error::InvalidData("Expected map node"));
-        }
+/// Tags of parameters written as flow sequences of plain numbers.
+const BUFFER_TAGS: &[&str] = &[
+    "!buffer_int",
+    "!buffer_f32",
+    "!buffer_u32",
+    "!buffer_binary",
+    "!str_raw",
+];
 
-        for child in $node.iter()? {
-            let key = child.key()?;
-            let value = $fn(&child)?;
-            if !$node.is_key_quoted()? {
-                if let Ok(hash) = lexical::parse::<u64, &str>(key) {
-                    $m.insert(hash as u32, value);
-                    continue;
+/// Wraps buffers in emitted YAML after every `per_line` elements. Buffer
+/// elements are plain numbers, so the flow sequences can be split on commas,
+/// and continuation lines are indented past the key to stay inside the
+/// sequence.
+fn wrap_buffers(text: &str, per_line: usize) -> std::string::String {
+    let per_line = per_line.max(1);
+    let mut out = std::string::String::with_capacity(text.len() + text.len() / 16);
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        let seq = split_key(trimmed).and_then(|(_, rest)| {
+            let rest = rest.trim_start();
+            let (tag, seq) = rest.split_once(' ')?;
+            (BUFFER_TAGS.contains(&tag) && seq.starts_with('[') && seq.ends_with(']'))
+                .then_some(seq)
+        });
+        let Some(seq) = seq.filter(|seq| seq.matches(',').count() >= per_line) else {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        };
+        out.push_str(&line[..line.len() - seq.len() + 1]);
+        let items = seq[1..seq.len() - 1].split(',').map(str::trim);
+        for (i, item) in items.enumerate() {
+            if i > 0 {
+                if i % per_line == 0 {
+                    out.push_str(",\n");
+                    out.push_str(&line[..indent]);
+                    out.push_str("  ");
+                } else {
+                    out.push_str(", ");
                 }
             }
-            $m.insert(hash_name(key), value);
+            out.push_str(item);
         }
-    };
+        out.push_str("]\n");
+    }
+    out
 }
 
-fn read_parameter_object<'a, 't>(
-    node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>,
-) -> Result<ParameterObject> {
-    if !node.is_valid() {
-        return Err(Error::InvalidData("Invalid YAML node for parameter object"));
+/// Inserts comments into emitted YAML above the keys with the annotated
+/// paths. The emitter does not support comments, so this follows the block
+/// structure of the output instead. Fragments start at the root list rather
+/// than at the `!io` header.
+fn insert_annotations(
+    text: &str,
+    annotations: &FxHashMap<String, std::string::String>,
+    fragment: bool,
+) -> std::string::String {
+    let root_kind = if fragment {
+        NodeKind::List
+    } else {
+        NodeKind::Root
+    };
+    let mut out = std::string::String::with_capacity(text.len());
+    let mut stack: Vec<(usize, NodeKind, Option<&str>)> = Vec::new();
+    let mut in_quote = false;
+    for line in text.lines() {
+        if in_quote {
+            in_quote = !has_open_quote(line);
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        }
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        if let Some((key, rest)) = split_key(trimmed) {
+            while stack.last().is_some_and(|(i, ..)| *i >= indent) {
+                stack.pop();
+            }
+            let parent = stack.last().map_or(root_kind, |(_, kind, _)| *kind);
+            let (kind, segment) = match (parent, key) {
+                (NodeKind::Root, "param_root") => (NodeKind::List, None),
+                (NodeKind::List, "objects") => (NodeKind::Objects, None),
+                (NodeKind::List, "lists") => (NodeKind::Lists, None),
+                (NodeKind::Objects, _) => (NodeKind::Object, Some(key)),
+                (NodeKind::Lists, _) => (NodeKind::List, Some(key)),
+                (NodeKind::Object, _) => (NodeKind::Other, Some(key)),
+                _ => (NodeKind::Other, None),
+            };
+            stack.push((indent, kind, segment));
+            if segment.is_some() {
+                let path = stack
+                    .iter()
+                    .filter_map(|(.., segment)| *segment)
+                    .collect::<Vec<_>>()
+                    .join("/");
+                if let Some(comment) = annotations.get(path.as_str()) {
+                    for comment_line in comment.lines() {
+                        out.push_str(&line[..indent]);
+                        out.push_str("# ");
+                        out.push_str(comment_line);
+                        out.push('\n');
+                    }
+                }
+            }
+            in_quote = has_open_quote(rest);
+        }
+        out.push_str(line);
+        out.push('\n');
     }
-    let mut param_object = ParameterObject::default();
-    read_map!(node, param_object, parse_parameter);
-    Ok(param_object)
+    out
 }
 
-fn read_parameter_list<'a, 't>(
-    node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>,
-) -> Result<ParameterList> {
-    if !node.is_valid() {
-        return Err(Error::InvalidData("Invalid YAML node for parameter list"));
-    }
-    let mut param_list = ParameterList::default();
-    let lists = node.get("lists")?;
-    let objects = node.get("objects")?;
-    read_map!(&objects, param_list.objects, read_parameter_object);
-    read_map!(&lists, param_list.lists, read_parameter_list);
-    Ok(param_list)
+/// Formats the floats of a parameter in order, reusing their original text
+/// from [`TextOptions::float_formats`] while it still matches the value.
+#[derive(Default)]
+struct FloatFormatter<'f> {
+    originals: std::slice::Iter<'f, std::string::String>,
 }
 
-fn read_parameter_io<'a, 't>(node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>) -> Result<ParameterIO> {
-    if !node.is_valid() {
-        return Err(Error::InvalidData("Invalid YAML node for parameter IO"));
-    }
-    let pio = ParameterIO {
-        version: {
-            let ver = node.get("version")?;
-            parse_num(&ver)?
-        },
-        data_type: {
-            let dt = node.get("type")?;
-            dt.val()?.into()
-        },
-        param_root: {
-            let pr = node.get("param_root")?;
-            read_parameter_list(&pr)?
-        },
-    };
-    Ok(pio)
+impl FloatFormatter<'_> {
+    fn format(&mut self, value: f32) -> std::string::String {
+        match self.originals.next() {
+            Some(text)
+                if lexical::parse::<f32, _>(text.trim())
+                    .is_ok_and(|v| v.to_bits() == value.to_bits()) =>
+            {
+                text.trim().into()
+            }
+            _ => lexical::to_string(value),
+        }
+    }
 }
 
 macro_rules! fill_node_from_struct {
-    ($node:expr, $tag:literal, $struct:expr, $($field:tt),+) => {{
+    ($node:expr, $floats:expr, $tag:literal, $struct:expr, $($field:tt),+) => {{
         $node.change_type(ryml::NodeType::Seq | ryml::NodeType::WipStyleFlowSl)?;
         $(
             let mut _child = $node.append_child()?;
-            _child.set_val(&lexical::to_string($struct.$field))?;
+            _child.set_val(&$floats.format($struct.$field))?;
         )+
         $node.set_val_tag($tag)?;
     }};
@@ -262,6 +629,7 @@ macro_rules! fill_node_from_struct {
 fn write_curves<'a, 't, const N: usize>(
     mut node: NodeRef<'a, 't, '_, &'t mut Tree<'a>>,
     curves: &[Curve; N],
+    floats: &mut FloatFormatter,
 ) -> Result<()> {
     node.change_type(ryml::NodeType::Seq | ryml::NodeType::WipStyleFlowSl)?;
     for curve in curves {
@@ -271,7 +639,7 @@ fn write_curves<'a, 't, const N: usize>(
         b.set_val(&lexical::to_string(curve.b))?;
         for float in curve.floats {
             let mut f = node.append_child()?;
-            f.set_val(&lexical::to_string(float))?;
+            f.set_val(&floats.format(float))?;
         }
     }
     node.set_val_tag("!curve")?;
@@ -283,7 +651,7 @@ fn write_buf<'a, 't, T: ToLexical + ToLexicalWithOptions>(
     mut node: NodeRef<'a, 't, '_, &'t mut Tree<'a>>,
     buf: &[T],
     use_hex: bool,
-    tag: &str,
+    tag: &'static str,
 ) -> Result<()> {
     node.change_type(ryml::NodeType::Seq | ryml::NodeType::WipStyleFlowSl)?;
     for val in buf {
@@ -300,17 +668,19 @@ fn write_buf<'a, 't, T: ToLexical + ToLexicalWithOptions>(
 }
 
 fn write_parameter<'a, 't>(
+    options: &TextOptions,
     param: &Parameter,
+    mut floats: FloatFormatter,
     mut node: NodeRef<'a, 't, '_, &'t mut Tree<'a>>,
 ) -> Result<()> {
     match param {
         Parameter::Bool(b) => node.set_val(if *b { "true" } else { "false" })?,
-        Parameter::F32(f) => node.set_val(&lexical::to_string(*f))?,
+        Parameter::F32(f) => node.set_val(&floats.format(*f))?,
         Parameter::I32(i) => node.set_val(&lexical::to_string(*i))?,
-        Parameter::Vec2(v) => fill_node_from_struct!(node, "!vec2", v, x, y),
-        Parameter::Vec3(v) => fill_node_from_struct!(node, "!vec3", v, x, y, z),
-        Parameter::Vec4(v) => fill_node_from_struct!(node, "!vec4", v, x, y, z, t),
-        Parameter::Color(c) => fill_node_from_struct!(node, "!color", c, r, g, b, a),
+        Parameter::Vec2(v) => fill_node_from_struct!(node, floats, "!vec2", v, x, y),
+        Parameter::Vec3(v) => fill_node_from_struct!(node, floats, "!vec3", v, x, y, z),
+        Parameter::Vec4(v) => fill_node_from_struct!(node, floats, "!vec4", v, x, y, z, t),
+        Parameter::Color(c) => fill_node_from_struct!(node, floats, "!color", c, r, g, b, a),
         Parameter::String32(s) => {
             node.set_val(s)?;
             node.set_val_tag("!str32")?;
@@ -319,31 +689,46 @@ fn write_parameter<'a, 't>(
             node.set_val(s)?;
             node.set_val_tag("!str64")?;
         }
-        Parameter::Curve1(c) => write_curves(node, c)?,
-        Parameter::Curve2(c) => write_curves(node, c)?,
-        Parameter::Curve3(c) => write_curves(node, c)?,
-        Parameter::Curve4(c) => write_curves(node, c)?,
+        Parameter::Curve1(c) => write_curves(node, c, &mut floats)?,
+        Parameter::Curve2(c) => write_curves(node, c, &mut floats)?,
+        Parameter::Curve3(c) => write_curves(node, c, &mut floats)?,
+        Parameter::Curve4(c) => write_curves(node, c, &mut floats)?,
         Parameter::BufferInt(buf) => {
             write_buf(node, buf, false, "!buffer_int")?;
         }
         Parameter::BufferF32(buf) => {
-            write_buf(node, buf, false, "!buffer_f32")?;
+            node.change_type(ryml::NodeType::Seq | ryml::NodeType::WipStyleFlowSl)?;
+            for val in buf {
+                node.append_child()?.set_val(&floats.format(*val))?;
+            }
+            node.set_val_tag("!buffer_f32")?;
         }
         Parameter::String256(s) => {
             node.set_val(s)?;
             node.set_val_tag("!str256")?;
         }
-        Parameter::Quat(q) => fill_node_from_struct!(node, "!quat", q, a, b, c, d),
+        Parameter::Quat(q) => fill_node_from_struct!(node, floats, "!quat", q, a, b, c, d),
         Parameter::U32(u) => {
-            node.set_val(&format_hex!(u))?;
+            if options.decimal_u32_below.is_some_and(|limit| *u < limit) {
+                node.set_val(&lexical::to_string(*u))?;
+            } else {
+                node.set_val(&format_hex!(u))?;
+            }
             node.set_val_tag("!u")?;
         }
         Parameter::BufferU32(buf) => {
             write_buf(node, buf, true, "!buffer_u32")?;
         }
+        Parameter::BufferBinary(buf) if options.base64_binary => {
+            node.set_val(&base64::engine::general_purpose::STANDARD.encode(buf))?;
+            node.set_val_tag("!buffer_binary_b64")?;
+        }
         Parameter::BufferBinary(buf) => {
             write_buf(node, buf, true, "!buffer_binary")?;
         }
+        Parameter::StringRefRaw(buf) => {
+            write_buf(node, buf, true, "!str_raw")?;
+        }
         Parameter::StringRef(s) => {
             if string_needs_quotes(s) {
                 let ty = node.node_type()?;
@@ -355,32 +740,174 @@ fn write_parameter<'a, 't>(
     Ok(())
 }
 
+/// Writes a value returned by a formatter, checking that scalars parse back
+/// to the original parameter.
+fn write_formatted<'a, 't>(
+    path: &str,
+    param: &Parameter,
+    value: FormattedValue,
+    mut node: NodeRef<'a, 't, '_, &'t mut Tree<'a>>,
+) -> Result<()> {
+    match value {
+        FormattedValue::Scalar { value, tag } => {
+            let text = match &tag {
+                Some(tag) => jstr!("{tag} {&value}"),
+                None => value.clone(),
+            };
+            let tree = Tree::parse(&text)?;
+            if parse_parameter(&tree.root_ref()?).ok().as_ref() != Some(param) {
+                return Err(Error::InvalidDataD(jstr!(
+                    "Formatted value `{&text}` for `{path}` does not parse back to the parameter"
+                )));
+            }
+            if let Some(tag) = tag {
+                // ryml only stores a pointer to the tag, so copy it into the
+                // tree's arena first by setting it as the value, which is
+                // overwritten below.
+                node.set_val(&tag)?;
+                let tag: *const str = node.val()?;
+                // SAFETY: The tag is now in the arena, and ryml updates tags
+                // which point into the arena if it is reallocated.
+                node.set_val_tag(unsafe { &*tag })?;
+            }
+            node.set_val(&value)?;
+        }
+        FormattedValue::Display(text) => node.set_val(&text)?,
+    }
+    Ok(())
+}
+
+/// State for writing a single document.
+struct WriteContext<'o, 'n> {
+    options: &'o TextOptions,
+    table: &'o NameTable<'n>,
+    names: NameCache<'o>,
+    formatter: Option<Formatter<'o>>,
+    /// Path of the structure being written, only tracked for hex dumps,
+    /// float formats, and formatters.
+    path: Vec<std::string::String>,
+    hex_dumps: FxHashMap<String, std::string::String>,
+    /// Hashes of the names which were written as numbers.
+    unresolved: Vec<u32>,
+}
+
+impl WriteContext<'_, '_> {
+    fn tracks_path(&self) -> bool {
+        self.options.hex_dump_binary
+            || !self.options.float_formats.is_empty()
+            || self.formatter.is_some()
+    }
+
+    fn push_path(&mut self, hash: u32, name: Option<&str>) {
+        if self.tracks_path() {
+            self.path
+                .push(name.map_or_else(|| hash.to_string(), Into::into));
+        }
+    }
+
+    fn pop_path(&mut self) {
+        self.path.pop();
+    }
+}
+
+/// Resolves the name for a structure or parameter key, returning `None` if it
+/// is unknown. The parent name is only used for deterministic names, since
+/// the name table otherwise looks it up by hash.
+fn resolve_name<'o>(
+    ctx: &mut WriteContext<'o, '_>,
+    hash: u32,
+    index: usize,
+    parent_hash: u32,
+    parent_name: Option<&str>,
+) -> Option<Cow<'o, str>> {
+    let table = ctx.table;
+    if ctx.options.known_names_only {
+        table.get_added_name(hash).map(Cow::Borrowed)
+    } else if ctx.options.deterministic_names {
+        let key = (hash, index, parent_hash, parent_name.is_some());
+        ctx.names.get_or_resolve(key, || {
+            table
+                .get_added_name(hash)
+                .map(Cow::Borrowed)
+                .or_else(|| table.guess_name(hash, index, parent_name).map(Cow::Owned))
+        })
+    } else if let Some(name) = table.get_known_name(hash) {
+        // The table may have learned the name since it was last looked up, so
+        // only failed guesses are reused.
+        Some(Cow::Borrowed(name))
+    } else {
+        ctx.names
+            .get_or_resolve((hash, index, parent_hash, false), || {
+                table
+                    .get_name(hash, index, parent_hash)
+                    .map(|name| Cow::Borrowed(&**name))
+            })
+    }
+}
+
+fn write_key<'a, 't, 'o>(
+    ctx: &mut WriteContext<'o, '_>,
+    node: &mut NodeRef<'a, 't, '_, &'t mut Tree<'a>>,
+    hash: u32,
+    index: usize,
+    parent_hash: u32,
+    parent_name: Option<&str>,
+) -> Result<Option<Cow<'o, str>>> {
+    let name = resolve_name(ctx, hash, index, parent_hash, parent_name);
+    match &name {
+        Some(name) => {
+            if lexical::parse::<u64, _>(name.as_bytes()).is_ok() {
+                let ty = node.node_type()?;
+                node.set_type_flags(ty | ryml::NodeType::WipKeyDquo)?;
+            }
+            node.set_key(name)?;
+        }
+        None => {
+            node.set_key(&lexical::to_string(hash))?;
+            ctx.unresolved.push(hash);
+        }
+    }
+    Ok(name)
+}
+
 fn write_parameter_object<'a, 't>(
+    ctx: &mut WriteContext,
     pobj: &ParameterObject,
     parent_hash: u32,
+    parent_name: Option<&str>,
     mut node: NodeRef<'a, 't, '_, &'t mut Tree<'a>>,
 ) -> Result<()> {
     node.change_type(ryml::NodeType::Map)?;
     for (i, (key, val)) in pobj.0.iter().enumerate() {
         let mut child = node.append_child()?;
-        if let Some(name) = get_default_name_table().get_name(key.0, i, parent_hash) {
-            if lexical::parse::<u64, _>(name.as_bytes()).is_ok() {
-                let ty = child.node_type()?;
-                child.set_type_flags(ty | ryml::NodeType::WipKeyDquo)?;
+        let name = write_key(ctx, &mut child, key.0, i, parent_hash, parent_name)?;
+        let mut floats = FloatFormatter::default();
+        if ctx.tracks_path() {
+            ctx.push_path(key.0, name.as_deref());
+            let path = ctx.path.join("/");
+            ctx.pop_path();
+            if let Some(originals) = ctx.options.float_formats.get(path.as_str()) {
+                floats.originals = originals.iter();
+            }
+            if ctx.options.hex_dump_binary && matches!(val, Parameter::BufferBinary(_)) {
+                ctx.hex_dumps.insert(path.as_str().into(), val.hex_dump()?);
+            }
+            if let Some(value) = ctx.formatter.as_mut().and_then(|f| f(&path, val)) {
+                write_formatted(&path, val, value, child)?;
+                continue;
             }
-            child.set_key(name)?;
-        } else {
-            child.set_key(&lexical::to_string(key.0))?;
         }
-        write_parameter(val, child)?;
+        write_parameter(ctx.options, val, floats, child)?;
     }
     node.set_val_tag("!obj")?;
     Ok(())
 }
 
 fn write_parameter_list<'a, 't>(
+    ctx: &mut WriteContext,
     plist: &ParameterList,
     parent_hash: u32,
+    parent_name: Option<&str>,
     mut node: NodeRef<'a, 't, '_, &'t mut Tree<'a>>,
 ) -> Result<()> {
     node.change_type(ryml::NodeType::Map)?;
@@ -389,39 +916,50 @@ fn write_parameter_list<'a, 't>(
     objects.change_type(ryml::NodeType::Map)?;
     for (i, (key, val)) in plist.objects.0.iter().enumerate() {
         let mut child = objects.append_child()?;
-        if let Some(name) = get_default_name_table().get_name(key.0, i, parent_hash) {
-            if lexical::parse::<u64, _>(name.as_bytes()).is_ok() {
-                let ty = child.node_type()?;
-                child.set_type_flags(ty | ryml::NodeType::WipKeyDquo)?;
-            }
-            child.set_key(name)?;
-        } else {
-            child.set_key(&lexical::to_string(key.0))?;
-        }
-        write_parameter_object(val, key.0, child)?;
+        let name = write_key(ctx, &mut child, key.0, i, parent_hash, parent_name)?;
+        ctx.push_path(key.0, name.as_deref());
+        write_parameter_object(ctx, val, key.0, name.as_deref(), child)?;
+        ctx.pop_path();
     }
     let mut lists = node.append_child()?;
     lists.set_key("lists")?;
     lists.change_type(ryml::NodeType::Map)?;
     for (i, (key, val)) in plist.lists.0.iter().enumerate() {
         let mut child = lists.append_child()?;
-        if let Some(name) = get_default_name_table().get_name(key.0, i, parent_hash) {
-            if lexical::parse::<u64, _>(name.as_bytes()).is_ok() {
-                let ty = child.node_type()?;
-                child.set_type_flags(ty | ryml::NodeType::WipKeyDquo)?;
-            }
-            child.set_key(name)?;
-        } else {
-            child.set_key(&lexical::to_string(key.0))?;
-        }
-        write_parameter_list(val, key.0, child)?;
+        let name = write_key(ctx, &mut child, key.0, i, parent_hash, parent_name)?;
+        ctx.push_path(key.0, name.as_deref());
+        write_parameter_list(ctx, val, key.0, name.as_deref(), child)?;
+        ctx.pop_path();
     }
     node.set_val_tag("!list")?;
     Ok(())
 }
 
-fn write_parameter_io(tree: &mut Tree<'_>, pio: &ParameterIO) -> Result<()> {
+/// Writes the parameter IO, returning the hex dumps to insert as comments and
+/// the hashes of unresolved names.
+fn write_parameter_io<'o>(
+    tree: &mut Tree<'_>,
+    pio: &ParameterIO,
+    options: &'o TextOptions,
+    table: &'o NameTable,
+    formatter: Option<Formatter<'o>>,
+) -> Result<(FxHashMap<String, std::string::String>, Vec<u32>)> {
+    let mut ctx = WriteContext {
+        options,
+        table,
+        names: NameCache::default(),
+        formatter,
+        path: Vec::new(),
+        hex_dumps: FxHashMap::default(),
+        unresolved: Vec::new(),
+    };
+    let root_name = options.root_name.as_deref().unwrap_or("param_root");
+    let root_hash = hash_name(root_name);
     let mut root = tree.root_ref_mut()?;
+    if options.fragment {
+        write_parameter_list(&mut ctx, &pio.param_root, root_hash, Some(root_name), root)?;
+        return Ok((ctx.hex_dumps, ctx.unresolved));
+    }
     root.change_type(ryml::NodeType::Map)?;
     root.set_val_tag("!io")?;
     root.get_mut("version")?
@@ -429,8 +967,14 @@ fn write_parameter_io(tree: &mut Tree<'_>, pio: &ParameterIO) -> Result<()> {
     root.get_mut("type")?.set_val(&pio.data_type)?;
     let mut param_root = root.append_child()?;
     param_root.set_key("param_root")?;
-    write_parameter_list(&pio.param_root, ROOT_KEY.0, param_root)?;
-    Ok(())
+    write_parameter_list(
+        &mut ctx,
+        &pio.param_root,
+        root_hash,
+        Some(root_name),
+        param_root,
+    )?;
+    Ok((ctx.hex_dumps, ctx.unresolved))
 }
 
 #[cfg(test)]
@@ -464,6 +1008,368 @@ mod tests {
         );
     }
 
+    #[test]
+    fn whitespace() {
+        let text = std::fs::read_to_string("test/aamp/test.yml").unwrap();
+        let pio = ParameterIO::from_text(&text).unwrap();
+        for file in ["test/aamp/test_crlf.yml", "test/aamp/test_tabs.yml"] {
+            let text = std::fs::read_to_string(file).unwrap();
+            assert_eq!(ParameterIO::from_text(&text).unwrap(), pio, "{file}");
+        }
+        assert_eq!(normalize_text("a:\r\n\t\tb: 1\r\n"), "a:\n    b: 1\n");
+        assert_eq!(normalize_text("a:\n  b: \"\t\""), "a:\n  b: \"\t\"");
+    }
+
+    #[test]
+    fn all_errors() {
+        let text = "!io\nversion: 0\ntype: xml\nparam_root: !list\n  objects:\n    Root: !obj\n      \
+                    Good: 1\n      Bad: !vec3 [1.0, x, 3.0]\n      Stray: ~\n    Broken: 5\n  lists: \
+                    {}\n";
+        let errors = ParameterIO::from_text_all_errors(text).unwrap_err();
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].to_string().contains("`param_root/Root/Bad`"));
+        assert!(errors[1].to_string().contains("`param_root/Root/Stray`"));
+        assert!(errors[2].to_string().contains("`param_root/Broken`"));
+        let text = std::fs::read_to_string("test/aamp/test.yml").unwrap();
+        assert_eq!(
+            ParameterIO::from_text_all_errors(&text).unwrap(),
+            ParameterIO::from_text(&text).unwrap()
+        );
+    }
+
+    #[test]
+    fn line_endings() {
+        let pio = ParameterIO::new().with_object(
+            "Root",
+            params!("Text" => Parameter::StringRef("Line 1\r\nLine 2\rLine 3".into())),
+        );
+        let text = pio.to_text();
+        assert!(!text.contains('\r'));
+        assert!(!text.starts_with('\u{feff}'));
+        assert_eq!(ParameterIO::from_text(&text).unwrap(), pio);
+        assert_eq!(normalize_output("\u{feff}a\r\nb\rc\n".into()), "a\nb\nc\n");
+    }
+
+    #[test]
+    fn multidoc() {
+        let text = std::fs::read_to_string("test/aamp/test.yml").unwrap();
+        let pio = ParameterIO::from_text(&text).unwrap();
+        let multi = format!("---\n{text}\n---\n---\n{text}");
+        let pios = ParameterIO::from_multidoc_text(multi).unwrap();
+        assert_eq!(pios, vec![pio.clone(), pio.clone()]);
+        assert_eq!(ParameterIO::from_multidoc_text(&text).unwrap(), vec![pio]);
+    }
+
+    #[test]
+    fn structure_tags() {
+        let text = std::fs::read_to_string("test/aamp/test.yml").unwrap();
+        let pio = ParameterIO::from_text(text).unwrap();
+
+        let tagged = "!io\nversion: 0\ntype: xml\nparam_root: !list\n  TestContent: !obj\n    \
+                      Bool_0: true\n  Child: !list\n    Grandchild: !obj {}\n";
+        let tagged_pio = ParameterIO::from_text(tagged).unwrap();
+        assert_eq!(
+            tagged_pio.object("TestContent").unwrap().get("Bool_0"),
+            pio.object("TestContent").unwrap().get("Bool_0")
+        );
+        assert!(
+            tagged_pio
+                .list("Child")
+                .unwrap()
+                .object("Grandchild")
+                .unwrap()
+                .is_empty()
+        );
+
+        let mismatched = "!io\nversion: 0\ntype: xml\nparam_root: !list\n  objects:\n    \
+                          TestContent: !list\n      objects: {}\n      lists: {}\n  lists: {}\n";
+        assert!(ParameterIO::from_text(mismatched).is_err());
+        let untagged = "!io\nversion: 0\ntype: xml\nparam_root: !list\n  TestContent:\n    \
+                        Bool_0: true\n";
+        assert!(ParameterIO::from_text(untagged).is_err());
+    }
+
+    #[test]
+    fn deterministic_names() {
+        let options = TextOptions {
+            deterministic_names: true,
+            ..Default::default()
+        };
+        // `Damage_3` can only be guessed at index 3 in a structure named
+        // `Damage`, so it is unknown at the root.
+        let pio = ParameterIO::new().with_object("Damage_3", ParameterObject::new());
+        let before = pio.to_text_with_options(&options);
+        assert!(!before.contains("Damage_3"));
+
+        let other = ParameterIO::new().with_list(
+            "Damage",
+            ParameterList::new().with_objects(
+                (0..4).map(|i| (jstr!("Damage_{&i.to_string()}"), ParameterObject::new())),
+            ),
+        );
+        assert!(other.to_text_with_options(&options).contains("Damage_3"));
+        assert_eq!(pio.to_text_with_options(&options), before);
+        let _ = other.to_text();
+        assert!(pio.to_text().contains("Damage_3"));
+        assert_eq!(pio.to_text_with_options(&options), before);
+    }
+
+    #[test]
+    fn known_names_only() {
+        let options = TextOptions {
+            known_names_only: true,
+            ..Default::default()
+        };
+        let pio = ParameterIO::new().with_list(
+            "Damage",
+            ParameterList::new().with_objects(
+                (0..4).map(|i| (jstr!("Damage_{&i.to_string()}"), ParameterObject::new())),
+            ),
+        );
+        let text = pio.to_text_with_options(&options);
+        assert!(text.contains("Damage:"));
+        assert!(!text.contains("Damage_3"));
+        assert!(text.contains(&hash_name("Damage_3").to_string()));
+        assert_eq!(ParameterIO::from_text(&text).unwrap(), pio);
+    }
+
+    #[test]
+    fn root_name() {
+        let pio = ParameterIO::new().with_objects(
+            (0..4).map(|i| (jstr!("Damage_{&i.to_string()}"), ParameterObject::new())),
+        );
+        let mut options = TextOptions {
+            deterministic_names: true,
+            fragment: true,
+            ..Default::default()
+        };
+        assert!(!pio.to_text_with_options(&options).contains("Damage_3"));
+        options.root_name = Some("Damage".into());
+        let text = pio.to_text_with_options(&options);
+        assert!(text.contains("Damage_3"));
+        assert_eq!(ParameterList::from_text(&text).unwrap(), pio.param_root);
+    }
+
+    #[test]
+    fn float_formats() {
+        let text = "!io\nversion: 0\ntype: xml\nparam_root: !list\n  objects:\n    Root: !obj\n      \
+                    Speed: 1.50\n      Scale: !vec3 [1, 2.0, 3.25]\n      Canonical: 0.5\n  lists: {}\n";
+        let (mut pio, float_formats) = ParameterIO::from_text_with_float_formats(text).unwrap();
+        assert_eq!(float_formats.len(), 2);
+        assert_eq!(float_formats["Root/Speed"], ["1.50"]);
+        assert!(!float_formats.contains_key("Root/Canonical"));
+        let options = TextOptions {
+            float_formats,
+            ..Default::default()
+        };
+        let scale_line = |text: &str| {
+            text.lines()
+                .find(|l| l.contains("Scale"))
+                .unwrap()
+                .to_owned()
+        };
+        let written = pio.to_text_with_options(&options);
+        assert!(written.contains("Speed: 1.50"));
+        assert!(!scale_line(&written).contains("1.0"));
+
+        let scale = pio.object_mut("Root").unwrap().get_mut("Scale").unwrap();
+        *scale = Parameter::Vec3(Vector3f {
+            x: 1.0,
+            y: 4.0,
+            z: 3.25,
+        });
+        let written = pio.to_text_with_options(&options);
+        assert!(!scale_line(&written).contains("1.0"));
+        assert!(scale_line(&written).contains("4.0"));
+        assert_eq!(ParameterIO::from_text(&written).unwrap(), pio);
+    }
+
+    #[test]
+    fn decimal_u32() {
+        let pio = ParameterIO::new().with_object(
+            "Root",
+            params!(
+                "Count" => Parameter::U32(12),
+                "Flags" => Parameter::U32(0x80000000)
+            ),
+        );
+        let options = TextOptions {
+            decimal_u32_below: Some(0x10000),
+            ..Default::default()
+        };
+        let text = pio.to_text_with_options(&options);
+        assert!(text.contains("Count: !u 12"));
+        assert!(text.contains("Flags: !u 0x80000000"));
+        assert_eq!(ParameterIO::from_text(&text).unwrap(), pio);
+        assert!(!pio.to_text().contains("Count: !u 12"));
+    }
+
+    #[test]
+    fn annotations() {
+        let pio = ParameterIO::new().with_list(
+            "AI",
+            ParameterList::new().with_object(
+                "Root",
+                params!(
+                    "Damage" => Parameter::I32(10),
+                    "Name" => Parameter::StringRef("Multi\nline: \"text\"".into())
+                ),
+            ),
+        );
+        let mut options = TextOptions::default();
+        options
+            .annotations
+            .insert("AI/Root/Damage".into(), "damage per hit".into());
+        options
+            .annotations
+            .insert("AI/Root".into(), "attack settings\nfor the root".into());
+        let text = pio.to_text_with_options(&options);
+        let root = text.find("# attack settings").unwrap();
+        assert!(text[root..].starts_with("# attack settings\n        # for the root\n"));
+        let damage = text.find("# damage per hit").unwrap();
+        assert!(text[damage..].contains("Damage: 10"));
+        assert_eq!(ParameterIO::from_text(&text).unwrap(), pio);
+    }
+
+    #[test]
+    fn fragment() {
+        let pio = ParameterIO::new().with_list(
+            "AI",
+            ParameterList::new().with_object("Root", params!("Damage" => Parameter::I32(10))),
+        );
+        let mut options = TextOptions {
+            fragment: true,
+            ..Default::default()
+        };
+        options
+            .annotations
+            .insert("AI/Root/Damage".into(), "damage per hit".into());
+        let text = pio.to_text_with_options(&options);
+        assert!(!text.contains("version"));
+        assert!(!text.contains("param_root"));
+        assert!(text.contains("# damage per hit"));
+        assert_eq!(ParameterList::from_text(&text).unwrap(), pio.param_root);
+        assert!(ParameterIO::from_text(&text).is_err());
+    }
+
+    #[test]
+    fn hex_dump_binary() {
+        let pio = ParameterIO::new().with_object(
+            "Root",
+            params!("Blob" => Parameter::BufferBinary(b"roead\x00\x01".to_vec())),
+        );
+        let options = TextOptions {
+            hex_dump_binary: true,
+            ..Default::default()
+        };
+        let text = pio.to_text_with_options(&options);
+        assert!(text.contains("# 00000000  72 6f 65 61 64 00 01"));
+        assert!(text.contains("|roead..|"));
+        assert_eq!(ParameterIO::from_text(&text).unwrap(), pio);
+    }
+
+    #[test]
+    fn base64_binary() {
+        let pio = ParameterIO::new().with_object(
+            "Root",
+            params!(
+                "Data" => Parameter::BufferBinary(b"roead".to_vec()),
+                "Empty" => Parameter::BufferBinary(vec![]),
+            ),
+        );
+        let options = TextOptions {
+            base64_binary: true,
+            ..Default::default()
+        };
+        let text = pio.to_text_with_options(&options);
+        assert!(text.contains("Data: !buffer_binary_b64 cm9lYWQ="));
+        assert_eq!(ParameterIO::from_text(&text).unwrap(), pio);
+    }
+
+    #[test]
+    fn reuse_writer() {
+        let options = TextOptions {
+            decimal_u32_below: Some(100),
+            ..Default::default()
+        };
+        let mut writer = TextWriter::new(options.clone());
+        for file in ["test/aamp/test.yml", "test/aamp/test_tabs.yml"] {
+            let pio = ParameterIO::from_text(std::fs::read_to_string(file).unwrap()).unwrap();
+            assert_eq!(writer.write(&pio), pio.to_text_with_options(&options));
+        }
+        writer.options_mut().fragment = true;
+        let pio = ParameterIO::new().with_object("Root", params!("Count" => Parameter::U32(5)));
+        let text = writer.write(&pio);
+        assert_eq!(ParameterList::from_text(&text).unwrap(), pio.param_root);
+    }
+
+    #[test]
+    fn lenient_bools() {
+        let options = ParseOptions {
+            lenient_bools: true,
+            ..Default::default()
+        };
+        for (spelling, value) in [
+            ("true", true),
+            ("True", true),
+            ("TRUE", true),
+            ("yes", true),
+            ("Yes", true),
+            ("YES", true),
+            ("false", false),
+            ("False", false),
+            ("FALSE", false),
+            ("no", false),
+            ("No", false),
+            ("NO", false),
+        ] {
+            let text = format!(
+                "!io\nversion: 0\ntype: xml\nparam_root: !list\n  objects:\n    Root: \
+                 !obj\n      Flag: {spelling}\n      Quoted: \"{spelling}\"\n  lists: {{}}\n"
+            );
+            let pio = ParameterIO::from_text_with_options(&text, &options).unwrap();
+            let root = pio.object("Root").unwrap();
+            assert_eq!(root.get("Flag"), Some(&Parameter::Bool(value)));
+            assert_eq!(
+                root.get("Quoted"),
+                Some(&Parameter::StringRef(spelling.into()))
+            );
+            if !matches!(spelling, "true" | "false") {
+                let strict = ParameterIO::from_text(&text).unwrap();
+                assert_eq!(
+                    strict.object("Root").unwrap().get("Flag"),
+                    Some(&Parameter::StringRef(spelling.into()))
+                );
+            }
+            let written = pio.to_text();
+            assert!(written.contains(&format!("Flag: {value}")));
+        }
+    }
+
+    #[test]
+    fn wrap_buffers() {
+        let pio = ParameterIO::new().with_object(
+            "Root",
+            params!(
+                "Values" => Parameter::BufferF32((0..10).map(|i| i as f32 * 0.5).collect()),
+                "Data" => Parameter::BufferInt(vec![1, 2, 3]),
+            ),
+        );
+        let options = TextOptions {
+            buffer_items_per_line: Some(4),
+            ..Default::default()
+        };
+        let text = pio.to_text_with_options(&options);
+        let values = text.lines().position(|l| l.contains("Values:")).unwrap();
+        let lines: Vec<_> = text.lines().skip(values).take(3).collect();
+        assert!(lines[0].ends_with("[0.0, 0.5, 1.0, 1.5,"));
+        assert!(lines[1].trim_start().starts_with("2.0, 2.5"));
+        assert!(lines[2].ends_with("4.0, 4.5]"));
+        let data = text.lines().find(|l| l.contains("Data:")).unwrap();
+        assert!(data.ends_with(']'));
+        assert_eq!(ParameterIO::from_text(&text).unwrap(), pio);
+    }
+
     static TEST_NAMES: &[&str] = &[
         "Bool_0",
         "Bool_1",
@@ -526,4 +1432,69 @@ mod tests {
             pio.to_text();
         }
     }
+
+    #[test]
+    fn formatter() {
+        let pio = ParameterIO::new().with_object(
+            "Root",
+            params!(
+                "Element" => Parameter::U32(2),
+                "Count" => Parameter::U32(3)
+            ),
+        );
+        let scalar = |value: &str| FormattedValue::Scalar {
+            value: value.into(),
+            tag:   Some("!u".into()),
+        };
+        let text = pio
+            .to_text_with_formatter(&TextOptions::default(), |path, _| {
+                (path == "Root/Count").then(|| scalar("3"))
+            })
+            .unwrap();
+        assert!(text.contains("Count: !u 3"));
+        assert!(text.contains("Element: !u 0x2"));
+        assert_eq!(ParameterIO::from_text(&text).unwrap(), pio);
+
+        let err = pio
+            .to_text_with_formatter(&TextOptions::default(), |path, _| {
+                (path == "Root/Count").then(|| scalar("4"))
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("Root/Count"));
+    }
+
+    #[test]
+    fn unresolved_names() {
+        let pio = ParameterIO::new()
+            .with_object(
+                "Root",
+                params!("Damage" => Parameter::I32(10), 0x12345678u32 => Parameter::I32(5)),
+            )
+            .with_object(
+                0x12345678u32,
+                params!(0x9abcdef0u32 => Parameter::Bool(true)),
+            );
+        let (text, unresolved) = pio.to_text_with_unresolved(&TextOptions::default());
+        assert_eq!(text, pio.to_text());
+        assert_eq!(unresolved, [0x12345678, 0x9abcdef0]);
+        let (_, unresolved) = ParameterIO::new()
+            .with_object("Root", params!("Damage" => Parameter::I32(10)))
+            .to_text_with_unresolved(&TextOptions::default());
+        assert!(unresolved.is_empty());
+    }
+
+    #[test]
+    fn empty() {
+        let pio = ParameterIO::new().with_version(3);
+        let text = pio.to_text();
+        assert!(text.contains("objects: {}"));
+        assert!(text.contains("lists: {}"));
+        let new_pio = ParameterIO::from_text(&text).unwrap();
+        assert!(new_pio.is_empty());
+        assert_eq!(new_pio, pio);
+        assert_eq!(
+            ParameterIO::from_binary(pio.to_binary()).unwrap().to_text(),
+            text
+        );
+    }
 }
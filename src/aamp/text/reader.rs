@@ -0,0 +1,651 @@
+//! Helpers for reading parameter data from YAML nodes.
+//!
+//! These are the building blocks of [`ParameterIO::from_text`], exposed for
+//! crates which read other YAML formats containing parameters or parameter
+//! structures. Nodes are [`ryml`] node references, which are re-exported
+//! here for convenience.
+use base64::Engine;
+use join_str::jstr;
+use lexical::{FromLexical, FromLexicalWithOptions};
+pub use ryml::{NodeRef, Tree};
+use rustc_hash::FxHashMap;
+
+use crate::{
+    aamp::*,
+    types::*,
+    yaml::*,
+    Error, Result,
+};
+
+#[inline(always)]
+fn recognize_tag(tag: &str) -> Option<TagBasedType> {
+    match tag {
+        "!str32" | "!str64" | "!str256" => Some(TagBasedType::Str),
+        "!u" => Some(TagBasedType::Int),
+        _ => None,
+    }
+}
+
+fn scalar_to_value(key: &str, tag: &str, scalar: Scalar) -> Result<Parameter> {
+    Ok(match scalar {
+        Scalar::String(s) => {
+            match tag {
+                "!str32" => Parameter::String32(s.into()),
+                "!str64" => Parameter::String64(Box::new(s.into())),
+                "!str256" => Parameter::String256(Box::new(s.into())),
+                _ => Parameter::StringRef(s.into()),
+            }
+        }
+        Scalar::Int(i) => {
+            if tag == "!u" {
+                Parameter::U32(i as u32)
+            } else {
+                Parameter::I32(i as i32)
+            }
+        }
+        Scalar::Float(f) => Parameter::F32(f as f32),
+        Scalar::Bool(b) => Parameter::Bool(b),
+        Scalar::Null => {
+            match tag {
+                "!str32" => Parameter::String32(Default::default()),
+                "!str64" => Parameter::String64(Default::default()),
+                "!str256" => Parameter::String256(Default::default()),
+                _ => return Err(null_value(key)),
+            }
+        }
+    })
+}
+
+/// Parse a number from a scalar node, accepting both decimal and `0x`
+/// prefixed hexadecimal values.
+#[inline(always)]
+pub fn parse_num<'a, 't, T>(node: &NodeRef<'a, 't, '_, &'t Tree<'a>>) -> Result<T>
+where
+    T: FromLexicalWithOptions + FromLexical,
+{
+    let val = node.val()?;
+    match T::from_lexical(val.as_bytes()) {
+        Ok(v) => Ok(v),
+        Err(_) => {
+            let opts = T::Options::default();
+            Ok(T::from_lexical_with_options::<
+                { lexical::NumberFormatBuilder::hexadecimal() },
+            >(
+                val.trim_start_matches("0x").as_bytes(), &opts
+            )?)
+        }
+    }
+}
+
+macro_rules! impl_from_node_for_struct {
+    ($type:tt, $($field:tt),+) => {
+        impl<'a, 't, 'k, 'r> TryFrom<&'r NodeRef<'a, 't, 'k, &'t Tree<'a>>> for $type {
+            type Error = Error;
+            fn try_from(node: &'r NodeRef<'a, 't, 'k, &'t Tree<'a>>) -> Result<Self>
+            {
+                let mut iter = node.iter()?;
+                let result = $type {
+                    $(
+                        $field: parse_num(
+                            &iter.next()
+                                .ok_or(Error::InvalidData(concat!(stringify!($type), " missing field", stringify!($field))))?
+                        )?,
+                    )+
+                };
+                Ok(result)
+            }
+        }
+    };
+}
+impl_from_node_for_struct!(Vector2f, x, y);
+impl_from_node_for_struct!(Vector3f, x, y, z);
+impl_from_node_for_struct!(Vector4f, x, y, z, t);
+impl_from_node_for_struct!(Quat, a, b, c, d);
+impl_from_node_for_struct!(Color, r, g, b, a);
+
+/// Read an array of `N` curves from a flat sequence node with 32 values per
+/// curve, as written for `!curve` parameters.
+pub fn read_curves<'a, 't, const N: usize>(
+    node: &NodeRef<'a, 't, '_, &'t Tree<'a>>,
+) -> Result<[Curve; N]> {
+    let mut iter = node.iter()?;
+    let mut curves = [Curve::default(); N];
+    for curve in &mut curves {
+        curve.a = parse_num(
+            &iter
+                .next()
+                .ok_or(Error::InvalidData("YAML curve missing a"))?,
+        )?;
+        curve.b = parse_num(
+            &iter
+                .next()
+                .ok_or(Error::InvalidData("YAML curve missing a"))?,
+        )?;
+        for f in &mut curve.floats {
+            *f = parse_num(
+                &iter
+                    .next()
+                    .ok_or(Error::InvalidData("YAML curve missing a float"))?,
+            )?;
+        }
+    }
+    Ok(curves)
+}
+
+/// Read a sequence node of numbers into a buffer, allocated up front for the
+/// number of children.
+#[inline(always)]
+pub fn read_buf<'a, 't, T: FromLexical + FromLexicalWithOptions>(
+    node: &NodeRef<'a, 't, '_, &'t Tree<'a>>,
+) -> Result<Vec<T>> {
+    let mut buf = Vec::with_capacity(node.num_children()?);
+    for child in node.iter()? {
+        buf.push(parse_num(&child)?);
+    }
+    Ok(buf)
+}
+
+fn null_value(key: &str) -> Error {
+    Error::InvalidDataD(jstr!(
+        "AAMP does not support null values (parameter `{key}`); quote empty strings as \"\""
+    ))
+}
+
+/// Whether a scalar node has no value at all, as with `Key:` or `Key: !str32`.
+/// ryml leaves the value of such nodes as a null string, so it must not be
+/// read with [`NodeRef::val`]. Only the length is checked here, since an
+/// unquoted empty scalar is always null.
+#[inline(always)]
+fn has_null_value<'a, 't>(node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>) -> Result<bool> {
+    let empty = node.data().is_none_or(|data| data.value.scalar.is_empty());
+    Ok(empty && !node.is_val_quoted()?)
+}
+
+fn unknown_tag<'a, 't>(node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>, tag: &str) -> Error {
+    let key = node.key().unwrap_or_default();
+    Error::InvalidDataD(jstr!(
+        "Invalid parameter: unknown tag `{tag}` (parameter `{key}`)"
+    ))
+}
+
+/// Parse a parameter from a node, using its tag to determine the type as
+/// in the parameter IO YAML format.
+pub fn parse_parameter<'a, 't>(node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>) -> Result<Parameter> {
+    parse_parameter_with_options(node, &ParseOptions::default())
+}
+
+/// Parse a parameter from a node like [`parse_parameter`], using the given
+/// options.
+pub fn parse_parameter_with_options<'a, 't>(
+    node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>,
+    options: &ParseOptions,
+) -> Result<Parameter> {
+    if !node.is_valid() {
+        return Err(Error::InvalidData("Invalid YAML node for parameter"));
+    }
+    let tag = node.val_tag().unwrap_or("");
+    let param = if node.is_seq()? {
+        match tag {
+            "!vec2" => Vector2f::try_from(node)?.into(),
+            "!vec3" => Vector3f::try_from(node)?.into(),
+            "!vec4" => Vector4f::try_from(node)?.into(),
+            "!quat" => Quat::try_from(node)?.into(),
+            "!color" => Color::try_from(node)?.into(),
+            "!curve" => {
+                match node.num_children()? {
+                    32 => read_curves::<1>(node)?.into(),
+                    64 => read_curves::<2>(node)?.into(),
+                    96 => read_curves::<3>(node)?.into(),
+                    128 => read_curves::<4>(node)?.into(),
+                    _ => return Err(Error::InvalidData("Invalid curve: wrong number of values")),
+                }
+            }
+            "!buffer_int" => read_buf::<i32>(node)?.into(),
+            "!buffer_f32" => read_buf::<f32>(node)?.into(),
+            "!buffer_u32" => read_buf::<u32>(node)?.into(),
+            "!buffer_binary" => read_buf::<u8>(node)?.into(),
+            "!str_raw" => Parameter::StringRefRaw(read_buf::<u8>(node)?),
+            "" => {
+                return Err(Error::InvalidData(
+                    "Invalid parameter: sequence without known tag",
+                ));
+            }
+            _ => return Err(unknown_tag(node, tag)),
+        }
+    } else if has_null_value(node)? {
+        return Err(null_value(node.key().unwrap_or_default()));
+    } else if tag == "!buffer_binary_b64" {
+        Parameter::BufferBinary(base64::engine::general_purpose::STANDARD.decode(node.val()?)?)
+    } else {
+        let tag_type = recognize_tag(tag).or_else(|| get_tag_based_type(tag));
+        if options.strict_tags && tag_type.is_none() && !tag.is_empty() {
+            return Err(unknown_tag(node, tag));
+        }
+        let scalar = parse_scalar(
+            tag_type,
+            node.val()?,
+            node.is_quoted()?,
+            options.lenient_bools,
+        )?;
+        scalar_to_value(node.key().unwrap_or_default(), tag, scalar)?
+    };
+    Ok(param)
+}
+
+/// Read a structure name from a node's key. Unquoted keys consisting only of
+/// digits are treated as hashes.
+#[inline]
+pub fn read_key<'a, 't>(node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>) -> Result<Name> {
+    let key = node.key()?;
+    if !node.is_key_quoted()? {
+        if let Ok(hash) = lexical::parse::<u64, &str>(key) {
+            return Ok(Name::from(hash as u32));
+        }
+    }
+    Ok(Name::from(key))
+}
+
+#[inline]
+fn has_child<'a, 't>(node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>, key: &str) -> Result<bool> {
+    for child in node.iter()? {
+        if child.key()? == key {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Checks that an explicit structure tag, if present, matches the structure
+/// being read. Untagged nodes are accepted.
+#[inline]
+fn check_structure_tag<'a, 't>(
+    node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>,
+    expected: &str,
+) -> Result<()> {
+    let tag = node.val_tag().unwrap_or("");
+    if matches!(tag, "!obj" | "!list" | "!io") && tag != expected {
+        return Err(Error::InvalidDataD(jstr!(
+            "Expected node tagged {expected}, found node tagged {tag}"
+        )));
+    }
+    Ok(())
+}
+
+/// State for reading a document. By default every error is returned
+/// immediately. When collecting, errors in parameters and structures are
+/// recorded along with their path, and reading continues with a placeholder
+/// in their place.
+#[derive(Default)]
+struct ReadContext {
+    options: ParseOptions,
+    errors: Option<Vec<Error>>,
+    path: Vec<std::string::String>,
+    /// When recording, the original text of the floats in each parameter
+    /// which would be written differently, keyed by path from the root list.
+    float_formats: Option<FxHashMap<String, Vec<std::string::String>>>,
+    /// Number of leading path segments above the root list.
+    root_depth: usize,
+}
+
+impl ReadContext {
+    fn new(options: ParseOptions) -> Self {
+        Self {
+            options,
+            ..Default::default()
+        }
+    }
+
+    fn collecting(options: ParseOptions) -> Self {
+        Self {
+            options,
+            errors: Some(Vec::new()),
+            ..Default::default()
+        }
+    }
+
+    fn recording_floats() -> Self {
+        Self {
+            float_formats: Some(FxHashMap::default()),
+            ..Default::default()
+        }
+    }
+
+    /// Record the original text of the floats in a parameter if any of them
+    /// is not formatted the way it would be written.
+    fn record_floats<'a, 't>(
+        &mut self,
+        node: &NodeRef<'a, 't, '_, &'t Tree<'a>>,
+        param: &Parameter,
+    ) -> Result<()> {
+        let Some(float_formats) = &mut self.float_formats else {
+            return Ok(());
+        };
+        let texts: Vec<std::string::String> = match param {
+            Parameter::F32(_) => vec![node.val()?.into()],
+            Parameter::Curve1(_)
+            | Parameter::Curve2(_)
+            | Parameter::Curve3(_)
+            | Parameter::Curve4(_) => {
+                // Each curve is written as two ints followed by 30 floats.
+                node.iter()?
+                    .enumerate()
+                    .filter(|(i, _)| i % 32 >= 2)
+                    .map(|(_, child)| child.val().map(Into::into))
+                    .collect::<std::result::Result<_, _>>()?
+            }
+            Parameter::Vec2(_)
+            | Parameter::Vec3(_)
+            | Parameter::Vec4(_)
+            | Parameter::Color(_)
+            | Parameter::Quat(_)
+            | Parameter::BufferF32(_) => {
+                node.iter()?
+                    .map(|child| child.val().map(Into::into))
+                    .collect::<std::result::Result<_, _>>()?
+            }
+            _ => return Ok(()),
+        };
+        let mut floats = Vec::with_capacity(texts.len());
+        param.clone().map_floats(|f| {
+            floats.push(f);
+            f
+        });
+        let canonical = texts
+            .iter()
+            .zip(&floats)
+            .all(|(text, f)| text.trim() == lexical::to_string(*f));
+        if !canonical {
+            let path = self.path[self.root_depth.min(self.path.len())..].join("/");
+            float_formats.insert(path.into(), texts);
+        }
+        Ok(())
+    }
+
+    /// Read the child with the given key, substituting the placeholder if
+    /// reading fails and errors are being collected.
+    fn read_child<T>(
+        &mut self,
+        key: &str,
+        read: impl FnOnce(&mut Self) -> Result<T>,
+        placeholder: impl FnOnce() -> T,
+    ) -> Result<T> {
+        if self.errors.is_none() && self.float_formats.is_none() {
+            return read(self);
+        }
+        self.path.push(key.into());
+        let result = read(self);
+        let path = self.path.join("/");
+        self.path.pop();
+        match (result, &mut self.errors) {
+            (Err(e), Some(errors)) => {
+                errors.push(Error::InvalidDataD(jstr!("At `{&path}`: {&e.to_string()}")));
+                Ok(placeholder())
+            }
+            (result, _) => result,
+        }
+    }
+}
+
+fn read_object_with<'a, 't>(
+    node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>,
+    ctx: &mut ReadContext,
+) -> Result<ParameterObject> {
+    if !node.is_valid() {
+        return Err(Error::InvalidData("Invalid YAML node for parameter object"));
+    }
+    check_structure_tag(node, "!obj")?;
+    if !node.is_map()? {
+        return Err(Error::InvalidData("Expected map node"));
+    }
+    let mut param_object = ParameterObject::default();
+    for child in node.iter()? {
+        let key = child.key().unwrap_or_default();
+        let value = ctx.read_child(
+            key,
+            |ctx| {
+                let param = parse_parameter_with_options(&child, &ctx.options)?;
+                ctx.record_floats(&child, &param)?;
+                Ok(param)
+            },
+            || Parameter::StringRef(child.val().unwrap_or_default().into()),
+        )?;
+        param_object.insert(read_key(&child)?, value);
+    }
+    Ok(param_object)
+}
+
+fn read_list_with<'a, 't>(
+    node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>,
+    ctx: &mut ReadContext,
+) -> Result<ParameterList> {
+    if !node.is_valid() {
+        return Err(Error::InvalidData("Invalid YAML node for parameter list"));
+    }
+    check_structure_tag(node, "!list")?;
+    if !node.is_map()? {
+        return Err(Error::InvalidData("Expected map node"));
+    }
+    let mut param_list = ParameterList::default();
+    let has_objects = has_child(node, "objects")?;
+    let has_lists = has_child(node, "lists")?;
+    if has_objects || has_lists {
+        if has_objects {
+            let objects = node.get("objects")?;
+            if !objects.is_map()? {
+                return Err(Error::InvalidData("Expected map node"));
+            }
+            for child in objects.iter()? {
+                let key = child.key().unwrap_or_default();
+                let value = ctx.read_child(
+                    key,
+                    |ctx| read_object_with(&child, ctx),
+                    Default::default,
+                )?;
+                param_list.objects.insert(read_key(&child)?, value);
+            }
+        }
+        if has_lists {
+            let lists = node.get("lists")?;
+            if !lists.is_map()? {
+                return Err(Error::InvalidData("Expected map node"));
+            }
+            for child in lists.iter()? {
+                let key = child.key().unwrap_or_default();
+                let value = ctx.read_child(
+                    key,
+                    |ctx| read_list_with(&child, ctx),
+                    Default::default,
+                )?;
+                param_list.lists.insert(read_key(&child)?, value);
+            }
+        }
+    } else if node.val_tag().unwrap_or("") == "!list" {
+        // Without the usual structure, the children of an explicitly tagged
+        // list must be tagged as well to tell objects and lists apart.
+        for child in node.iter()? {
+            let name = read_key(&child)?;
+            let key = child.key()?;
+            ctx.read_child(
+                key,
+                |ctx| {
+                    match child.val_tag().unwrap_or("") {
+                        "!obj" => {
+                            param_list
+                                .objects
+                                .insert(name, read_object_with(&child, ctx)?);
+                        }
+                        "!list" => {
+                            param_list
+                                .lists
+                                .insert(name, read_list_with(&child, ctx)?);
+                        }
+                        _ => {
+                            return Err(Error::InvalidDataD(jstr!(
+                                "Untagged child `{key}` in parameter list without `objects` \
+                                 or `lists`"
+                            )));
+                        }
+                    }
+                    Ok(())
+                },
+                || (),
+            )?;
+        }
+    } else {
+        return Err(Error::InvalidData(
+            "Parameter list must have `objects` or `lists` keys or be tagged !list",
+        ));
+    }
+    Ok(param_list)
+}
+
+fn read_io_with<'a, 't>(
+    node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>,
+    ctx: &mut ReadContext,
+) -> Result<ParameterIO> {
+    if !node.is_valid() {
+        return Err(Error::InvalidData("Invalid YAML node for parameter IO"));
+    }
+    check_structure_tag(node, "!io")?;
+    let pio = ParameterIO {
+        version: ctx.read_child(
+            "version",
+            |_| parse_num(&node.get("version")?),
+            Default::default,
+        )?,
+        data_type: ctx.read_child(
+            "type",
+            |_| Ok(node.get("type")?.val()?.into()),
+            Default::default,
+        )?,
+        param_root: ctx.read_child(
+            "param_root",
+            |ctx| {
+                ctx.root_depth = ctx.path.len();
+                read_list_with(&node.get("param_root")?, ctx)
+            },
+            Default::default,
+        )?,
+    };
+    Ok(pio)
+}
+
+/// Read a parameter object from a map node.
+pub fn read_parameter_object<'a, 't>(
+    node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>,
+) -> Result<ParameterObject> {
+    read_object_with(node, &mut ReadContext::default())
+}
+
+/// Read a parameter list from a map node, either with `objects` and `lists`
+/// children or with explicitly tagged children.
+pub fn read_parameter_list<'a, 't>(
+    node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>,
+) -> Result<ParameterList> {
+    read_list_with(node, &mut ReadContext::default())
+}
+
+/// Read a parameter IO from a map node with `version`, `type`, and
+/// `param_root` children.
+pub fn read_parameter_io<'a, 't>(node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>) -> Result<ParameterIO> {
+    read_io_with(node, &mut ReadContext::default())
+}
+
+/// Read a parameter IO from a map node like [`read_parameter_io`], using the
+/// given options.
+pub fn read_parameter_io_with_options<'a, 't>(
+    node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>,
+    options: &ParseOptions,
+) -> Result<ParameterIO> {
+    read_io_with(node, &mut ReadContext::new(*options))
+}
+
+/// Read a parameter IO like [`read_parameter_io`], also returning the
+/// original text of floats which are not formatted the way they would be
+/// written, keyed by parameter path.
+pub(crate) fn read_parameter_io_float_formats<'a, 't>(
+    node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>,
+) -> Result<(ParameterIO, FxHashMap<String, Vec<std::string::String>>)> {
+    let mut ctx = ReadContext::recording_floats();
+    let pio = read_io_with(node, &mut ctx)?;
+    Ok((pio, ctx.float_formats.unwrap_or_default()))
+}
+
+/// Read a parameter IO like [`read_parameter_io`], but continue past invalid
+/// parameters and structures, returning every error found.
+pub(crate) fn read_parameter_io_all_errors<'a, 't>(
+    node: &'_ NodeRef<'a, 't, '_, &'t Tree<'a>>,
+) -> std::result::Result<ParameterIO, Vec<Error>> {
+    let mut ctx = ReadContext::collecting(Default::default());
+    let result = read_io_with(node, &mut ctx);
+    let mut errors = ctx.errors.unwrap_or_default();
+    match result {
+        Ok(pio) if errors.is_empty() => Ok(pio),
+        Ok(_) => Err(errors),
+        Err(e) => {
+            errors.push(e);
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_reader() {
+        let text = "Scale: !vec3 [1.0, 2.0, 3.0]\nIds: !buffer_u32 [1, 0x2]\nCount: 4\n";
+        let tree = Tree::parse(text).unwrap();
+        let root = tree.root_ref().unwrap();
+        assert_eq!(
+            parse_parameter(&root.get("Scale").unwrap()).unwrap(),
+            Parameter::Vec3(Vector3f { x: 1.0, y: 2.0, z: 3.0 })
+        );
+        let ids = root.get("Ids").unwrap();
+        assert_eq!(read_buf::<u32>(&ids).unwrap(), vec![1, 2]);
+        assert_eq!(parse_num::<i32>(&root.get("Count").unwrap()).unwrap(), 4);
+        let obj = read_parameter_object(&root).unwrap();
+        assert_eq!(obj.len(), 3);
+        assert_eq!(read_key(&root.get("Count").unwrap()).unwrap(), Name::from_str("Count"));
+    }
+
+    #[test]
+    fn null_value() {
+        let text = "Name: !str32\nEmpty: \"\"\nStray: ~\n";
+        let tree = Tree::parse(text).unwrap();
+        let root = tree.root_ref().unwrap();
+        let err = parse_parameter(&root.get("Name").unwrap()).unwrap_err();
+        assert!(err.to_string().contains("`Name`"));
+        assert_eq!(
+            parse_parameter(&root.get("Empty").unwrap()).unwrap(),
+            Parameter::StringRef(Default::default())
+        );
+        let err = parse_parameter(&root.get("Stray").unwrap()).unwrap_err();
+        assert!(err.to_string().contains("`Stray`"));
+    }
+
+    #[test]
+    fn unknown_tags() {
+        let text = "Seq: !mytag [1, 2]\nScalar: !mytag foo\nCount: !u 4\n";
+        let tree = Tree::parse(text).unwrap();
+        let root = tree.root_ref().unwrap();
+        let err = parse_parameter(&root.get("Seq").unwrap()).unwrap_err();
+        assert!(err.to_string().contains("`!mytag`"));
+        assert_eq!(
+            parse_parameter(&root.get("Scalar").unwrap()).unwrap(),
+            Parameter::StringRef("foo".into())
+        );
+        let strict = ParseOptions {
+            strict_tags: true,
+            ..Default::default()
+        };
+        let err = parse_parameter_with_options(&root.get("Scalar").unwrap(), &strict).unwrap_err();
+        assert!(err.to_string().contains("`!mytag`"));
+        assert!(err.to_string().contains("`Scalar`"));
+        assert_eq!(
+            parse_parameter_with_options(&root.get("Count").unwrap(), &strict).unwrap(),
+            Parameter::U32(4)
+        );
+    }
+}
@@ -1,6 +1,5 @@
 use std::{
     cell::RefCell,
-    collections::hash_map::Entry,
     hash::Hasher,
     io::{Cursor, Seek, SeekFrom, Write},
     rc::Rc,
@@ -8,16 +7,119 @@ use std::{
 };
 
 use binrw::prelude::*;
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 
 use super::*;
 use crate::{util::align, Result};
 
+/// Options for serializing parameter IOs to binary with
+/// [`ParameterIO::write_with_options`] and
+/// [`ParameterIO::to_binary_with_options`].
+///
+/// Each flag selects one of the layout details which differ between tools
+/// producing AAMP files, so that the output can be matched byte for byte
+/// against a reference file. The defaults match [`ParameterIO::to_binary`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Write the padding after the last section as zero bytes, so that the
+    /// output is exactly as long as the file size in the header. When
+    /// disabled, the padding is only skipped over, which leaves in-memory
+    /// output up to 3 bytes shorter than the declared size.
+    pub pad_end: bool,
+    /// Write identical parameter data and strings only once, pointing every
+    /// parameter which uses them at the same offset. When disabled, each
+    /// parameter gets its own copy, as in files which were not deduplicated.
+    pub dedup:   bool,
+    /// Write `String32`, `String64`, and `String256` parameters which have
+    /// non-zero bytes after the null terminator, as kept by
+    /// [`ParameterIO::from_binary_with_fixed_string_slots`], as full 32, 64,
+    /// or 256 byte slots. Other strings are written as usual. When disabled,
+    /// only the string and its terminator are written.
+    pub fixed_string_slots: bool,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            pad_end: false,
+            dedup:   true,
+            fixed_string_slots: false,
+        }
+    }
+}
+
+/// Kind of structure described by a [`LayoutEntry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LayoutKind {
+    /// A parameter list header.
+    List,
+    /// A parameter object header.
+    Object,
+    /// A parameter header.
+    Parameter,
+}
+
+/// Location of a structure in the binary output of
+/// [`ParameterIO::to_binary`], as reported by [`ParameterIO::binary_layout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayoutEntry {
+    /// Kind of structure.
+    pub kind: LayoutKind,
+    /// Name of the structure. The root list is named `param_root`.
+    pub name: Name,
+    /// Absolute offset of the structure header.
+    pub offset: u32,
+    /// Size of the structure header in bytes.
+    pub size: u32,
+    /// For parameters, the absolute offset and size in bytes of the data the
+    /// header points to. For buffers, this excludes the element count stored
+    /// just before the data. Deduplicated parameters share the same data.
+    pub data: Option<(u32, u32)>,
+}
+
 impl ParameterIO {
+    /// Get the location of every list, object, and parameter header, and of
+    /// parameter data, in the output of [`ParameterIO::to_binary`]. Entries
+    /// are in the order the headers are written. This is meant for debugging
+    /// and for tools which visualize the binary format.
+    pub fn binary_layout(&self) -> Vec<LayoutEntry> {
+        let mut buf = Vec::new();
+        let mut layout = Vec::new();
+        self.write_inner(
+            Cursor::new(&mut buf),
+            WriteOptions::default(),
+            Some(&mut layout),
+        )
+        .expect("Parameter IO should serialize to binary without error");
+        layout
+    }
+
     /// Serialize the parameter IO to binary using the given writer.
     pub fn write<W: Write + Seek>(&self, writer: W) -> Result<()> {
+        self.write_with_options(writer, WriteOptions::default())
+    }
+
+    /// Serialize the parameter IO to binary using the given writer, with
+    /// the given layout options.
+    pub fn write_with_options<W: Write + Seek>(
+        &self,
+        writer: W,
+        options: WriteOptions,
+    ) -> Result<()> {
+        self.write_inner(writer, options, None)
+    }
+
+    fn write_inner<W: Write + Seek>(
+        &self,
+        writer: W,
+        options: WriteOptions,
+        layout: Option<&mut Vec<LayoutEntry>>,
+    ) -> Result<()> {
         let mut ctx = WriteContext {
             writer,
+            options,
+            layout,
+            layout_index: Default::default(),
             list_count: Default::default(),
             object_count: Default::default(),
             param_count: Default::default(),
@@ -46,7 +148,11 @@ impl ParameterIO {
         ctx.write_string_section()?;
 
         let unknown_section_begin = ctx.writer.stream_position()?;
-        ctx.align()?;
+        if options.pad_end {
+            ctx.pad()?;
+        } else {
+            ctx.align()?;
+        }
 
         let header = ResHeader {
             version: 2,
@@ -69,11 +175,120 @@ impl ParameterIO {
 
     /// Serialize the parameter IO to in-memory bytes.
     pub fn to_binary(&self) -> Vec<u8> {
+        self.to_binary_with_options(WriteOptions::default())
+    }
+
+    /// Serialize the parameter IO to in-memory bytes, with the given layout
+    /// options.
+    pub fn to_binary_with_options(&self, options: WriteOptions) -> Vec<u8> {
         let mut buf = Vec::new();
-        self.write(Cursor::new(&mut buf))
+        self.write_with_options(Cursor::new(&mut buf), options)
             .expect("Parameter IO should serialize to binary without error");
         buf
     }
+
+    /// Serialize the parameter IO to in-memory bytes, writing back the
+    /// duplicate entries returned by
+    /// [`ParameterIO::from_binary_with_duplicates`], so that files with
+    /// colliding names are preserved instead of losing the dropped entries.
+    ///
+    /// Each duplicate is written in its parent just before the entry with the
+    /// same name, in the order given, so parsing the output with
+    /// [`ParameterIO::from_binary_with_duplicates`] returns this parameter IO
+    /// and the same duplicates again. Returns an error if the parent of a
+    /// duplicate does not exist.
+    pub fn to_binary_with_duplicates(&self, duplicates: &[DuplicateEntry]) -> Result<Vec<u8>> {
+        // Duplicates are inserted under unique placeholder names, which are
+        // replaced by their real names once the layout is known.
+        let mut pio = self.clone();
+        let mut used = FxHashSet::default();
+        collect_names(&pio.param_root, &mut used);
+        for duplicate in duplicates {
+            match &duplicate.value {
+                DuplicateValue::List(list) => collect_names(list, &mut used),
+                DuplicateValue::Object(object) => used.extend(object.0.keys().copied()),
+                DuplicateValue::Parameter(_) => (),
+            }
+        }
+        let mut placeholders = FxHashMap::default();
+        let mut next = 0u32;
+        for duplicate in duplicates {
+            let missing_parent = || {
+                let path = duplicate
+                    .path
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                Error::InvalidDataD(format!("Parent of duplicate entry `{path}` does not exist"))
+            };
+            let (&name, parent_path) = duplicate.path.split_last().ok_or_else(missing_parent)?;
+            while used.contains(&Name(next)) {
+                next += 1;
+            }
+            let placeholder = Name(next);
+            used.insert(placeholder);
+            placeholders.insert(placeholder, name);
+            match &duplicate.value {
+                DuplicateValue::List(list) => {
+                    let parent =
+                        find_list(&mut pio.param_root, parent_path).ok_or_else(missing_parent)?;
+                    insert_before(&mut parent.lists.0, name, placeholder, list.clone());
+                }
+                DuplicateValue::Object(object) => {
+                    let parent =
+                        find_list(&mut pio.param_root, parent_path).ok_or_else(missing_parent)?;
+                    insert_before(&mut parent.objects.0, name, placeholder, object.clone());
+                }
+                DuplicateValue::Parameter(param) => {
+                    let (object_name, list_path) =
+                        parent_path.split_last().ok_or_else(missing_parent)?;
+                    let parent = find_list(&mut pio.param_root, list_path)
+                        .and_then(|list| list.objects.0.get_mut(object_name))
+                        .ok_or_else(missing_parent)?;
+                    insert_before(&mut parent.0, name, placeholder, param.clone());
+                }
+            }
+        }
+
+        let mut buf = Vec::new();
+        let mut layout = Vec::new();
+        pio.write_inner(
+            Cursor::new(&mut buf),
+            WriteOptions::default(),
+            Some(&mut layout),
+        )?;
+        for entry in layout {
+            if let Some(name) = placeholders.get(&entry.name) {
+                buf[entry.offset as usize..][..4].copy_from_slice(&name.0.to_le_bytes());
+            }
+        }
+        Ok(buf)
+    }
+}
+
+fn collect_names(list: &ParameterList, names: &mut FxHashSet<Name>) {
+    names.insert(ROOT_KEY);
+    names.extend(list.objects.0.keys().copied());
+    names.extend(list.lists.0.keys().copied());
+    for object in list.objects.0.values() {
+        names.extend(object.0.keys().copied());
+    }
+    for list in list.lists.0.values() {
+        collect_names(list, names);
+    }
+}
+
+fn find_list<'a>(list: &'a mut ParameterList, path: &[Name]) -> Option<&'a mut ParameterList> {
+    path.iter()
+        .try_fold(list, |list, name| list.lists.0.get_mut(name))
+}
+
+/// Insert a value just before the entry with the given name, or at the end if
+/// there is none.
+fn insert_before<V>(map: &mut ParameterStructureMap<V>, name: Name, key: Name, value: V) {
+    let index = map.get_index_of(&name).unwrap_or(map.len());
+    map.shift_insert(index, key, value);
 }
 
 #[inline]
@@ -93,15 +308,42 @@ fn hash_param_data(param: &Parameter) -> u64 {
     hasher.finish()
 }
 
+/// Size in bytes of a parameter's data, excluding the element count of
+/// buffers and including the null terminator of strings.
+fn data_size(param: &Parameter) -> u32 {
+    (match param {
+        Parameter::Bool(_) | Parameter::F32(_) | Parameter::I32(_) | Parameter::U32(_) => 4,
+        Parameter::Vec2(_) => 8,
+        Parameter::Vec3(_) => 12,
+        Parameter::Vec4(_) | Parameter::Color(_) | Parameter::Quat(_) => 16,
+        Parameter::Curve1(_) => std::mem::size_of::<[Curve; 1]>(),
+        Parameter::Curve2(_) => std::mem::size_of::<[Curve; 2]>(),
+        Parameter::Curve3(_) => std::mem::size_of::<[Curve; 3]>(),
+        Parameter::Curve4(_) => std::mem::size_of::<[Curve; 4]>(),
+        Parameter::BufferInt(v) => v.len() * 4,
+        Parameter::BufferF32(v) => v.len() * 4,
+        Parameter::BufferU32(v) => v.len() * 4,
+        Parameter::BufferBinary(v) => v.len(),
+        Parameter::String32(_)
+        | Parameter::String64(_)
+        | Parameter::String256(_)
+        | Parameter::StringRef(_)
+        | Parameter::StringRefRaw(_) => param.as_string_bytes().map_or(0, |s| s.len()) + 1,
+    }) as u32
+}
+
 struct WriteContext<'pio, W: Write + Seek> {
     writer: W,
+    options: WriteOptions,
+    layout: Option<&'pio mut Vec<LayoutEntry>>,
+    layout_index: FxHashMap<usize, usize>,
     list_count: u32,
     object_count: u32,
     param_count: u32,
     param_queue: Vec<&'pio Parameter>,
     string_param_queue: Vec<&'pio Parameter>,
     offsets: FxHashMap<usize, u32>,
-    string_offsets: FxHashMap<&'pio str, u32>,
+    string_offsets: FxHashMap<&'pio [u8], u32>,
     buffer_offsets: FxHashMap<u64, u32>,
 }
 
@@ -119,6 +361,44 @@ impl<'pio, W: Write + Seek> WriteContext<'pio, W> {
         Ok(())
     }
 
+    /// Aligns like [`Self::align`], but also fills any bytes which were
+    /// skipped over at the end of the stream with zeros.
+    fn pad(&mut self) -> BinResult<()> {
+        self.align()?;
+        let pos = self.writer.stream_position()?;
+        let end = self.writer.seek(SeekFrom::End(0))?;
+        if end < pos {
+            self.writer.write_all(&[0u8; 4][..(pos - end) as usize])?;
+        } else {
+            self.writer.seek(SeekFrom::Start(pos))?;
+        }
+        Ok(())
+    }
+
+    fn record_header<T>(&mut self, item: &T, kind: LayoutKind, name: Name, offset: u32) {
+        if let Some(layout) = self.layout.as_mut() {
+            self.layout_index
+                .insert(item as *const _ as usize, layout.len());
+            layout.push(LayoutEntry {
+                kind,
+                name,
+                offset,
+                size: match kind {
+                    LayoutKind::List => 0xC,
+                    LayoutKind::Object | LayoutKind::Parameter => 0x8,
+                },
+                data: None,
+            });
+        }
+    }
+
+    fn record_data(&mut self, param: &Parameter, offset: u32) {
+        if let Some(layout) = self.layout.as_mut() {
+            let index = self.layout_index[&(param as *const _ as usize)];
+            layout[index].data = Some((offset, data_size(param)));
+        }
+    }
+
     #[inline]
     fn write_at<T>(&mut self, offset: u32, data: T) -> BinResult<()>
     where
@@ -201,7 +481,7 @@ impl<'pio, W: Write + Seek> WriteContext<'pio, W> {
                 if let Some(obj) = object.borrow().as_ref() {
                     for param in obj.0.values() {
                         let mut ctx = ctx.lock().expect("Context should unlock");
-                        if param.is_string_type() {
+                        if param.is_string() {
                             ctx.string_param_queue.push(param);
                         } else {
                             ctx.param_queue.push(param);
@@ -261,13 +541,13 @@ impl<'pio, W: Write + Seek> WriteContext<'pio, W> {
 
     fn write_parameter_data(&mut self, param: &Parameter) -> BinResult<()> {
         debug_assert!(
-            !param.is_string_type(),
+            !param.is_string(),
             "`write_parameter_data` called with string parameter"
         );
 
         let parent_offset = self.get_offset(param);
         let mut data_offset =
-            self.writer.stream_position()? as u32 + if param.is_buffer_type() { 4 } else { 0 };
+            self.writer.stream_position()? as u32 + if param.is_buffer() { 4 } else { 0 };
         let mut existed = true;
 
         // We're going to do this very differently from the oead method
@@ -276,9 +556,14 @@ impl<'pio, W: Write + Seek> WriteContext<'pio, W> {
         // first we get the chance to skip writing the data even to a temp
         // buffer if it's already been written.
         let hash = hash_param_data(param);
-        data_offset = match self.buffer_offsets.entry(hash) {
-            Entry::Occupied(entry) => *entry.get(),
-            Entry::Vacant(entry) => {
+        let existing = self
+            .buffer_offsets
+            .get(&hash)
+            .copied()
+            .filter(|_| self.options.dedup);
+        data_offset = match existing {
+            Some(offset) => offset,
+            None => {
                 let mut tmp_writer = Cursor::new(Vec::<u8>::with_capacity(0x200));
                 match param {
                     Parameter::Bool(b) => tmp_writer.write_le(&u32::from(*b))?,
@@ -307,11 +592,13 @@ impl<'pio, W: Write + Seek> WriteContext<'pio, W> {
                 }
                 self.writer.write_all(tmp_writer.into_inner().as_slice())?;
                 existed = false;
-                *entry.insert(data_offset)
+                self.buffer_offsets.insert(hash, data_offset);
+                data_offset
             }
         };
 
         self.write_at(parent_offset + 0x4, u24((data_offset - parent_offset) / 4))?;
+        self.record_data(param, data_offset);
         if !existed {
             self.align()?;
         }
@@ -320,17 +607,38 @@ impl<'pio, W: Write + Seek> WriteContext<'pio, W> {
 
     fn write_string(&mut self, param: &'pio Parameter) -> BinResult<()> {
         let parent_offset = self.get_offset(param);
-        let string_ = param.as_str().expect("Parameter should be a string");
+        let string_ = param
+            .as_string_bytes()
+            .expect("Parameter should be a string");
+        // Only strings with data after the terminator need their full slot.
+        let slot = match param {
+            Parameter::String32(s) => Some(s.as_raw_bytes().as_slice()),
+            Parameter::String64(s) => Some(s.as_raw_bytes().as_slice()),
+            Parameter::String256(s) => Some(s.as_raw_bytes().as_slice()),
+            _ => None,
+        }
+        .filter(|slot| {
+            self.options.fixed_string_slots && slot[string_.len()..].iter().any(|&c| c != 0)
+        });
+        let string_ = slot.unwrap_or(string_);
         let pos = self.writer.stream_position()? as u32;
         let mut existed = true;
-        let offset = *self.string_offsets.entry(string_).or_insert_with(|| {
+        let offset = if self.options.dedup {
+            *self.string_offsets.entry(string_).or_insert_with(|| {
+                existed = false;
+                pos
+            })
+        } else {
             existed = false;
             pos
-        });
+        };
         self.write_at(parent_offset + 0x4, u24((offset - parent_offset) / 4))?;
+        self.record_data(param, offset);
         if !existed {
-            self.writer.write_le(&string_.as_bytes())?;
-            self.writer.write_le(&0u8)?;
+            self.writer.write_le(&string_)?;
+            if slot.is_none() {
+                self.writer.write_le(&0u8)?;
+            }
             self.align()?;
         }
         Ok(())
@@ -339,6 +647,7 @@ impl<'pio, W: Write + Seek> WriteContext<'pio, W> {
     fn write_list(&mut self, name: Name, list: &ParameterList) -> BinResult<()> {
         let offset = self.writer.stream_position()? as u32;
         self.offsets.insert(list as *const _ as usize, offset);
+        self.record_header(list, LayoutKind::List, name, offset);
         self.list_count += 1;
         self.writer.write_le(&ResParameterList {
             name,
@@ -353,6 +662,7 @@ impl<'pio, W: Write + Seek> WriteContext<'pio, W> {
     fn write_object(&mut self, name: Name, object: &ParameterObject) -> BinResult<()> {
         let offset = self.writer.stream_position()? as u32;
         self.offsets.insert(object as *const _ as usize, offset);
+        self.record_header(object, LayoutKind::Object, name, offset);
         self.object_count += 1;
         self.writer.write_le(&ResParameterObj {
             name,
@@ -365,6 +675,7 @@ impl<'pio, W: Write + Seek> WriteContext<'pio, W> {
     fn write_parameter(&mut self, name: Name, param: &Parameter) -> BinResult<()> {
         let offset = self.writer.stream_position()? as u32;
         self.offsets.insert(param as *const _ as usize, offset);
+        self.record_header(param, LayoutKind::Parameter, name, offset);
         self.param_count += 1;
         self.writer.write_le(&ResParameter {
             name,
@@ -408,4 +719,153 @@ mod tests {
             assert_eq!(pio, new_pio);
         }
     }
+
+    #[test]
+    fn raw_strings() {
+        // "あ" in Shift-JIS
+        let raw = vec![0x82, 0xA0];
+        let pio = ParameterIO::new().with_object(
+            "Root",
+            params!(
+                "Raw" => Parameter::StringRefRaw(raw.clone()),
+                "Name" => Parameter::StringRef("Apple".into())
+            ),
+        );
+        let new_pio = ParameterIO::from_binary(pio.to_binary()).unwrap();
+        assert_eq!(pio, new_pio);
+        let raw_param = &new_pio.object("Root").unwrap()["Raw"];
+        assert_eq!(raw_param.get_type(), ParameterType::StringRef);
+        assert_eq!(raw_param.as_string_bytes().unwrap(), raw.as_slice());
+    }
+
+    #[test]
+    fn layout_options() {
+        let object = params!(
+            "Value" => Parameter::Vec3(Vector3f { x: 1.0, y: 2.0, z: 3.0 }),
+            "Name" => Parameter::StringRef("Apple".into())
+        );
+        let pio = ParameterIO::new()
+            .with_data_type("bxml")
+            .with_object("A", object.clone())
+            .with_object("B", object);
+        let default = pio.to_binary();
+        assert_ne!(default.len() % 4, 0);
+
+        let padded = pio.to_binary_with_options(WriteOptions {
+            pad_end: true,
+            ..Default::default()
+        });
+        let file_size = u32::from_le_bytes(padded[0xC..0x10].try_into().unwrap());
+        assert_eq!(padded.len(), file_size as usize);
+        assert_eq!(&padded[..default.len()], default.as_slice());
+
+        let undeduped = pio.to_binary_with_options(WriteOptions {
+            pad_end: true,
+            dedup:   false,
+            ..Default::default()
+        });
+        assert_eq!(undeduped.len(), padded.len() + 12 + 8);
+        assert_eq!(ParameterIO::from_binary(undeduped).unwrap(), pio);
+    }
+
+    #[test]
+    fn fixed_string_slots() {
+        let mut slot = [0; 32];
+        slot[..4].copy_from_slice(b"Link");
+        slot[8..12].copy_from_slice(b"junk");
+        let pio = ParameterIO::new().with_object(
+            "Root",
+            params!(
+                "Name" => Parameter::String32(FixedSafeString::from_raw_bytes(slot).unwrap()),
+                "Title" => Parameter::String64(Box::new("Hero".into()))
+            ),
+        );
+        let options = WriteOptions {
+            fixed_string_slots: true,
+            ..Default::default()
+        };
+        let data = pio.to_binary_with_options(options);
+        assert!(data.windows(4).any(|w| w == b"junk"));
+
+        let new_pio = ParameterIO::from_binary_with_fixed_string_slots(&data).unwrap();
+        let Parameter::String32(name) = &new_pio.object("Root").unwrap()["Name"] else {
+            panic!("Name should be a String32");
+        };
+        assert_eq!(name.as_raw_bytes(), &slot);
+        assert_eq!(new_pio.to_binary_with_options(options), data);
+
+        // By default, the string is cut off at the null terminator. The kept
+        // bytes do not affect comparisons.
+        let truncated = ParameterIO::from_binary(&data).unwrap();
+        assert_eq!(truncated, new_pio);
+        let name = truncated.object("Root").unwrap().get("Name").unwrap();
+        assert_eq!(name.as_str().unwrap(), "Link");
+        assert!(!truncated.to_binary().windows(4).any(|w| w == b"junk"));
+    }
+
+    #[test]
+    fn duplicates() {
+        let pio = ParameterIO::new().with_list(
+            "AI",
+            ParameterList::new()
+                .with_object(
+                    "Root",
+                    params!("A" => Parameter::I32(1), "B" => Parameter::I32(2)),
+                )
+                .with_list("Child", ParameterList::new())
+                .with_list("Other", ParameterList::new()),
+        );
+        // Give `B` the same name hash as `A`, and `Other` the same as `Child`.
+        let mut data = pio.to_binary();
+        for (from, to) in [("B", "A"), ("Other", "Child")] {
+            let entry = pio
+                .binary_layout()
+                .into_iter()
+                .find(|entry| entry.name == Name::from_str(from))
+                .unwrap();
+            data[entry.offset as usize..][..4].copy_from_slice(&Name::from_str(to).0.to_le_bytes());
+        }
+        let (new_pio, duplicates) = ParameterIO::from_binary_with_duplicates(&data).unwrap();
+        assert_eq!(duplicates.len(), 2);
+        assert_eq!(
+            new_pio.to_binary_with_duplicates(&duplicates).unwrap(),
+            data
+        );
+        assert_eq!(
+            new_pio.to_binary_with_duplicates(&[]).unwrap(),
+            new_pio.to_binary()
+        );
+
+        let missing = DuplicateEntry {
+            path:  vec![Name::from_str("Missing"), Name::from_str("A")],
+            value: DuplicateValue::Object(ParameterObject::new()),
+        };
+        assert!(new_pio.to_binary_with_duplicates(&[missing]).is_err());
+    }
+
+    #[test]
+    fn layout() {
+        let pio = ParameterIO::new().with_object(
+            "Root",
+            params!(
+                "Value" => Parameter::Vec3(Vector3f { x: 1.0, y: 2.0, z: 3.0 }),
+                "Ids" => Parameter::BufferU32(vec![1, 2]),
+                "Name" => Parameter::StringRef("Apple".into())
+            ),
+        );
+        let data = pio.to_binary();
+        let layout = pio.binary_layout();
+        assert_eq!(layout.len(), 5);
+        assert_eq!(layout[0].kind, LayoutKind::List);
+        assert_eq!(layout[0].name, ROOT_KEY);
+        assert_eq!(layout[1].kind, LayoutKind::Object);
+        for entry in &layout {
+            let name = u32::from_le_bytes(data[entry.offset as usize..][..4].try_into().unwrap());
+            assert_eq!(Name::from(name), entry.name);
+            assert_eq!(entry.data.is_some(), entry.kind == LayoutKind::Parameter);
+        }
+        assert_eq!(layout[3].data.unwrap().1, 8);
+        let (offset, size) = layout[4].data.unwrap();
+        assert_eq!(&data[offset as usize..][..size as usize], b"Apple\0");
+    }
 }
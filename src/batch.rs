@@ -0,0 +1,249 @@
+//! Parallel conversion of directories of AAMP and BYML files between binary
+//! and YAML.
+//!
+//! This module is only available with the `parallel` feature.
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+
+use crate::{
+    aamp::{get_default_name_table, NameTable, ParameterIO, TextOptions},
+    byml::Byml,
+    Result,
+};
+
+/// Options for converting directories with [`convert_dir`].
+#[derive(Debug, Clone, Copy)]
+pub struct ConvertOptions<'a> {
+    /// Convert binary files to YAML if `true`, or YAML files (ending in
+    /// `.yml`) back to binary if `false`. Defaults to `true`.
+    pub to_text: bool,
+    /// Endianness and version of BYML files written as binary.
+    pub byml: crate::byml::WriteOptions,
+    /// Yaz0 compress binary files whose extension starts with `s` (except
+    /// `sarc`), as with [`crate::yaz0::compress_if`]. This has no effect
+    /// without the `yaz0` feature. Defaults to `true`.
+    pub compress: bool,
+    /// Name table used to resolve names when converting parameter IOs to
+    /// YAML, so that a whole conversion job uses one isolated set of names.
+    /// Names guessed during the conversion are also added to this table. If
+    /// `None` (the default), the default name table is used.
+    pub name_table: Option<&'a NameTable<'static>>,
+}
+
+impl Default for ConvertOptions<'_> {
+    fn default() -> Self {
+        Self {
+            to_text: true,
+            byml: Default::default(),
+            compress: true,
+            name_table: None,
+        }
+    }
+}
+
+/// Recursively convert every AAMP and BYML file in a directory, writing the
+/// results to the same relative paths in the output directory.
+///
+/// When converting to YAML, Yaz0 compressed files are decompressed first, the
+/// format is detected by its magic, and `.yml` is appended to each file name.
+/// Files which are neither AAMP nor BYML are skipped. When converting to
+/// binary, only `.yml` files are converted, and parameter IOs are told apart
+/// from BYML documents by the `!io` tag at the start of the document.
+///
+/// Files are converted in parallel. Returns the paths of the files written,
+/// or the first error encountered, with the relative path of the file it
+/// occurred in (see [`crate::Error::entry_path`]).
+pub fn convert_dir(
+    in_dir: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+    options: ConvertOptions<'_>,
+) -> Result<Vec<PathBuf>> {
+    let in_dir = in_dir.as_ref();
+    let out_dir = out_dir.as_ref();
+    let mut files = Vec::new();
+    collect_files(in_dir, &mut files)?;
+    let written = files
+        .par_iter()
+        .map(|file| {
+            let rel = file.strip_prefix(in_dir).unwrap_or(file);
+            convert_file(file, rel, out_dir, options).map_err(|e| e.in_entry(rel.to_string_lossy()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(written.into_iter().flatten().collect())
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn is_yaml(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "yml")
+}
+
+/// Converts a single file, returning the path written or `None` if the file
+/// was skipped.
+fn convert_file(
+    file: &Path,
+    rel: &Path,
+    out_dir: &Path,
+    options: ConvertOptions<'_>,
+) -> Result<Option<PathBuf>> {
+    if options.to_text == is_yaml(file) {
+        return Ok(None);
+    }
+    let data = std::fs::read(file)?;
+    let (out_path, out_data) = if options.to_text {
+        let data = decompress(&data)?;
+        let text = if data.starts_with(b"AAMP") {
+            let table = options.name_table.unwrap_or(get_default_name_table());
+            ParameterIO::from_binary(&data)?.to_text_with_name_table(&TextOptions::default(), table)
+        } else if data.starts_with(b"BY") || data.starts_with(b"YB") {
+            Byml::from_binary(&data)?.to_text()
+        } else {
+            return Ok(None);
+        };
+        let mut name = rel.as_os_str().to_owned();
+        name.push(".yml");
+        (out_dir.join(name), text.into_bytes())
+    } else {
+        let text = std::str::from_utf8(&data)?;
+        let binary = if text.trim_start().starts_with("!io") {
+            ParameterIO::from_text(text)?.to_binary()
+        } else {
+            Byml::from_text(text)?.to_binary_with_options(options.byml)
+        };
+        let out_path = out_dir.join(rel.with_extension(""));
+        let binary = compress(binary, &out_path, options.compress);
+        (out_path, binary)
+    };
+    if let Some(parent) = out_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&out_path, out_data)?;
+    Ok(Some(out_path))
+}
+
+#[cfg(feature = "yaz0")]
+fn decompress(data: &[u8]) -> Result<std::borrow::Cow<'_, [u8]>> {
+    if data.starts_with(b"Yaz0") {
+        Ok(crate::yaz0::decompress(data)?.into())
+    } else {
+        Ok(data.into())
+    }
+}
+
+#[cfg(not(feature = "yaz0"))]
+fn decompress(data: &[u8]) -> Result<std::borrow::Cow<'_, [u8]>> {
+    if data.starts_with(b"Yaz0") {
+        Err(crate::Error::InvalidData(
+            "Converting Yaz0 compressed files requires the `yaz0` feature",
+        ))
+    } else {
+        Ok(data.into())
+    }
+}
+
+#[cfg(feature = "yaz0")]
+fn compress(data: Vec<u8>, path: &Path, compress: bool) -> Vec<u8> {
+    if compress {
+        crate::yaz0::compress_if(&data, path).into_owned()
+    } else {
+        data
+    }
+}
+
+#[cfg(not(feature = "yaz0"))]
+fn compress(data: Vec<u8>, _path: &Path, _compress: bool) -> Vec<u8> {
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create an empty directory which no other test run uses.
+    fn temp_dir() -> PathBuf {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let id = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let pid = std::process::id();
+        let dir = std::env::temp_dir().join(format!("roead_batch_test_{pid}_{id}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn convert_roundtrip() {
+        let root = temp_dir();
+        let input = root.join("in");
+        std::fs::create_dir_all(input.join("Actor")).unwrap();
+        let pio_data = std::fs::read("test/aamp/DamageReactionTable.bxml").unwrap();
+        let byml_data = std::fs::read("test/byml/ActorInfo.product.byml").unwrap();
+        std::fs::write(input.join("Actor/Table.bxml"), &pio_data).unwrap();
+        std::fs::write(input.join("ActorInfo.byml"), &byml_data).unwrap();
+        std::fs::write(input.join("Readme.txt"), b"Not a parameter file").unwrap();
+
+        let mut written = convert_dir(&input, root.join("text"), Default::default()).unwrap();
+        written.sort();
+        assert_eq!(
+            written,
+            vec![
+                root.join("text/Actor/Table.bxml.yml"),
+                root.join("text/ActorInfo.byml.yml"),
+            ]
+        );
+
+        let options = ConvertOptions {
+            to_text: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            convert_dir(root.join("text"), root.join("bin"), options)
+                .unwrap()
+                .len(),
+            2
+        );
+        let pio =
+            ParameterIO::from_binary(std::fs::read(root.join("bin/Actor/Table.bxml")).unwrap());
+        assert_eq!(pio.unwrap(), ParameterIO::from_binary(&pio_data).unwrap());
+        let byml = Byml::from_binary(std::fs::read(root.join("bin/ActorInfo.byml")).unwrap());
+        assert_eq!(byml.unwrap(), Byml::from_binary(byml_data).unwrap());
+
+        // Without BOTW strings, names are written as hashes but still
+        // convert back to the same parameter IO.
+        let table = NameTable::new(false);
+        let options = ConvertOptions {
+            name_table: Some(&table),
+            ..Default::default()
+        };
+        convert_dir(&input, root.join("hashes"), options).unwrap();
+        let text = std::fs::read_to_string(root.join("hashes/Actor/Table.bxml.yml")).unwrap();
+        assert_ne!(
+            text,
+            std::fs::read_to_string(root.join("text/Actor/Table.bxml.yml")).unwrap()
+        );
+        assert_eq!(
+            ParameterIO::from_text(text).unwrap(),
+            ParameterIO::from_binary(&pio_data).unwrap()
+        );
+
+        let options = ConvertOptions {
+            to_text: false,
+            ..Default::default()
+        };
+        std::fs::write(root.join("text/Broken.yml"), b"!io\nversion: x\n").unwrap();
+        let err = convert_dir(root.join("text"), root.join("bin"), options).unwrap_err();
+        assert_eq!(err.entry_path(), vec!["Broken.yml"]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,319 @@
+//! Structural comparison and three-way merging of BYML documents.
+use std::collections::BTreeSet;
+
+use indexmap::IndexMap;
+
+use super::*;
+
+/// A single difference between two BYML documents.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difference {
+    /// The node only exists in the other document.
+    Added(Byml),
+    /// The node only exists in the original document.
+    Removed(Byml),
+    /// The node exists in both documents with different values.
+    Changed {
+        /// The original value.
+        old: Byml,
+        /// The value in the other document.
+        new: Byml,
+    },
+}
+
+/// The differences between two BYML documents, keyed by node path (e.g.
+/// `Actors/12/name`). Map keys are written as-is, hash map keys in decimal,
+/// and array elements by index. Differences are sorted by path within each
+/// map, and by index within each array.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BymlDiff {
+    /// Map of node paths to differences.
+    pub changes: IndexMap<std::string::String, Difference>,
+}
+
+impl BymlDiff {
+    /// Returns `true` if no differences were found.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the number of differences.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Iterate the differences.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&std::string::String, &Difference)> {
+        self.changes.iter()
+    }
+}
+
+/// A node which was changed differently on both sides of a merge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeConflict {
+    /// The path of the node (see [`BymlDiff`]).
+    pub path: std::string::String,
+    /// The node in the common base, if it exists there.
+    pub base: Option<Byml>,
+    /// The node in our version, if it exists there. This is the version kept
+    /// in the merged document.
+    pub ours: Option<Byml>,
+    /// The node in their version, if it exists there.
+    pub theirs: Option<Byml>,
+}
+
+/// The result of a three-way merge with [`Byml::merge`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BymlMerge {
+    /// The merged document. Conflicting nodes are taken from our version.
+    pub merged: Byml,
+    /// Every node which was changed differently on both sides.
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl BymlMerge {
+    /// Returns `true` if the merge had no conflicts.
+    #[inline]
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Returns the children of two or three container nodes of the same type,
+/// as path segments sorted for maps and in order for arrays. Returns `None`
+/// if the nodes are not all maps, hash maps, or arrays of the same kind.
+fn child_keys(nodes: &[Option<&Byml>]) -> Option<Vec<ChildKey>> {
+    match nodes.iter().flatten().next()? {
+        Byml::Map(_) => {
+            let mut keys = BTreeSet::new();
+            for node in nodes.iter().flatten() {
+                keys.extend(node.as_map().ok()?.keys().cloned());
+            }
+            Some(keys.into_iter().map(ChildKey::Key).collect())
+        }
+        Byml::HashMap(_) => {
+            let mut keys = BTreeSet::new();
+            for node in nodes.iter().flatten() {
+                keys.extend(node.as_hash_map().ok()?.keys().copied());
+            }
+            Some(keys.into_iter().map(ChildKey::Hash).collect())
+        }
+        Byml::Array(_) => {
+            let mut len = 0;
+            for node in nodes.iter().flatten() {
+                len = len.max(node.as_array().ok()?.len());
+            }
+            Some((0..len).map(ChildKey::Index).collect())
+        }
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ChildKey {
+    Key(String),
+    Hash(u32),
+    Index(usize),
+}
+
+impl ChildKey {
+    fn get<'a>(&self, node: Option<&'a Byml>) -> Option<&'a Byml> {
+        match (self, node?) {
+            (ChildKey::Key(key), Byml::Map(map)) => map.get(key),
+            (ChildKey::Hash(hash), Byml::HashMap(map)) => map.get(hash),
+            (ChildKey::Index(index), Byml::Array(array)) => array.get(*index),
+            _ => None,
+        }
+    }
+
+    fn join(&self, path: &str) -> std::string::String {
+        let segment = match self {
+            ChildKey::Key(key) => key.to_string(),
+            ChildKey::Hash(hash) => hash.to_string(),
+            ChildKey::Index(index) => index.to_string(),
+        };
+        if path.is_empty() {
+            segment
+        } else {
+            format!("{path}/{segment}")
+        }
+    }
+}
+
+/// Creates an empty container of the same type as the node.
+fn empty_like(node: &Byml) -> Byml {
+    match node {
+        Byml::Map(_) => Byml::Map(Default::default()),
+        Byml::HashMap(_) => Byml::HashMap(Default::default()),
+        _ => Byml::Array(Vec::new()),
+    }
+}
+
+fn diff_node(path: &str, a: Option<&Byml>, b: Option<&Byml>, diff: &mut BymlDiff) {
+    if a == b {
+        return;
+    }
+    if let (Some(_), Some(_), Some(keys)) = (a, b, child_keys(&[a, b])) {
+        for key in keys {
+            diff_node(&key.join(path), key.get(a), key.get(b), diff);
+        }
+        return;
+    }
+    let change = match (a, b) {
+        (Some(old), Some(new)) => Difference::Changed {
+            old: old.clone(),
+            new: new.clone(),
+        },
+        (Some(old), None) => Difference::Removed(old.clone()),
+        (None, Some(new)) => Difference::Added(new.clone()),
+        (None, None) => return,
+    };
+    diff.changes.insert(path.into(), change);
+}
+
+fn merge_node(
+    path: &str,
+    base: Option<&Byml>,
+    ours: Option<&Byml>,
+    theirs: Option<&Byml>,
+    conflicts: &mut Vec<MergeConflict>,
+) -> Option<Byml> {
+    if ours == theirs || theirs == base {
+        return ours.cloned();
+    }
+    if ours == base {
+        return theirs.cloned();
+    }
+    // Both sides changed the node. Containers which both sides kept (or both
+    // added) are merged child by child; anything else is a conflict.
+    if let (Some(node), Some(_), Some(keys)) = (ours, theirs, child_keys(&[base, ours, theirs])) {
+        let mut merged = empty_like(node);
+        for key in keys {
+            let child = merge_node(
+                &key.join(path),
+                key.get(base),
+                key.get(ours),
+                key.get(theirs),
+                conflicts,
+            );
+            match (&mut merged, key, child) {
+                (Byml::Map(map), ChildKey::Key(key), Some(child)) => {
+                    map.insert(key, child);
+                }
+                (Byml::HashMap(map), ChildKey::Hash(hash), Some(child)) => {
+                    map.insert(hash, child);
+                }
+                (Byml::Array(array), ChildKey::Index(_), Some(child)) => array.push(child),
+                _ => (),
+            }
+        }
+        return Some(merged);
+    }
+    conflicts.push(MergeConflict {
+        path: path.into(),
+        base: base.cloned(),
+        ours: ours.cloned(),
+        theirs: theirs.cloned(),
+    });
+    ours.cloned()
+}
+
+impl Byml {
+    /// Compare this document with another, returning every node which was
+    /// added, removed, or changed in `other`. Maps and hash maps are compared
+    /// by key and arrays by index, so inserting an element in the middle of
+    /// an array shows up as a change to every element after it. Value hash
+    /// maps are compared as a whole.
+    pub fn diff(&self, other: &Byml) -> BymlDiff {
+        let mut diff = BymlDiff::default();
+        diff_node("", Some(self), Some(other), &mut diff);
+        diff
+    }
+
+    /// Merge two documents which were both derived from this one, applying
+    /// the changes made in each. A node changed in only one of them takes
+    /// that change. Maps and hash maps changed in both are merged by key,
+    /// and arrays by index. Any node changed differently in both is a
+    /// conflict, and our version of it is kept.
+    ///
+    /// **Note:** Arrays are merged purely by index, so the merge does not
+    /// understand elements which were inserted, removed, or reordered other
+    /// than at the end. If both sides change the middle of the same array,
+    /// expect conflicts, or elements being combined incorrectly.
+    pub fn merge(&self, ours: &Byml, theirs: &Byml) -> BymlMerge {
+        let mut conflicts = Vec::new();
+        let merged = merge_node("", Some(self), Some(ours), Some(theirs), &mut conflicts)
+            .unwrap_or(Byml::Null);
+        BymlMerge { merged, conflicts }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_and_merge() {
+        let base = map!(
+            "Name" => Byml::String("Apple".into()),
+            "Price" => Byml::I32(10),
+            "Tags" => Byml::Array(vec![Byml::String("Fruit".into())]),
+        );
+        let mut ours = base.clone();
+        ours.as_mut_map()
+            .unwrap()
+            .insert("Price".into(), Byml::I32(12));
+        ours.as_mut_map()
+            .unwrap()
+            .insert("Color".into(), Byml::String("Red".into()));
+        let mut theirs = base.clone();
+        theirs["Tags"]
+            .as_mut_array()
+            .unwrap()
+            .push(Byml::String("Food".into()));
+        theirs.as_mut_map().unwrap().remove("Name");
+
+        let diff = base.diff(&ours);
+        assert_eq!(diff.len(), 2);
+        assert_eq!(
+            diff.changes["Color"],
+            Difference::Added(Byml::String("Red".into()))
+        );
+        assert_eq!(
+            diff.changes["Price"],
+            Difference::Changed {
+                old: Byml::I32(10),
+                new: Byml::I32(12),
+            }
+        );
+        let diff = base.diff(&theirs);
+        assert_eq!(diff.changes.keys().collect::<Vec<_>>(), ["Name", "Tags/1"]);
+
+        let merge = base.merge(&ours, &theirs);
+        assert!(merge.is_clean());
+        assert_eq!(
+            merge.merged,
+            map!(
+                "Price" => Byml::I32(12),
+                "Color" => Byml::String("Red".into()),
+                "Tags" => Byml::Array(vec![
+                    Byml::String("Fruit".into()),
+                    Byml::String("Food".into()),
+                ]),
+            )
+        );
+
+        theirs
+            .as_mut_map()
+            .unwrap()
+            .insert("Price".into(), Byml::I32(8));
+        let merge = base.merge(&ours, &theirs);
+        assert_eq!(merge.conflicts.len(), 1);
+        assert_eq!(merge.conflicts[0].path, "Price");
+        assert_eq!(merge.conflicts[0].theirs, Some(Byml::I32(8)));
+        assert_eq!(merge.merged["Price"], Byml::I32(12));
+    }
+}
@@ -58,10 +58,13 @@
 //! # Ok(())
 //! # }
 //! ```
+mod diff;
 #[cfg(feature = "yaml")]
 mod text;
 mod writer;
+pub use diff::{BymlDiff, BymlMerge, Difference, MergeConflict};
 use num_traits::AsPrimitive;
+pub use writer::WriteOptions;
 use smartstring::alias::String;
 
 use crate::{Error, Result};
@@ -200,7 +203,7 @@ pub enum Byml {
 }
 
 impl Byml {
-    fn type_name(&self) -> String {
+    pub(crate) fn type_name(&self) -> String {
         match self {
             Byml::String(_) => "String".into(),
             Byml::BinaryData(_) => "Binary".into(),
@@ -225,6 +228,53 @@ impl Byml {
         matches!(self, Self::Null)
     }
 
+    /// Create an empty map node, to be filled with [`Byml::with_entry`].
+    pub fn new_map() -> Self {
+        Self::Map(Default::default())
+    }
+
+    /// Create an empty array node, to be filled with [`Byml::with_element`].
+    pub fn new_array() -> Self {
+        Self::Array(Default::default())
+    }
+
+    /// Insert an entry into a map node and return the node, for building maps
+    /// in a single expression:
+    ///
+    /// ```
+    /// # use roead::byml::Byml;
+    /// let actor = Byml::new_map()
+    ///     .with_entry("name", "Enemy_Lizalfos_Senior")
+    ///     .with_entry("life", 120)
+    ///     .with_entry("tags", Byml::new_array().with_element("Enemy").with_element("Lizalfos"));
+    /// assert_eq!(actor["life"], Byml::I32(120));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the node is not a [`Byml::Map`].
+    pub fn with_entry(mut self, key: impl Into<String>, value: impl Into<Byml>) -> Self {
+        match &mut self {
+            Self::Map(map) => {
+                map.insert(key.into(), value.into());
+            }
+            _ => panic!("Cannot add a map entry to a {} node", self.type_name()),
+        }
+        self
+    }
+
+    /// Append an element to an array node and return the node, for building
+    /// arrays in a single expression.
+    ///
+    /// # Panics
+    /// Panics if the node is not a [`Byml::Array`].
+    pub fn with_element(mut self, value: impl Into<Byml>) -> Self {
+        match &mut self {
+            Self::Array(array) => array.push(value.into()),
+            _ => panic!("Cannot add an array element to a {} node", self.type_name()),
+        }
+        self
+    }
+
     /// Get a reference to the inner bool value.
     pub fn as_bool(&self) -> Result<bool> {
         if let Self::Bool(v) = self {
@@ -332,6 +382,25 @@ impl Byml {
         }
     }
 
+    /// Get the inner f32 value. This is the same as [`Byml::as_float`], named
+    /// after the Rust type like the integer accessors.
+    #[inline]
+    pub fn as_f32(&self) -> Result<f32> {
+        self.as_float()
+    }
+
+    /// Get the inner f64 value. This is the same as [`Byml::as_double`].
+    #[inline]
+    pub fn as_f64(&self) -> Result<f64> {
+        self.as_double()
+    }
+
+    /// Get the inner string value as a string slice.
+    #[inline]
+    pub fn as_str(&self) -> Result<&str> {
+        self.as_string().map(|s| s.as_str())
+    }
+
     /// Get a reference to the inner string value.
     pub fn as_string(&self) -> Result<&String> {
         if let Self::String(v) = self {
@@ -639,6 +708,9 @@ impl TryFrom<Byml> for bool {
 }
 
 impl From<i32> for Byml {
+    /// Creates an `I32` node. Integer literals default to `i32`, so they
+    /// become `I32` rather than `I64` nodes; convert from an `i64` for the
+    /// latter.
     fn from(value: i32) -> Self {
         Self::I32(value)
     }
@@ -653,6 +725,7 @@ impl TryFrom<Byml> for i32 {
 }
 
 impl From<u32> for Byml {
+    /// Creates a `U32` node.
     fn from(value: u32) -> Self {
         Self::U32(value)
     }
@@ -667,6 +740,7 @@ impl TryFrom<Byml> for u32 {
 }
 
 impl From<i64> for Byml {
+    /// Creates an `I64` node.
     fn from(value: i64) -> Self {
         Self::I64(value)
     }
@@ -681,6 +755,7 @@ impl TryFrom<Byml> for i64 {
 }
 
 impl From<u64> for Byml {
+    /// Creates a `U64` node.
     fn from(value: u64) -> Self {
         Self::U64(value)
     }
@@ -695,6 +770,9 @@ impl TryFrom<Byml> for u64 {
 }
 
 impl From<f32> for Byml {
+    /// Creates a `Float` node. Float literals default to `f64`, so they
+    /// must be suffixed (e.g. `1.0f32`) to become `Float` rather than
+    /// `Double` nodes.
     fn from(value: f32) -> Self {
         Self::Float(value)
     }
@@ -709,6 +787,7 @@ impl TryFrom<Byml> for f32 {
 }
 
 impl From<f64> for Byml {
+    /// Creates a `Double` node.
     fn from(value: f64) -> Self {
         Self::Double(value)
     }
@@ -1061,7 +1140,7 @@ macro_rules! array {
         $crate::byml::Byml::Array(Default::default())
     );
     ($elem:expr; $n:expr) => (
-        $crate::byml::Byml::Array(vec![$elem, $n])
+        $crate::byml::Byml::Array(vec![$elem; $n])
     );
     ($($x:expr),+ $(,)?) => (
         $crate::byml::Byml::Array(vec![$($x),+])
@@ -1114,5 +1193,41 @@ mod tests {
         assert_eq!(map["test"], Byml::String("bob".into()));
         let arr = array!(Byml::String("bob".into()), Byml::Bool(true));
         assert_eq!(arr.as_array().unwrap().len(), 2);
+        let arr = array!(Byml::Null; 3);
+        assert_eq!(arr.as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn typed_accessors() {
+        let map = Byml::new_map()
+            .with_entry("name", "test")
+            .with_entry("scale", 1.5f32)
+            .with_entry("items", Byml::new_array().with_element(1));
+        let map = map.as_map().unwrap();
+        assert_eq!(map["name"].as_str().unwrap(), "test");
+        assert_eq!(map["scale"].as_f32().unwrap(), 1.5);
+        assert_eq!(map["items"].as_array().unwrap()[0].as_i32().unwrap(), 1);
+        let err = map["scale"].as_f64().unwrap_err().to_string();
+        assert!(err.contains("Float") && err.contains("Double"));
+        assert!(map["items"].as_map().is_err());
+    }
+
+    #[test]
+    fn builders() {
+        let map = Byml::new_map()
+            .with_entry("name", "test")
+            .with_entry("count", 1)
+            .with_entry("flag", true)
+            .with_entry("items", Byml::new_array().with_element(1.5f32).with_element(2u32));
+        assert_eq!(
+            map,
+            map!(
+                "name" => Byml::String("test".into()),
+                "count" => Byml::I32(1),
+                "flag" => Byml::Bool(true),
+                "items" => array!(Byml::Float(1.5), Byml::U32(2))
+            )
+        );
+        assert_eq!(Byml::from_iter([Byml::I32(1)]), array!(Byml::I32(1)));
     }
 }
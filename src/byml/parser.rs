@@ -1,6 +1,7 @@
 use std::io::{Read, Seek, SeekFrom};
 
 use binrw::{binrw, BinRead, VecArgs};
+use join_str::jstr;
 
 use super::*;
 use crate::{
@@ -144,6 +145,9 @@ impl StringTableParser {
     }
 }
 
+/// Node type of the path table in some version 1 documents.
+const PATH_TABLE_NODE_TYPE: u8 = 0xc3;
+
 struct Parser<R: Read + Seek> {
     reader: BinReader<R>,
     string_table: StringTableParser,
@@ -163,16 +167,28 @@ impl<R: Read + Seek> Parser<R> {
             Endian::Little
         };
         if !is_valid_version(header.inner.version) {
-            return Err(Error::InvalidData("Unsupported BYML version (1-7 only)"));
+            return Err(Error::InvalidDataD(jstr!(
+                "Unsupported BYML version {&header.inner.version.to_string()} (1-7 only)"
+            )));
         }
         let mut reader = BinReader::new(reader, endian);
+        let mut root_node_offset = header.inner.root_node_offset;
+        if header.inner.version == 1 && root_node_offset != 0 {
+            // Some version 1 documents (from Splatoon) have a path table, whose
+            // offset comes before the root node offset in the header. Path
+            // nodes are not supported, but documents without any can be read.
+            let node_type: u8 = reader.read_at(root_node_offset as u64)?;
+            if node_type == PATH_TABLE_NODE_TYPE {
+                root_node_offset = reader.read_at(0x10)?;
+            }
+        }
         Ok(Self {
             string_table: StringTableParser::new(header.inner.string_table_offset, &mut reader)?,
             hash_key_table: StringTableParser::new(
                 header.inner.hash_key_table_offset,
                 &mut reader,
             )?,
-            root_node_offset: header.inner.root_node_offset,
+            root_node_offset,
             reader,
         })
     }
@@ -339,4 +355,34 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn legacy_versions() {
+        let byml = Byml::Array(vec![Byml::I32(5), Byml::String("Legacy".into())]);
+        for version in 2..=4 {
+            let data = byml.to_binary_with_version(Endian::Little, version);
+            assert_eq!(Byml::from_binary(&data).unwrap(), byml);
+            let mut data = data;
+            data[2..4].copy_from_slice(&1u16.to_le_bytes());
+            assert_eq!(Byml::from_binary(&data).unwrap(), byml);
+        }
+
+        // Version 1 with an empty path table before the root node.
+        #[rustfmt::skip]
+        let data = [
+            b'Y', b'B', 1, 0,
+            0, 0, 0, 0, // hash key table
+            0, 0, 0, 0, // string table
+            0x14, 0, 0, 0, // path table
+            0x18, 0, 0, 0, // root node
+            0xc3, 0, 0, 0,
+            0xc0, 1, 0, 0, 0xd1, 0, 0, 0, 5, 0, 0, 0,
+        ];
+        assert_eq!(Byml::from_binary(data).unwrap(), Byml::Array(vec![Byml::I32(5)]));
+
+        let mut data = byml.to_binary_with_version(Endian::Little, 2);
+        data[2..4].copy_from_slice(&9u16.to_le_bytes());
+        let err = Byml::from_binary(&data).unwrap_err();
+        assert!(err.to_string().contains("Unsupported BYML version 9"));
+    }
 }
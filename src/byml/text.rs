@@ -12,6 +12,12 @@ impl Byml {
 
     /// Serialize the document to YAML. This can only be done for Null, Array,
     /// or Hash nodes.
+    ///
+    /// Every scalar type is written so that it parses back as the same type:
+    /// `I32` and `Float` untagged (floats always with a decimal point), and
+    /// `U32`, `I64`, `U64`, and `Double` tagged `!u`, `!l`, `!ul`, and `!f64`
+    /// as in oead. When parsing, the explicit tags `!s32`, `!u32`, `!s64`,
+    /// and `!u64` are also accepted.
     pub fn to_text(&self) -> std::string::String {
         Emitter::new(self)
             .emit()
@@ -28,7 +34,7 @@ fn is_binary_tag(tag: &str) -> bool {
 fn recognize_tag(tag: &str) -> Option<TagBasedType> {
     match tag {
         "!f64" => Some(TagBasedType::Float),
-        "!u" | "!l" | "!ul" => Some(TagBasedType::Int),
+        "!u" | "!l" | "!ul" | "!s32" | "!u32" | "!s64" | "!u64" => Some(TagBasedType::Int),
         "tag:yaml.org,2002:binary" | "!!binary" | "!!file" => Some(TagBasedType::Str),
         _ => None,
     }
@@ -91,7 +97,7 @@ impl<'a> Parser<'a> {
         } else {
             let tag = node.val_tag().unwrap_or("");
             let tag_type = get_tag_based_type(tag).or_else(|| recognize_tag(tag));
-            let scalar = parse_scalar(tag_type, node.val()?, node.is_quoted()?)?;
+            let scalar = parse_scalar(tag_type, node.val()?, node.is_quoted()?, false)?;
             match scalar {
                 Scalar::Bool(b) => Ok(Byml::Bool(b)),
                 Scalar::Float(f) => {
@@ -102,9 +108,9 @@ impl<'a> Parser<'a> {
                 }
                 Scalar::Int(i) => {
                     match tag {
-                        "!u" => Ok(Byml::U32(i as u32)),
-                        "!ul" => Ok(Byml::U64(i as u64)),
-                        "!l" => Ok(Byml::I64(i as i64)),
+                        "!u" | "!u32" => Ok(Byml::U32(i as u32)),
+                        "!ul" | "!u64" => Ok(Byml::U64(i as u64)),
+                        "!l" | "!s64" => Ok(Byml::I64(i as i64)),
                         _ => Ok(Byml::I32(i as i32)),
                     }
                 }
@@ -318,4 +324,35 @@ mod test {
             assert_eq!(byml, byml);
         }
     }
+
+    #[test]
+    fn typed_scalars() {
+        let text = std::fs::read_to_string("test/byml/scalars.yml").unwrap();
+        let byml = Byml::from_text(text).unwrap();
+        for (key, expected) in [
+            ("Bool", Byml::Bool(true)),
+            ("Double", Byml::Double(2.5)),
+            ("ExplicitI32", Byml::I32(-7)),
+            ("ExplicitI64", Byml::I64(-9000000000)),
+            ("ExplicitU32", Byml::U32(7)),
+            ("ExplicitU64", Byml::U64(0x200000000)),
+            ("Float", Byml::Float(1.5)),
+            ("I32", Byml::I32(-7)),
+            ("I64", Byml::I64(-9000000000)),
+            ("Null", Byml::Null),
+            ("String", Byml::String("Hello".into())),
+            ("U32", Byml::U32(7)),
+            ("U64", Byml::U64(0x200000000)),
+            ("Whole", Byml::Float(1.0)),
+            ("WholeDouble", Byml::Double(1.0)),
+        ] {
+            assert_eq!(byml[key], expected, "{key}");
+        }
+        let text = byml.to_text();
+        let roundtrip = Byml::from_text(&text).unwrap();
+        for (key, node) in byml.as_map().unwrap() {
+            assert_eq!(roundtrip[key].get_node_type(), node.get_node_type());
+        }
+        assert_eq!(roundtrip, byml);
+    }
 }
@@ -89,6 +89,69 @@ impl Byml {
             .expect("BYML should serialize to binary without error");
         buf
     }
+
+    /// Serialize the document to BYML with the specified options. This can
+    /// only be done for Null, Array, or Hash nodes.
+    ///
+    /// Combined with [`WriteOptions::from_binary`], this rebuilds a document
+    /// in the same format as an existing file. Files written by oead or roead
+    /// round trip to identical bytes, since both sort the string and hash key
+    /// tables in byte order and lay out nodes the same way. Files written by
+    /// Nintendo's own tools may order nodes differently, so they can
+    /// generally only be reproduced semantically.
+    pub fn to_binary_with_options(&self, options: WriteOptions) -> Vec<u8> {
+        self.to_binary_with_version(options.endian, options.version)
+    }
+}
+
+/// Options for serializing BYML documents to binary with
+/// [`Byml::to_binary_with_options`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Byte order of the document.
+    pub endian:  Endian,
+    /// BYML format version (2-4 are supported for writing).
+    pub version: u16,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            endian:  Endian::Little,
+            version: 2,
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Get the endianness and version of an existing binary document, for
+    /// writing a document in the same format.
+    ///
+    /// **Note**: If and only if the `yaz0` feature is enabled, this function
+    /// automatically decompresses the data when necessary.
+    pub fn from_binary(data: impl AsRef<[u8]>) -> Result<Self> {
+        #[cfg(feature = "yaz0")]
+        {
+            if data.as_ref().starts_with(b"Yaz0") {
+                return Self::from_binary(crate::yaz0::decompress(data.as_ref())?);
+            }
+        }
+        let data = data.as_ref();
+        if data.len() < 4 {
+            return Err(Error::InsufficientData(data.len(), 4));
+        }
+        let (endian, version) = match &data[..2] {
+            b"BY" => (Endian::Big, u16::from_be_bytes([data[2], data[3]])),
+            b"YB" => (Endian::Little, u16::from_le_bytes([data[2], data[3]])),
+            magic => {
+                return Err(Error::BadMagic(
+                    std::string::String::from_utf8_lossy(magic).into(),
+                    "BY or YB",
+                ));
+            }
+        };
+        Ok(Self { endian, version })
+    }
 }
 
 struct NonInlineNode<'a> {
@@ -417,4 +480,25 @@ mod test {
             assert_eq!(byml, new_byml);
         }
     }
+
+    #[test]
+    fn identical_roundtrip() {
+        let bytes = std::fs::read("test/byml/MainFieldLocation.byml").unwrap();
+        let options = WriteOptions::from_binary(&bytes).unwrap();
+        assert_eq!(options, WriteOptions {
+            endian:  Endian::Little,
+            version: 2,
+        });
+        let byml = Byml::from_binary(&bytes).unwrap();
+        assert_eq!(byml.to_binary_with_options(options), bytes);
+
+        let options = WriteOptions {
+            endian:  Endian::Big,
+            version: 3,
+        };
+        let be_bytes = byml.to_binary_with_options(options);
+        assert_eq!(WriteOptions::from_binary(&be_bytes).unwrap(), options);
+        let new_byml = Byml::from_binary(&be_bytes).unwrap();
+        assert_eq!(new_byml.to_binary_with_options(options), be_bytes);
+    }
 }
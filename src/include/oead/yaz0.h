@@ -51,8 +51,14 @@ Header GetHeader(rust::Slice<const u8> data);
 /// @param data_alignment  Required buffer alignment hint for decompression
 /// @param level  Compression level (6 to 9; 6 is fastest and 9 is slowest)
 rust::Vec<u8> Compress(rust::Slice<const u8> src, u32 data_alignment = 0, int level = 7);
+/// Same as Compress, but appends the compressed data to an existing buffer.
+void CompressInto(rust::Slice<const u8> src, rust::Vec<u8>& dst, u32 data_alignment = 0,
+                  int level = 7);
 
 void Decompress(rust::Slice<const u8> src, rust::Slice<u8> dst);
 void DecompressUnsafe(rust::Slice<const u8> src, rust::Slice<u8> dst);
+/// Same as Decompress, but stops once dst is full instead of requiring it to
+/// hold the entire decompressed data.
+void DecompressPrefix(rust::Slice<const u8> src, rust::Slice<u8> dst);
 
 }  // namespace oead::yaz0
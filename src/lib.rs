@@ -27,8 +27,13 @@
 //! feature set includes `byml`, `aamp`, `sarc,` and `yaz0`. For compatibility
 //! with many existing tools for these formats, there is also a `yaml` feature
 //! which enables serializing/deserializing AAMP and BYML files as YAML
-//! documents. Finally, serde support is available using the `with-serde`
-//! feature.
+//! documents. Serde support is available using the `with-serde` feature.
+//! The `parallel` feature adds the `batch` module for converting whole
+//! directories of files between binary and YAML in parallel, the `mmap`
+//! feature allows parsing AAMP files from memory-mapped files, and the `json`
+//! feature adds loose conversion of parameters to and from JSON values.
+//! Finally, the `testing` feature provides helpers for checking that data
+//! survives conversion, for use in the tests of tools built on roead.
 //!
 //! For API documentation, see the docs for each module.
 //!
@@ -55,14 +60,18 @@
 #![cfg_attr(not(test), deny(clippy::unwrap_used))]
 #[cfg(feature = "aamp")]
 pub mod aamp;
+#[cfg(feature = "parallel")]
+pub mod batch;
 #[cfg(feature = "byml")]
 pub mod byml;
 #[cfg(feature = "sarc")]
 pub mod sarc;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod types;
 mod util;
 #[cfg(feature = "yaml")]
-mod yaml;
+pub mod yaml;
 #[cfg(feature = "yaz0")]
 pub mod yaz0;
 
@@ -101,6 +110,53 @@ pub enum Error {
     Yaz0Error(#[from] cxx::Exception),
     #[error("{0}")]
     Any(String),
+    #[error("Operation aborted")]
+    Aborted,
+    /// A resource limit was exceeded while parsing untrusted data, such as
+    /// one set with `aamp::Limits`. Contains what was limited and the limit.
+    #[error("Limit exceeded: more than {1} {0}")]
+    LimitExceeded(&'static str, usize),
+    /// An error which occurred while processing a file stored inside another,
+    /// such as a SARC entry. Nested files produce a chain of these, from the
+    /// outermost to the innermost file.
+    #[error("In `{name}`: {source}")]
+    InEntry {
+        /// Name of the file in which the error occurred.
+        name:   String,
+        /// The underlying error.
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Wrap the error with the name of the file it occurred in, for errors
+    /// processing files stored inside another (e.g. SARC entries).
+    pub fn in_entry(self, name: impl Into<String>) -> Self {
+        Error::InEntry {
+            name:   name.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Get the names of the nested files the error occurred in, from the
+    /// outermost to the innermost.
+    pub fn entry_path(&self) -> Vec<&str> {
+        let mut path = Vec::new();
+        let mut error = self;
+        while let Error::InEntry { name, source } = error {
+            path.push(name.as_str());
+            error = source;
+        }
+        path
+    }
+
+    /// Get the underlying error, without any file context.
+    pub fn innermost(&self) -> &Error {
+        match self {
+            Error::InEntry { source, .. } => source.innermost(),
+            _ => self,
+        }
+    }
 }
 
 #[cfg_attr(feature = "sarc", binrw::binread, brw(repr = u16))]
@@ -43,8 +43,11 @@
 //! ```
 mod parse;
 mod write;
+use std::borrow::Cow;
+
 use binrw::{binrw, BinRead, BinWrite};
-pub use parse::Sarc;
+use join_str::jstr;
+pub use parse::{FileReader, Sarc};
 pub use write::SarcWriter;
 
 use crate::Endian;
@@ -57,6 +60,7 @@ pub struct File<'a> {
     pub name: Option<&'a str>,
     /// File data (as a slice).
     pub data: &'a [u8],
+    name_hash: u32,
     index: usize,
     sarc: &'a Sarc<'a>,
 }
@@ -98,6 +102,12 @@ impl<'a> File<'a> {
         self.index
     }
 
+    /// Hash of the file name, which is stored even if the name is not.
+    #[inline(always)]
+    pub fn name_hash(&self) -> u32 {
+        self.name_hash
+    }
+
     #[cfg(feature = "yaz0")]
     /// Returns a decompressed copy of the file data.
     #[inline(always)]
@@ -112,9 +122,13 @@ impl<'a> File<'a> {
             || (self.data.len() > 0x15 && &self.data[0x11..0x15] == b"SARC")
     }
 
-    /// Attempt to parse file as SARC.
+    /// Attempt to parse file as SARC. Errors are wrapped in
+    /// [`Error::InEntry`](crate::Error::InEntry) with the file name, if any.
     pub fn parse_sarc(&self) -> crate::Result<Sarc> {
-        Sarc::new(self.data)
+        Sarc::new(self.data).map_err(|e| match self.name {
+            Some(name) => e.in_entry(name),
+            None => e,
+        })
     }
 
     /// Check if the file is yaz0 compressed.
@@ -123,6 +137,24 @@ impl<'a> File<'a> {
         self.data.len() > 4 && &self.data[0..4] == b"Yaz0"
     }
 
+    /// The conventional name for the file once decompressed: for a Yaz0
+    /// compressed file whose extension starts with `s` (other than `sarc`),
+    /// as in `Actor/Pack/Enemy.sbactorpack`, the `s` is removed to give
+    /// `Actor/Pack/Enemy.bactorpack`, matching the extensions which
+    /// `yaz0::compress_if` compresses. Otherwise the name is returned
+    /// unchanged. Returns `None` if the file has no name.
+    pub fn suggested_name(&self) -> Option<Cow<'a, str>> {
+        let name = self.name?;
+        if self.is_compressed() {
+            if let Some((stem, ext)) = name.rsplit_once('.') {
+                if !ext.contains('/') && ext.len() > 1 && ext.starts_with('s') && ext != "sarc" {
+                    return Some(Cow::Owned(jstr!("{stem}.{&ext[1..]}")));
+                }
+            }
+        }
+        Some(Cow::Borrowed(name))
+    }
+
     /// Check if the file is an AAMP.
     #[inline(always)]
     pub fn is_aamp(&self) -> bool {
@@ -158,6 +190,10 @@ const fn hash_name(multiplier: u32, name: &str) -> u32 {
 #[brw(magic = b"SARC")]
 struct ResHeader {
     header_size: u16,
+    // Read in the archive's own endianness, the byte order mark is always
+    // 0xFEFF, so it carries no information here. It is checked by `Sarc::new`
+    // instead, which lets `Sarc::from_binary_auto` accept archives without one.
+    #[br(map = |_: u16| Endian::Little)]
     bom: Endian,
     file_size: u32,
     data_offset: u32,
@@ -56,8 +56,10 @@ impl<'a> Iterator for FileIterator<'a> {
             .ok()?;
             self.index += 1;
             Some(File {
-                name:  if self.entry.rel_name_opt_offset != 0 {
-                    let name_offset = self.sarc.names_offset as usize
+                name:  if let (Some(names_offset), true) =
+                    (self.sarc.names_offset, self.entry.rel_name_opt_offset != 0)
+                {
+                    let name_offset = names_offset as usize
                         + (self.entry.rel_name_opt_offset & 0xFFFFFF) as usize * 4;
                     let term_pos = find_null(&self.sarc.data[name_offset..]).ok()?;
                     Some(
@@ -71,6 +73,7 @@ impl<'a> Iterator for FileIterator<'a> {
                     (self.sarc.data_offset + self.entry.data_begin) as usize
                         ..(self.sarc.data_offset + self.entry.data_end) as usize,
                 )?,
+                name_hash: self.entry.name_hash,
                 index: self.index,
                 sarc:  self.sarc,
             })
@@ -78,14 +81,81 @@ impl<'a> Iterator for FileIterator<'a> {
     }
 }
 
+/// Reader over the data of a single file in a [`Sarc`], as returned by
+/// [`Sarc::readers`].
+///
+/// If the `yaz0` feature is enabled and the file is Yaz0 compressed, the data
+/// is decompressed the first time the reader is read from, so only the files
+/// currently being read are held in memory in decompressed form.
+#[derive(Debug)]
+pub struct FileReader<'a> {
+    data: &'a [u8],
+    #[cfg(feature = "yaz0")]
+    decompressed: Option<Cursor<Vec<u8>>>,
+}
+
+impl<'a> FileReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self {
+            data,
+            #[cfg(feature = "yaz0")]
+            decompressed: None,
+        }
+    }
+
+    /// Returns true if the file data is Yaz0 compressed and will be
+    /// decompressed as it is read.
+    pub fn is_compressed(&self) -> bool {
+        cfg!(feature = "yaz0") && self.data.starts_with(b"Yaz0")
+    }
+}
+
+impl std::io::Read for FileReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        #[cfg(feature = "yaz0")]
+        if self.is_compressed() {
+            if self.decompressed.is_none() {
+                let data = crate::yaz0::decompress(self.data).map_err(|e| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
+                })?;
+                self.decompressed = Some(Cursor::new(data));
+            }
+            return self
+                .decompressed
+                .as_mut()
+                .expect("Data should be decompressed")
+                .read(buf);
+        }
+        self.data.read(buf)
+    }
+}
+
 #[derive(Clone)]
 /// A simple SARC archive reader
+///
+/// The archive data is stored as a [`Cow`], so a `Sarc` can either own its
+/// buffer or borrow it for the lifetime `'a`:
+///
+/// - [`Sarc::new`] accepts owned or borrowed data. Compressed data is
+///   decompressed into a new owned buffer when the `yaz0` feature is enabled.
+/// - [`Sarc::from_compressed`] accepts Yaz0 compressed data only, with the
+///   `yaz0` feature.
+/// - [`Sarc::new_borrowed`] only accepts borrowed, uncompressed data and never
+///   copies it, which is useful for parsing a memory-mapped file.
+/// - [`Sarc::into_owned`] copies borrowed data so the archive no longer
+///   depends on the original buffer.
+///
+/// File data returned by methods like [`Sarc::get_data`] is always tied to
+/// the `Sarc` itself. For a borrowed archive, [`Sarc::get_borrowed_data`]
+/// returns slices tied to the original buffer instead, which can outlive the
+/// `Sarc`.
 pub struct Sarc<'a> {
     num_files: u16,
     entries_offset: u16,
     hash_multiplier: u32,
     data_offset: u32,
-    names_offset: u32,
+    /// Absent in hash-only archives without a name table.
+    names_offset: Option<u32>,
     endian: Endian,
     data: Cow<'a, [u8]>,
 }
@@ -145,7 +215,90 @@ impl<'a> Sarc<'_> {
         let mut reader = Cursor::new(data.as_ref());
         reader.set_position(6);
         let endian: Endian = Endian::read_ne(&mut reader).map_err(Error::from)?;
-        reader.set_position(0);
+        let Sarc {
+            num_files,
+            entries_offset,
+            hash_multiplier,
+            data_offset,
+            names_offset,
+            ..
+        } = Sarc::parse_header(&data, endian)?;
+        Ok(Sarc {
+            data,
+            data_offset,
+            endian,
+            entries_offset,
+            num_files,
+            hash_multiplier,
+            names_offset,
+        })
+    }
+
+    /// Parses a SARC archive from binary data, retrying with the opposite
+    /// endianness if the archive cannot be parsed with the one indicated by
+    /// its byte order mark. Returns the archive along with the endianness
+    /// that succeeded.
+    ///
+    /// If the byte order mark is missing or invalid, the endianness is
+    /// guessed from the header size field instead. If neither endianness
+    /// works, the error from the first attempt is returned.
+    ///
+    /// **Note**: If and only if the `yaz0` feature is enabled, this function
+    /// automatically decompresses the SARC when necessary.
+    pub fn from_binary_auto<T: Into<Cow<'a, [u8]>>>(data: T) -> crate::Result<(Sarc<'a>, Endian)> {
+        let data = data.into();
+        #[cfg(feature = "yaz0")]
+        let data = if data.starts_with(b"Yaz0") {
+            crate::yaz0::decompress(&data)?.into()
+        } else {
+            data
+        };
+
+        let detected = match data.get(6..8) {
+            Some([0xFE, 0xFF]) => Endian::Big,
+            Some([0xFF, 0xFE]) => Endian::Little,
+            _ => match data.get(4..6) {
+                Some([0x00, 0x14]) => Endian::Big,
+                _ => Endian::Little,
+            },
+        };
+        let opposite = match detected {
+            Endian::Big => Endian::Little,
+            Endian::Little => Endian::Big,
+        };
+        let (header, endian) = match Sarc::parse_header(&data, detected) {
+            Ok(header) => (header, detected),
+            Err(e) => (
+                Sarc::parse_header(&data, opposite).map_err(|_| e)?,
+                opposite,
+            ),
+        };
+        let Sarc {
+            num_files,
+            entries_offset,
+            hash_multiplier,
+            data_offset,
+            names_offset,
+            ..
+        } = header;
+        Ok((
+            Sarc {
+                data,
+                data_offset,
+                endian,
+                entries_offset,
+                num_files,
+                hash_multiplier,
+                names_offset,
+            },
+            endian,
+        ))
+    }
+
+    /// Validates the SARC headers with the given endianness, returning an
+    /// archive which borrows the data.
+    fn parse_header(data: &[u8], endian: Endian) -> crate::Result<Sarc<'_>> {
+        let mut reader = Cursor::new(data);
 
         let header: ResHeader = read(endian, &mut reader)?;
         if header.version != 0x0100 {
@@ -170,19 +323,24 @@ impl<'a> Sarc<'_> {
         let hash_multiplier = fat_header.hash_multiplier;
         let data_offset = header.data_offset;
 
+        // Hash-only archives may omit the name table entirely, in which case
+        // the file data follows the entries directly.
         let fnt_header_offset = entries_offset as usize + 0x10 * num_files as usize;
-        reader.set_position(fnt_header_offset as u64);
-        let fnt_header: ResFntHeader = read(endian, &mut reader)?;
-        if fnt_header.header_size as usize != 0x08 {
-            return Err(Error::InvalidData("SFNT header wrong size (expected 0x8)"));
-        }
-
-        let names_offset = reader.position() as u32;
-        if data_offset < names_offset {
+        let names_offset = if data.get(fnt_header_offset..fnt_header_offset + 4) == Some(b"SFNT") {
+            reader.set_position(fnt_header_offset as u64);
+            let fnt_header: ResFntHeader = read(endian, &mut reader)?;
+            if fnt_header.header_size as usize != 0x08 {
+                return Err(Error::InvalidData("SFNT header wrong size (expected 0x8)"));
+            }
+            Some(reader.position() as u32)
+        } else {
+            None
+        };
+        if (data_offset as usize) < names_offset.unwrap_or(fnt_header_offset as u32) as usize {
             return Err(Error::InvalidData("Invalid name table offset in SARC"));
         }
         Ok(Sarc {
-            data,
+            data: data.into(),
             data_offset,
             endian,
             entries_offset,
@@ -192,6 +350,29 @@ impl<'a> Sarc<'_> {
         })
     }
 
+    /// Decompresses Yaz0 compressed data and parses it as a SARC archive.
+    ///
+    /// By convention, compressed archives have an extension starting with
+    /// `s`, such as `.ssarc`, `.sbactorpack`, `.sblarc`, or `.spack`, while
+    /// `.sarc` and `.pack` archives are stored uncompressed. Returns an error
+    /// if the data is not Yaz0 compressed.
+    #[cfg(feature = "yaz0")]
+    pub fn from_compressed(data: impl AsRef<[u8]>) -> crate::Result<Sarc<'static>> {
+        Sarc::new(crate::yaz0::decompress(data)?)
+    }
+
+    /// Parses a SARC archive from borrowed binary data without copying it.
+    /// Unlike [`Sarc::new`], this never decompresses the archive, and returns
+    /// an error if the data is yaz0-compressed.
+    pub fn new_borrowed(data: &'a [u8]) -> crate::Result<Sarc<'a>> {
+        if data.starts_with(b"Yaz0") {
+            return Err(Error::InvalidData(
+                "Cannot borrow compressed SARC data (decompress it first)",
+            ));
+        }
+        Self::new(data)
+    }
+
     /// Get the number of files that are stored in the archive
     pub fn len(&self) -> usize {
         self.num_files as usize
@@ -212,12 +393,28 @@ impl<'a> Sarc<'_> {
         self.endian
     }
 
+    /// Check if the archive has a name table. Hash-only archives without one
+    /// can only be accessed by index or with [`Sarc::get_by_hash`], and their
+    /// files have no names.
+    pub fn has_names(&self) -> bool {
+        self.names_offset.is_some()
+    }
+
+    /// Get the name hash of a file name, as used by this archive.
+    pub fn hash_name(&self, file: &str) -> u32 {
+        hash_name(self.hash_multiplier, file)
+    }
+
     #[inline(always)]
     fn find_file(&self, file: &str) -> Result<Option<usize>> {
+        self.find_hash(hash_name(self.hash_multiplier, file))
+    }
+
+    #[inline(always)]
+    fn find_hash(&self, needle_hash: u32) -> Result<Option<usize>> {
         if self.num_files == 0 {
             return Ok(None);
         }
-        let needle_hash = hash_name(self.hash_multiplier, file);
         let mut a: u32 = 0;
         let mut b: u32 = self.num_files as u32 - 1;
         let mut reader = Cursor::new(self.data.as_ref());
@@ -255,6 +452,13 @@ impl<'a> Sarc<'_> {
         file_index.map(|i| self.file_at(i)).transpose()
     }
 
+    /// Get a file by its name hash, returning `None` on its absence or any
+    /// error. This works even for archives without a name table.
+    pub fn get_by_hash(&self, hash: u32) -> Option<File<'_>> {
+        let file_index = self.find_hash(hash).ok()?;
+        file_index.and_then(|i| self.file_at(i).ok())
+    }
+
     /// Get file data by name, returning a [`Result`] of an [`Option`]. This
     /// distinguishes between failed parsing (e.g. due to a corrupted SARC)
     /// and the absence of the file. If you don't care about any potential
@@ -262,22 +466,51 @@ impl<'a> Sarc<'_> {
     pub fn try_get_data(&self, file: &str) -> Result<Option<&[u8]>> {
         let file_index = self.find_file(file)?;
         file_index
-            .map(|i| -> Result<&[u8]> {
-                let entry_offset = self.entries_offset as usize + size_of::<ResFatEntry>() * i;
-                let entry: ResFatEntry =
-                    read(self.endian, &mut Cursor::new(&self.data[entry_offset..]))?;
-                Ok(&self.data[(self.data_offset + entry.data_begin) as usize
-                    ..(self.data_offset + entry.data_end) as usize])
-            })
+            .map(|i| Ok(&self.data[self.data_range(i)?]))
             .transpose()
     }
 
+    #[inline(always)]
+    fn data_range(&self, index: usize) -> Result<std::ops::Range<usize>> {
+        let entry_offset = self.entries_offset as usize + size_of::<ResFatEntry>() * index;
+        let entry: ResFatEntry = read(self.endian, &mut Cursor::new(&self.data[entry_offset..]))?;
+        Ok((self.data_offset + entry.data_begin) as usize
+            ..(self.data_offset + entry.data_end) as usize)
+    }
+
     /// Get file data by name, returning `None` on its absence or any error.
     /// If you need to know the error, use [`Sarc::try_get_data`].
     pub fn get_data(&self, file: &str) -> Option<&[u8]> {
         self.try_get_data(file).ok().flatten()
     }
 
+    /// Open a SARC archive stored as a file in this one, such as an actor pack
+    /// inside a title pack. Errors parsing the nested archive are wrapped in
+    /// [`Error::InEntry`] with the name of the file.
+    ///
+    /// **Note**: If and only if the `yaz0` feature is enabled, a compressed
+    /// nested archive is decompressed automatically. Otherwise, a compressed
+    /// entry results in an error.
+    pub fn open_nested(&self, name: &str) -> Result<Sarc<'_>> {
+        let data = self
+            .try_get_data(name)?
+            .ok_or_else(|| Error::InvalidDataD(jstr!("File `{name}` not found in SARC")))?;
+        if !SarcWriter::is_file_sarc(data) {
+            return Err(Error::InvalidDataD(jstr!(
+                "File `{name}` is not a SARC archive"
+            )));
+        }
+        #[cfg(not(feature = "yaz0"))]
+        {
+            if data.starts_with(b"Yaz0") {
+                return Err(Error::InvalidDataD(jstr!(
+                    "File `{name}` is a compressed SARC, which requires the `yaz0` feature"
+                )));
+            }
+        }
+        Sarc::new(data).map_err(|e| e.in_entry(name))
+    }
+
     /// Get a file by index. Returns error if index > file count.
     pub fn file_at(&self, index: usize) -> Result<File> {
         if index >= self.num_files as usize {
@@ -290,9 +523,11 @@ impl<'a> Sarc<'_> {
         let entry: ResFatEntry = read(self.endian, &mut Cursor::new(&self.data[entry_offset..]))?;
 
         Ok(File {
-            name: if entry.rel_name_opt_offset != 0 {
-                let name_offset = self.names_offset as usize
-                    + (entry.rel_name_opt_offset & 0xFFFFFF) as usize * 4;
+            name: if let (Some(names_offset), true) =
+                (self.names_offset, entry.rel_name_opt_offset != 0)
+            {
+                let name_offset =
+                    names_offset as usize + (entry.rel_name_opt_offset & 0xFFFFFF) as usize * 4;
                 let term_pos = find_null(&self.data[name_offset..])?;
                 Some(std::str::from_utf8(
                     &self.data[name_offset..name_offset + term_pos],
@@ -302,6 +537,7 @@ impl<'a> Sarc<'_> {
             },
             data: &self.data[(self.data_offset + entry.data_begin) as usize
                 ..(self.data_offset + entry.data_end) as usize],
+            name_hash: entry.name_hash,
             index,
             sarc: self,
         })
@@ -322,6 +558,31 @@ impl<'a> Sarc<'_> {
         }
     }
 
+    /// Returns an iterator over the names and data of files whose names match
+    /// a predicate, such as all files with a given extension. Files without
+    /// names are skipped. Entries are read lazily as the iterator advances.
+    pub fn find<F>(&self, predicate: F) -> impl Iterator<Item = (&str, &[u8])>
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.files().filter_map(move |file| {
+            file.name
+                .filter(|name| predicate(name))
+                .map(|name| (name, file.data))
+        })
+    }
+
+    /// Returns an iterator over the names of the contained files and readers
+    /// for their data, for streaming files out of the archive without copying
+    /// them. Names are `None` for files without names. Entries are read
+    /// lazily as the iterator advances.
+    ///
+    /// **Note**: If and only if the `yaz0` feature is enabled, compressed
+    /// files are decompressed as they are read (see [`FileReader`]).
+    pub fn readers(&self) -> impl Iterator<Item = (Option<&str>, FileReader<'_>)> {
+        self.files().map(|file| (file.name, FileReader::new(file.data)))
+    }
+
     /// Guess the minimum data alignment for files that are stored in the
     /// archive
     pub fn guess_min_alignment(&self) -> usize {
@@ -355,6 +616,36 @@ impl<'a> Sarc<'_> {
     }
 }
 
+impl<'a> Sarc<'a> {
+    /// Returns true if the archive borrows its data rather than owning it.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.data, Cow::Borrowed(_))
+    }
+
+    /// Convert the archive into one which owns its data, copying the data if
+    /// it is borrowed.
+    pub fn into_owned(self) -> Sarc<'static> {
+        Sarc {
+            data: Cow::Owned(self.data.into_owned()),
+            ..self
+        }
+    }
+
+    /// Get file data by name from a borrowed archive. The returned slice
+    /// borrows from the original buffer, not the `Sarc`, so it can outlive
+    /// the archive. Returns `None` if the file is absent, on any error, or if
+    /// the archive owns its data.
+    pub fn get_borrowed_data(&self, file: &str) -> Option<&'a [u8]> {
+        match self.data {
+            Cow::Borrowed(data) => {
+                let index = self.find_file(file).ok()??;
+                data.get(self.data_range(index).ok()?)
+            }
+            Cow::Owned(_) => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs::read;
@@ -383,4 +674,156 @@ mod tests {
                 .unwrap_or_else(|| panic!("Could not find file {}", file));
         }
     }
+
+    #[test]
+    fn borrowed_sarc() {
+        let data = read("test/sarc/Dungeon119.pack").unwrap();
+        let file_data = {
+            let sarc = Sarc::new_borrowed(&data).unwrap();
+            assert!(sarc.is_borrowed());
+            sarc.get_borrowed_data("Model/DgnMrgPrt_Dungeon119.sbfres")
+                .unwrap()
+        };
+        let owned = Sarc::new(data.clone()).unwrap();
+        assert_eq!(
+            owned.get_data("Model/DgnMrgPrt_Dungeon119.sbfres").unwrap(),
+            file_data
+        );
+        assert!(!owned.is_borrowed());
+        assert!(owned.get_borrowed_data("Model/DgnMrgPrt_Dungeon119.sbfres").is_none());
+        let converted = Sarc::new_borrowed(&data).unwrap().into_owned();
+        assert!(!converted.is_borrowed());
+        assert_eq!(converted, owned);
+        assert!(Sarc::new_borrowed(b"Yaz0\0\0\0\0").is_err());
+    }
+
+    #[test]
+    fn open_nested() {
+        let inner = SarcWriter::new(Endian::Little)
+            .with_file("Actor/Test.bxml", b"Hello".to_vec())
+            .to_binary();
+        let outer = SarcWriter::new(Endian::Little)
+            .with_file("Actor/Pack/Test.sbactorpack", inner)
+            .with_file("Actor/Plain.txt", b"Not an archive, just some text".to_vec())
+            .to_binary();
+        let sarc = Sarc::new(&outer).unwrap();
+        let nested = sarc.open_nested("Actor/Pack/Test.sbactorpack").unwrap();
+        assert_eq!(nested.get_data("Actor/Test.bxml"), Some(b"Hello".as_slice()));
+        assert!(sarc.open_nested("Actor/Plain.txt").is_err());
+        assert!(sarc.open_nested("Actor/Missing.sbactorpack").is_err());
+
+        let mut corrupt = b"SARC".to_vec();
+        corrupt.resize(0x20, 0xFF);
+        let outer = SarcWriter::new(Endian::Little)
+            .with_file(
+                "Pack/Title.pack",
+                SarcWriter::new(Endian::Little)
+                    .with_file("Actor/Pack/Test.sbactorpack", corrupt)
+                    .to_binary(),
+            )
+            .to_binary();
+        let sarc = Sarc::new(&outer).unwrap();
+        let err = sarc
+            .open_nested("Pack/Title.pack")
+            .and_then(|title| {
+                title
+                    .open_nested("Actor/Pack/Test.sbactorpack")
+                    .map(|_| ())
+                    .map_err(|e| e.in_entry("Pack/Title.pack"))
+            })
+            .unwrap_err();
+        assert_eq!(err.entry_path(), ["Pack/Title.pack", "Actor/Pack/Test.sbactorpack"]);
+        assert!(!matches!(err.innermost(), Error::InEntry { .. }));
+        assert!(err.to_string().starts_with("In `Pack/Title.pack`: In `Actor/Pack/Test"));
+    }
+
+    #[test]
+    fn readers() {
+        use std::io::Read;
+
+        let data = read("test/sarc/Dungeon119.pack").unwrap();
+        let sarc = Sarc::new(&data).unwrap();
+        let mut count = 0;
+        for ((name, mut reader), file) in sarc.readers().zip(sarc.files()) {
+            assert_eq!(name, file.name());
+            let mut buf = Vec::new();
+            reader.read_to_end(&mut buf).unwrap();
+            if !reader.is_compressed() {
+                assert_eq!(buf, file.data());
+            }
+            count += 1;
+        }
+        assert_eq!(count, sarc.len());
+    }
+
+    #[test]
+    fn suggested_name() {
+        let data = SarcWriter::new(Endian::Little)
+            .with_file("Actor/Pack/Enemy.sbactorpack", b"Yaz0\0\0\0\0".to_vec())
+            .with_file("Pack/Bootup.ssarc", b"Yaz0\0\0\0\0".to_vec())
+            .with_file("Actor/Pack/Plain.sbactorpack", b"SARC".to_vec())
+            .with_file("Model/No.Extension/Test", b"Yaz0\0\0\0\0".to_vec())
+            .to_binary();
+        let sarc = Sarc::new(&data).unwrap();
+        for (file, expected) in [
+            ("Actor/Pack/Enemy.sbactorpack", "Actor/Pack/Enemy.bactorpack"),
+            ("Pack/Bootup.ssarc", "Pack/Bootup.sarc"),
+            ("Actor/Pack/Plain.sbactorpack", "Actor/Pack/Plain.sbactorpack"),
+            ("Model/No.Extension/Test", "Model/No.Extension/Test"),
+        ] {
+            let name = sarc.get(file).unwrap().suggested_name();
+            assert_eq!(name.as_deref(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn nameless() {
+        let data = read("test/sarc/Nameless.sarc").unwrap();
+        let sarc = Sarc::new(&data).unwrap();
+        assert!(!sarc.has_names());
+        assert_eq!(sarc.len(), 2);
+        assert!(sarc.files().all(|file| file.name().is_none()));
+        let hash = sarc.hash_name("Actor/Nameless_B.txt");
+        let file = sarc.get_by_hash(hash).unwrap();
+        assert_eq!(file.data(), b"Second file\n");
+        assert_eq!(file.name_hash(), hash);
+        assert_eq!(sarc.get_data("Actor/Nameless_A.txt"), Some(b"First file\n".as_slice()));
+        assert!(sarc.get_by_hash(0).is_none());
+
+        let named = Sarc::new(read("test/sarc/Dungeon119.pack").unwrap()).unwrap();
+        assert!(named.has_names());
+        let file = named.get("Model/DgnMrgPrt_Dungeon119.sbfres").unwrap();
+        assert_eq!(named.get_by_hash(file.name_hash()).unwrap().name(), file.name());
+    }
+
+    #[test]
+    fn find() {
+        let data = read("test/sarc/Dungeon119.pack").unwrap();
+        let sarc = Sarc::new(&data).unwrap();
+        let models: Vec<_> = sarc.find(|name| name.ends_with(".sbfres")).collect();
+        assert_eq!(models.len(), 2);
+        assert!(models.iter().all(|(name, data)| {
+            name.starts_with("Model/") && sarc.get_data(name) == Some(*data)
+        }));
+        assert_eq!(sarc.find(|name| name.ends_with(".bxml")).count(), 0);
+    }
+
+    #[test]
+    fn endian_retry() {
+        let mut data = read("test/sarc/Dungeon119.pack").unwrap();
+        let (sarc, endian) = Sarc::from_binary_auto(&data).unwrap();
+        assert_eq!(endian, Endian::Big);
+        assert_eq!(sarc, Sarc::new(&data).unwrap());
+        // Byte order mark claims the wrong endianness
+        data[6..8].copy_from_slice(&[0xFF, 0xFE]);
+        assert!(Sarc::new(&data).is_err());
+        let (sarc, endian) = Sarc::from_binary_auto(&data).unwrap();
+        assert_eq!(endian, Endian::Big);
+        assert_eq!(sarc.len(), 10);
+        // Byte order mark missing entirely
+        data[6..8].copy_from_slice(&[0, 0]);
+        assert_eq!(Sarc::from_binary_auto(&data).unwrap().1, Endian::Big);
+        data[..4].copy_from_slice(b"NOPE");
+        assert!(Sarc::from_binary_auto(&data).is_err());
+    }
 }
@@ -1,7 +1,7 @@
 use std::{
     borrow::Borrow,
     hash::Hash,
-    io::{Cursor, Seek, SeekFrom},
+    io::{Cursor, Read, Seek},
     ops::Deref,
 };
 
@@ -85,7 +85,9 @@ pub struct SarcWriter {
     hash_multiplier: u32,
     min_alignment: usize,
     alignment_map: FxHashMap<String, usize>,
+    file_alignments: FxHashMap<String, usize>,
     brw_endian: binrw::Endian,
+    dedup: bool,
     /// Files to be written.
     pub files: IndexMap<String, Vec<u8>>,
 }
@@ -98,6 +100,8 @@ impl std::fmt::Debug for SarcWriter {
             .field("hash_multiplier", &self.hash_multiplier)
             .field("min_alignment", &self.min_alignment)
             .field("alignment_map", &self.alignment_map)
+            .field("file_alignments", &self.file_alignments)
+            .field("dedup", &self.dedup)
             .field("files", &self.files.keys().collect::<Vec<_>>())
             .finish()
     }
@@ -110,6 +114,8 @@ impl PartialEq for SarcWriter {
             && self.hash_multiplier == other.hash_multiplier
             && self.min_alignment == other.min_alignment
             && self.alignment_map == other.alignment_map
+            && self.file_alignments == other.file_alignments
+            && self.dedup == other.dedup
             && self.files == other.files
     }
 }
@@ -124,15 +130,25 @@ impl SarcWriter {
             legacy: false,
             hash_multiplier: HASH_MULTIPLIER,
             alignment_map: FxHashMap::default(),
+            file_alignments: FxHashMap::default(),
             files: IndexMap::new(),
             brw_endian: match endian {
                 Endian::Big => binrw::Endian::Big,
                 Endian::Little => binrw::Endian::Little,
             },
             min_alignment: 4,
+            dedup: false,
         }
     }
 
+    /// Creates a new SARC writer with room for at least `capacity` files
+    /// before reallocating.
+    pub fn with_capacity(endian: Endian, capacity: usize) -> SarcWriter {
+        let mut writer = Self::new(endian);
+        writer.reserve(capacity);
+        writer
+    }
+
     /// Creates a new SARC writer by taking attributes and files
     /// from an existing SARC reader
     pub fn from_sarc(sarc: &Sarc) -> SarcWriter {
@@ -142,6 +158,7 @@ impl SarcWriter {
             legacy: false,
             hash_multiplier: HASH_MULTIPLIER,
             alignment_map: FxHashMap::default(),
+            file_alignments: FxHashMap::default(),
             files: sarc
                 .files()
                 .filter_map(|f| f.name.map(|name| (name.to_string(), f.data.to_vec())))
@@ -151,6 +168,7 @@ impl SarcWriter {
                 Endian::Little => binrw::Endian::Little,
             },
             min_alignment: sarc.guess_min_alignment(),
+            dedup: false,
         }
     }
 
@@ -172,80 +190,126 @@ impl SarcWriter {
         buf
     }
 
+    /// Write a SARC archive to an in-memory buffer and Yaz0 compress it with
+    /// the given options, as used for archives with an extension starting
+    /// with `s` (e.g. `.ssarc` or `.sbactorpack`). Can be read back with
+    /// [`Sarc::from_compressed`].
+    #[cfg(feature = "yaz0")]
+    pub fn to_compressed(&mut self, options: crate::yaz0::CompressOptions) -> Vec<u8> {
+        crate::yaz0::compress_with_options(self.to_binary(), options)
+    }
+
     /// Write a SARC archive to a Write + Seek writer using the specified
     /// endianness. Default alignment requirements may be automatically
     /// added.
     pub fn write<W: Write + Seek>(&mut self, writer: &mut W) -> Result<()> {
-        writer.seek(SeekFrom::Start(0x14))?;
-        ResFatHeader {
-            header_size: 0x0C,
-            num_files: self.files.len() as u16,
-            hash_multiplier: self.hash_multiplier,
-        }
-        .write_options(writer, self.brw_endian, ())?;
+        self.write_to(writer)
+    }
 
+    /// Write a SARC archive to any writer, such as a file or a compressor,
+    /// without seeking. Only the header and name tables are buffered in
+    /// memory; file data is streamed directly. The output is identical to
+    /// [`SarcWriter::to_binary`]. Default alignment requirements may be
+    /// automatically added.
+    pub fn write_to<W: Write>(&mut self, mut writer: W) -> Result<()> {
         self.files.sort_unstable_by(|ka, _, kb, _| {
             hash_name(HASH_MULTIPLIER, ka).cmp(&hash_name(HASH_MULTIPLIER, kb))
         });
         self.add_default_alignments();
         let mut alignments: Vec<usize> = Vec::with_capacity(self.files.len());
+        let mut shared: Vec<bool> = Vec::with_capacity(self.files.len());
+        let mut entries: Vec<ResFatEntry> = Vec::with_capacity(self.files.len());
 
+        let mut rel_string_offset = 0;
+        let mut rel_data_offset = 0;
         {
-            let mut rel_string_offset = 0;
-            let mut rel_data_offset = 0;
+            let mut data_offsets: FxHashMap<&[u8], usize> = FxHashMap::default();
             for (name, data) in self.files.iter() {
                 let alignment = self.get_alignment_for_file(name, data);
                 alignments.push(alignment);
 
-                let offset = align(rel_data_offset, alignment);
-                ResFatEntry {
+                // Identical data can only be shared if the existing copy also
+                // satisfies this file's alignment requirement.
+                let existing = data_offsets
+                    .get(data.as_slice())
+                    .filter(|offset| self.dedup && *offset % alignment == 0);
+                shared.push(existing.is_some());
+                let offset = match existing {
+                    Some(offset) => *offset,
+                    None => {
+                        let offset = align(rel_data_offset, alignment);
+                        if self.dedup {
+                            data_offsets.entry(data).or_insert(offset);
+                        }
+                        rel_data_offset = offset + data.len();
+                        offset
+                    }
+                };
+                entries.push(ResFatEntry {
                     name_hash: hash_name(self.hash_multiplier, name.as_ref()),
                     rel_name_opt_offset: 1 << 24 | (rel_string_offset / 4),
                     data_begin: offset as u32,
                     data_end: (offset + data.len()) as u32,
-                }
-                .write_options(writer, self.brw_endian, ())?;
+                });
 
-                rel_data_offset = offset + data.len();
                 rel_string_offset += align(name.len() + 1, 4) as u32;
             }
         }
 
-        ResFntHeader {
-            header_size: 0x8,
-            reserved: 0,
-        }
-        .write_options(writer, self.brw_endian, ())?;
-        for (name, _) in self.files.iter() {
-            name.as_bytes().write_options(writer, self.brw_endian, ())?;
-            0u8.write_options(writer, self.brw_endian, ())?;
-            let pos = writer.stream_position()? as usize;
-            writer.seek(SeekFrom::Start(align(pos, 4) as u64))?;
-        }
-
+        // The data section is aligned to every file's alignment, so offsets
+        // relative to it are also aligned in the file as a whole.
         let required_alignment = alignments
             .iter()
             .fold(1, |acc: usize, alignment| acc.lcm(alignment));
-        let pos = writer.stream_position()? as usize;
-        writer.seek(SeekFrom::Start(align(pos, required_alignment) as u64))?;
-        let data_offset_begin = writer.stream_position()? as u32;
-        for ((_, data), alignment) in self.files.iter().zip(alignments.iter()) {
-            let pos = writer.stream_position()? as usize;
-            writer.seek(SeekFrom::Start(align(pos, *alignment) as u64))?;
-            data.write(writer)?;
-        }
+        let names_end = 0x14 + 0x0C + 0x10 * entries.len() + 0x8 + rel_string_offset as usize;
+        let data_offset_begin = align(names_end, required_alignment);
 
-        let file_size = writer.stream_position()? as u32;
-        writer.seek(SeekFrom::Start(0))?;
+        let mut meta = Cursor::new(Vec::with_capacity(data_offset_begin));
         ResHeader {
             header_size: 0x14,
             bom: self.endian,
-            file_size,
-            data_offset: data_offset_begin,
+            file_size: (data_offset_begin + rel_data_offset) as u32,
+            data_offset: data_offset_begin as u32,
             version: 0x0100,
             reserved: 0,
         }
-        .write_options(writer, self.brw_endian, ())?;
+        .write_options(&mut meta, self.brw_endian, ())?;
+        ResFatHeader {
+            header_size: 0x0C,
+            num_files: self.files.len() as u16,
+            hash_multiplier: self.hash_multiplier,
+        }
+        .write_options(&mut meta, self.brw_endian, ())?;
+        for entry in &entries {
+            entry.write_options(&mut meta, self.brw_endian, ())?;
+        }
+        ResFntHeader {
+            header_size: 0x8,
+            reserved: 0,
+        }
+        .write_options(&mut meta, self.brw_endian, ())?;
+        let mut meta = meta.into_inner();
+        for (name, _) in self.files.iter() {
+            meta.extend_from_slice(name.as_bytes());
+            meta.resize(align(meta.len() + 1, 4), 0);
+        }
+        meta.resize(data_offset_begin, 0);
+        writer.write_all(&meta)?;
+
+        let mut pos = data_offset_begin;
+        for (((_, data), alignment), _) in self
+            .files
+            .iter()
+            .zip(alignments.iter())
+            .zip(shared.iter())
+            .filter(|(_, shared)| !**shared)
+        {
+            let padding = align(pos, *alignment) - pos;
+            std::io::copy(&mut std::io::repeat(0).take(padding as u64), &mut writer)?;
+            writer.write_all(data)?;
+            pos += padding + data.len();
+        }
+        writer.flush()?;
         Ok(())
     }
 
@@ -333,6 +397,25 @@ impl SarcWriter {
         self
     }
 
+    /// Set whether to store byte-identical files only once. When enabled,
+    /// entries with the same data will point to the same data offset, as long
+    /// as the existing copy meets the alignment requirement of each entry.
+    ///
+    /// This is disabled by default, since not all consumers expect multiple
+    /// entries to share data.
+    #[inline]
+    pub fn set_dedup(&mut self, value: bool) {
+        self.dedup = value
+    }
+
+    /// Builder-style method to set whether to store byte-identical files only
+    /// once
+    #[inline]
+    pub fn with_dedup(mut self, value: bool) -> Self {
+        self.set_dedup(value);
+        self
+    }
+
     /// Set the endianness
     #[inline]
     pub fn set_endian(&mut self, endian: Endian) {
@@ -391,6 +474,9 @@ impl SarcWriter {
             None => "",
         };
         let mut alignment = self.min_alignment;
+        if let Some(requirement) = self.file_alignments.get(name) {
+            return alignment.lcm(requirement);
+        }
         if let Some(requirement) = self.alignment_map.get(ext) {
             alignment = alignment.lcm(requirement);
         }
@@ -406,6 +492,24 @@ impl SarcWriter {
         alignment
     }
 
+    /// Reserve room for at least `additional` more files.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.files.reserve(additional);
+    }
+
+    /// Get the number of files to be written.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Check if there are no files to be written.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
     /// Add a file to the archive, with greater generic flexibility than using
     /// `insert` on the `files` field.
     #[inline]
@@ -420,6 +524,41 @@ impl SarcWriter {
         self
     }
 
+    /// Add a file to the archive with a fixed data alignment. This overrides
+    /// the alignment that would otherwise be chosen from the file's extension
+    /// or contents, for files with requirements the writer doesn't know
+    /// about. The minimum alignment still applies.
+    ///
+    /// Panics if an invalid alignment is provided. If you're not passing an
+    /// alignment that is known at compile-time, you should probably check
+    /// using [`is_valid_alignment`] first.
+    pub fn add_file_with_alignment(
+        &mut self,
+        name: impl Into<String>,
+        data: impl Into<Vec<u8>>,
+        alignment: usize,
+    ) {
+        if !is_valid_alignment(alignment) {
+            panic!("Invalid SARC file alignment");
+        }
+        let name = name.into();
+        self.file_alignments.insert(name.clone(), alignment);
+        self.files.insert(name, data.into());
+    }
+
+    /// Builder-style method to add a file to the archive with a fixed data
+    /// alignment.
+    #[inline]
+    pub fn with_file_with_alignment(
+        mut self,
+        name: impl Into<String>,
+        data: impl Into<Vec<u8>>,
+        alignment: usize,
+    ) -> Self {
+        self.add_file_with_alignment(name, data, alignment);
+        self
+    }
+
     /// Add files to the archive from an iterator, with greater generic
     /// flexibility than using `extend` on the `files` field.
     #[inline]
@@ -445,13 +584,15 @@ impl SarcWriter {
         self
     }
 
-    /// Remove a file from the archive, for convenience.
+    /// Remove a file from the archive, along with any alignment override, for
+    /// convenience.
     #[inline]
     pub fn remove_file<Q: ?Sized + Hash + Eq>(&mut self, name: &Q)
     where
         String: Borrow<Q>,
     {
         self.files.shift_remove(name);
+        self.file_alignments.remove(name);
     }
 
     /// Get a file's data from the archive, for convience.
@@ -462,6 +603,32 @@ impl SarcWriter {
     {
         self.files.get(name)
     }
+
+    /// Get a mutable reference to a file's data, for modifying it in place
+    /// before writing.
+    #[inline]
+    pub fn get_file_mut<Q: ?Sized + Hash + Eq>(&mut self, name: &Q) -> Option<&mut Vec<u8>>
+    where
+        String: Borrow<Q>,
+    {
+        self.files.get_mut(name)
+    }
+
+    /// Iterate over the names and data of the files to be written, in the
+    /// order they were added.
+    pub fn files(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.files
+            .iter()
+            .map(|(name, data)| (name.as_str(), data.as_slice()))
+    }
+
+    /// Iterate over the files to be written with mutable access to their
+    /// data, e.g. to compress or patch every file just before writing.
+    pub fn files_mut(&mut self) -> impl Iterator<Item = (&str, &mut Vec<u8>)> {
+        self.files
+            .iter_mut()
+            .map(|(name, data)| (name.as_str(), data))
+    }
 }
 
 impl From<&Sarc<'_>> for SarcWriter {
@@ -512,4 +679,97 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn dedup_sarc() {
+        let data = std::fs::read("test/sarc/test.sarc").unwrap();
+        let sarc = Sarc::new(&data).unwrap();
+        let file = sarc.files().next().unwrap();
+        let mut sarc_writer = SarcWriter::from_sarc(&sarc)
+            .with_file("Copy/A.bin", file.data)
+            .with_file("Copy/B.bin", file.data);
+        let plain_data = sarc_writer.to_binary();
+        sarc_writer.set_dedup(true);
+        let dedup_data = sarc_writer.to_binary();
+        assert!(dedup_data.len() < plain_data.len());
+        let dedup_sarc = Sarc::new(&dedup_data).unwrap();
+        for file in Sarc::new(&plain_data).unwrap().files() {
+            assert_eq!(dedup_sarc.get_data(file.unwrap_name()), Some(file.data));
+        }
+    }
+
+    #[test]
+    fn modify_files() {
+        let mut writer = SarcWriter::new(crate::Endian::Little)
+            .with_file("A.txt", b"apple".as_slice())
+            .with_file("B.bin", b"banana".as_slice());
+        assert_eq!(writer.files().collect::<Vec<_>>(), [
+            ("A.txt", b"apple".as_slice()),
+            ("B.bin", b"banana".as_slice())
+        ]);
+        for (name, data) in writer.files_mut() {
+            if name.ends_with(".txt") {
+                data.make_ascii_uppercase();
+            }
+        }
+        writer.get_file_mut("B.bin").unwrap().push(b'!');
+        let data = writer.to_binary();
+        let sarc = Sarc::new(&data).unwrap();
+        assert_eq!(sarc.get_data("A.txt"), Some(b"APPLE".as_slice()));
+        assert_eq!(sarc.get_data("B.bin"), Some(b"banana!".as_slice()));
+    }
+
+    #[test]
+    fn capacity() {
+        let mut sarc_writer = SarcWriter::with_capacity(crate::Endian::Little, 100);
+        assert!(sarc_writer.is_empty());
+        assert!(sarc_writer.files.capacity() >= 100);
+        sarc_writer.add_files((0..100).map(|i| (format!("File{i}.bin"), vec![i as u8])));
+        sarc_writer.reserve(50);
+        assert!(sarc_writer.files.capacity() >= 150);
+        assert_eq!(sarc_writer.len(), 100);
+        let sarc = Sarc::new(sarc_writer.to_binary()).unwrap();
+        assert_eq!(sarc.len(), 100);
+    }
+
+    #[test]
+    fn file_alignment() {
+        let mut writer = SarcWriter::new(crate::Endian::Little)
+            .with_file("A.bin", b"apple".as_slice())
+            .with_file_with_alignment("Custom.bin", b"banana".as_slice(), 0x100)
+            .with_file_with_alignment("Texture.gtx", b"cherry".as_slice(), 8);
+        let data = writer.to_binary();
+        let sarc = Sarc::new(&data).unwrap();
+        let offset = |name| sarc.get_data(name).unwrap().as_ptr() as usize - data.as_ptr() as usize;
+        assert_eq!(offset("Custom.bin") % 0x100, 0);
+        // Without the override, .gtx files would be aligned to 0x2000.
+        assert!(data.len() < 0x2000);
+        assert_eq!(offset("Texture.gtx") % 8, 0);
+        assert_eq!(sarc.get_data("Custom.bin"), Some(b"banana".as_slice()));
+
+        writer.remove_file("Custom.bin");
+        writer.add_file("Custom.bin", b"banana".as_slice());
+        assert!(writer.to_binary().len() < 0x100);
+    }
+
+    #[test]
+    fn write_to_sink() {
+        let data = std::fs::read("test/sarc/A-1.00.sarc").unwrap();
+        let mut sarc_writer = SarcWriter::from_sarc(&Sarc::new(&data).unwrap());
+        let mut sink = Vec::new();
+        sarc_writer.write_to(&mut sink).unwrap();
+        assert_eq!(sink, sarc_writer.to_binary());
+        assert_eq!(sink, data);
+    }
+
+    #[cfg(feature = "yaz0")]
+    #[test]
+    fn compressed_roundtrip() {
+        let data = std::fs::read("test/sarc/A-1.00.sarc").unwrap();
+        let mut sarc_writer = SarcWriter::from_sarc(&Sarc::new(&data).unwrap());
+        let compressed = sarc_writer.to_compressed(Default::default());
+        assert!(compressed.starts_with(b"Yaz0"));
+        assert_eq!(Sarc::from_compressed(&compressed).unwrap(), Sarc::new(&data).unwrap());
+        assert!(Sarc::from_compressed(&data).is_err());
+    }
 }
@@ -0,0 +1,74 @@
+//! Test helpers for tools built on roead.
+//!
+//! This module is only available with the `testing` feature.
+use std::fmt::Write;
+
+use crate::aamp::ParameterIO;
+
+fn check_same(expected: &ParameterIO, actual: &ParameterIO, format: &str) {
+    if expected == actual {
+        return;
+    }
+    let mut details = std::string::String::new();
+    if expected.version != actual.version {
+        let _ = writeln!(
+            details,
+            "  version: {} != {}",
+            expected.version, actual.version
+        );
+    }
+    if expected.data_type != actual.data_type {
+        let _ = writeln!(
+            details,
+            "  data type: {:?} != {:?}",
+            expected.data_type, actual.data_type
+        );
+    }
+    for (path, difference) in expected.diff(actual).iter() {
+        let _ = writeln!(details, "  {path}: {difference:?}");
+    }
+    if details.is_empty() {
+        details.push_str("  (same entries in a different order)\n");
+    }
+    panic!("Parameter IO did not survive a {format} round trip:\n{details}");
+}
+
+/// Assert that a parameter IO is unchanged after converting it to binary and
+/// back, and (with the `yaml` feature) to YAML and back. On failure, this
+/// panics with a list of every parameter which changed.
+pub fn assert_roundtrips(pio: &ParameterIO) {
+    let binary = pio.to_binary();
+    let from_binary = ParameterIO::from_binary(binary)
+        .unwrap_or_else(|e| panic!("Failed to parse binary parameter IO: {e}"));
+    check_same(pio, &from_binary, "binary");
+
+    #[cfg(feature = "yaml")]
+    {
+        let text = pio.to_text();
+        let from_text = ParameterIO::from_text(&text)
+            .unwrap_or_else(|e| panic!("Failed to parse parameter IO text: {e}\n{text}"));
+        check_same(pio, &from_text, "text");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aamp::*;
+
+    #[test]
+    fn roundtrips() {
+        for file in ["test/aamp/Lizalfos.bphysics", "test/aamp/GameRomHorse.bxml"] {
+            let pio = ParameterIO::from_binary(std::fs::read(file).unwrap()).unwrap();
+            assert_roundtrips(&pio);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Root/Damage")]
+    fn reports_differences() {
+        let expected = ParameterIO::new().with_object("Root", params!("Damage" => Parameter::I32(1)));
+        let actual = ParameterIO::new().with_object("Root", params!("Damage" => Parameter::I32(2)));
+        check_same(&expected, &actual, "binary");
+    }
+}
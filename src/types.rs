@@ -18,7 +18,7 @@ use serde::{Deserialize, Serialize};
     derive(Serialize, Deserialize),
     serde(from = "std::string::String", into = "std::string::String")
 )]
-#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd)]
+#[derive(Clone, Copy)]
 pub struct FixedSafeString<const N: usize> {
     data: [u8; N],
     len:  usize,
@@ -33,6 +33,28 @@ impl<const N: usize> Default for FixedSafeString<N> {
     }
 }
 
+// Bytes after the null terminator are ignored, so that they can be kept
+// without affecting comparisons.
+impl<const N: usize> PartialEq for FixedSafeString<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for FixedSafeString<N> {}
+
+impl<const N: usize> std::hash::Hash for FixedSafeString<N> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl<const N: usize> PartialOrd for FixedSafeString<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.as_str().partial_cmp(other.as_str())
+    }
+}
+
 impl<const N: usize> std::fmt::Debug for FixedSafeString<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.as_ref().fmt(f)
@@ -40,10 +62,48 @@ impl<const N: usize> std::fmt::Debug for FixedSafeString<N> {
 }
 
 impl<const N: usize> FixedSafeString<N> {
+    /// The maximum length of a string that fits without truncation, leaving
+    /// room for the null terminator.
+    pub const MAX_LEN: usize = N - 1;
+
     /// Extracts a string slice from the owned string.
     pub fn as_str(&self) -> &str {
         self.as_ref()
     }
+
+    /// Creates a fixed string, returning an error instead of truncating if
+    /// the string is longer than [`FixedSafeString::MAX_LEN`] bytes.
+    pub fn try_from_str(s: &str) -> crate::Result<Self> {
+        if s.len() > Self::MAX_LEN {
+            Err(crate::Error::InvalidDataD(format!(
+                "String of {} bytes is too long for FixedSafeString<{}>, which holds at most {} \
+                 bytes with its null terminator",
+                s.len(),
+                N,
+                Self::MAX_LEN
+            )))
+        } else {
+            Ok(s.into())
+        }
+    }
+
+    /// Creates a fixed string from its full fixed-size buffer. The string
+    /// ends at the first null byte, but any bytes after it are kept, so that
+    /// [`FixedSafeString::as_raw_bytes`] returns the buffer unchanged.
+    /// Returns an error if the string is not valid UTF-8.
+    pub fn from_raw_bytes(data: [u8; N]) -> crate::Result<Self> {
+        let len = data.iter().position(|&c| c == 0).unwrap_or(N);
+        std::str::from_utf8(&data[..len])?;
+        Ok(Self { data, len })
+    }
+
+    /// The full fixed-size buffer, including the null terminator and any bytes
+    /// after it. Those bytes are zero unless they were kept by
+    /// [`FixedSafeString::from_raw_bytes`], and are ignored when comparing or
+    /// hashing fixed strings.
+    pub fn as_raw_bytes(&self) -> &[u8; N] {
+        &self.data
+    }
 }
 
 impl<const N: usize> std::ops::Deref for FixedSafeString<N> {
@@ -293,7 +353,34 @@ impl std::hash::Hash for Color {
     }
 }
 
+impl Color {
+    /// Create an opaque color (alpha 1.0) from its red, green, and blue
+    /// components.
+    pub const fn rgb(r: f32, g: f32, b: f32) -> Self {
+        Self { r, g, b, a: 1.0 }
+    }
+
+    /// Create a color from its red, green, blue, and alpha components.
+    pub const fn rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// Get the red, green, and blue components, ignoring alpha.
+    pub const fn to_rgb(&self) -> (f32, f32, f32) {
+        (self.r, self.g, self.b)
+    }
+
+    /// Get the red, green, blue, and alpha components.
+    pub const fn to_rgba(&self) -> (f32, f32, f32, f32) {
+        (self.r, self.g, self.b, self.a)
+    }
+}
+
 /// Curve (`sead::hostio::curve*`)
+///
+/// The fields follow `sead::hostio::CurveData`: `a` is the number of floats
+/// in use and `b` is the curve type, which can be accessed as a
+/// [`CurveType`] with [`Curve::curve_type`].
 #[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Default, Clone, Copy)]
 #[cfg_attr(feature = "binrw", binrw::binrw)]
@@ -303,6 +390,95 @@ pub struct Curve {
     pub floats: [f32; 30],
 }
 
+impl Curve {
+    /// Get the type of the curve.
+    pub fn curve_type(&self) -> CurveType {
+        self.b.into()
+    }
+
+    /// Set the type of the curve.
+    pub fn set_curve_type(&mut self, curve_type: CurveType) {
+        self.b = curve_type.into();
+    }
+
+    /// Builder-style method to set the type of the curve.
+    pub fn with_curve_type(mut self, curve_type: CurveType) -> Self {
+        self.set_curve_type(curve_type);
+        self
+    }
+}
+
+/// Curve type (`sead::hostio::CurveType`).
+///
+/// Types not known to sead are kept as [`CurveType::Unknown`], so converting
+/// to and from the raw number is always lossless.
+#[cfg_attr(feature = "with-serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CurveType {
+    /// Linear interpolation between evenly spaced points (0).
+    Linear,
+    /// Hermite interpolation between evenly spaced points (1).
+    Hermit,
+    /// Step function over evenly spaced points (2).
+    Step,
+    /// Sine wave (3).
+    Sin,
+    /// Cosine wave (4).
+    Cos,
+    /// Squared sine wave (5).
+    SinPow2,
+    /// Linear interpolation between 2D points (6).
+    Linear2D,
+    /// Hermite interpolation between 2D points (7).
+    Hermit2D,
+    /// Step function over 2D points (8).
+    Step2D,
+    /// Non-uniform spline (9).
+    NonUniformSpline,
+    /// Smooth Hermite interpolation between 2D points (10).
+    Hermit2DSmooth,
+    /// Any other value.
+    Unknown(u32),
+}
+
+impl From<u32> for CurveType {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => CurveType::Linear,
+            1 => CurveType::Hermit,
+            2 => CurveType::Step,
+            3 => CurveType::Sin,
+            4 => CurveType::Cos,
+            5 => CurveType::SinPow2,
+            6 => CurveType::Linear2D,
+            7 => CurveType::Hermit2D,
+            8 => CurveType::Step2D,
+            9 => CurveType::NonUniformSpline,
+            10 => CurveType::Hermit2DSmooth,
+            other => CurveType::Unknown(other),
+        }
+    }
+}
+
+impl From<CurveType> for u32 {
+    fn from(value: CurveType) -> Self {
+        match value {
+            CurveType::Linear => 0,
+            CurveType::Hermit => 1,
+            CurveType::Step => 2,
+            CurveType::Sin => 3,
+            CurveType::Cos => 4,
+            CurveType::SinPow2 => 5,
+            CurveType::Linear2D => 6,
+            CurveType::Hermit2D => 7,
+            CurveType::Step2D => 8,
+            CurveType::NonUniformSpline => 9,
+            CurveType::Hermit2DSmooth => 10,
+            CurveType::Unknown(other) => other,
+        }
+    }
+}
+
 #[cfg(feature = "almost")]
 impl PartialEq for Curve {
     fn eq(&self, other: &Self) -> bool {
@@ -1,3 +1,4 @@
+//! YAML helpers shared by the AAMP and BYML text formats.
 use join_str::jstr;
 
 use crate::{Error, Result};
@@ -50,16 +51,37 @@ fn in_nan(input: &str) -> bool {
     matches!(input, ".nan" | ".NaN" | ".NAN")
 }
 
+/// Parses a boolean. Only `true` and `false` are accepted unless `lenient` is
+/// set, which also accepts capitalized and uppercase spellings and `yes`/`no`.
+#[inline]
+fn parse_bool(value: &str, lenient: bool) -> Option<bool> {
+    match value {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ if !lenient => None,
+        "True" | "TRUE" | "yes" | "Yes" | "YES" => Some(true),
+        "False" | "FALSE" | "no" | "No" | "NO" => Some(false),
+        _ => None,
+    }
+}
+
 /// Deliberately not compliant to the YAML 1.2 standard to get rid of unused
 /// features that harm performance.
+///
+/// Booleans are only read from unquoted `true` and `false` unless
+/// `lenient_bools` is set, in which case other common spellings are accepted
+/// too.
 #[inline]
 pub(crate) fn parse_scalar(
     tag_type: Option<TagBasedType>,
     value: &str,
     is_quoted: bool,
+    lenient_bools: bool,
 ) -> Result<Scalar> {
-    if tag_type == Some(TagBasedType::Bool) || matches!(value, "true" | "false") {
-        Ok(Scalar::Bool(&value[..1] == "t"))
+    if let Some(b) = parse_bool(value, lenient_bools).filter(|_| !is_quoted) {
+        Ok(Scalar::Bool(b))
+    } else if tag_type == Some(TagBasedType::Bool) {
+        Ok(Scalar::Bool(value.starts_with('t')))
     } else {
         // Floating-point conversions.
         let is_possible_double = value.contains('.');
@@ -124,17 +146,105 @@ pub(crate) fn parse_scalar(
     }
 }
 
-#[inline]
-pub(crate) fn string_needs_quotes(value: &str) -> bool {
+/// Returns `true` if a string must be quoted to be written as a YAML scalar
+/// and read back as the same string. This is the logic roead uses when writing
+/// AAMP and BYML documents.
+///
+/// Strings which would otherwise be read as another type need quotes:
+/// ```
+/// # use roead::yaml::string_needs_quotes;
+/// assert!(string_needs_quotes("true"));
+/// assert!(string_needs_quotes("null"));
+/// assert!(string_needs_quotes("123"));
+/// assert!(string_needs_quotes("-5"));
+/// assert!(string_needs_quotes("1.5"));
+/// assert!(string_needs_quotes(".inf"));
+/// assert!(!string_needs_quotes("1.5.0"));
+/// ```
+///
+/// So do strings which start with a YAML indicator character or would
+/// otherwise be parsed as YAML syntax:
+/// ```
+/// # use roead::yaml::string_needs_quotes;
+/// assert!(string_needs_quotes("*alias"));
+/// assert!(string_needs_quotes("> folded"));
+/// assert!(string_needs_quotes("!tag"));
+/// assert!(string_needs_quotes("- item"));
+/// assert!(string_needs_quotes("key: value"));
+/// assert!(string_needs_quotes(""));
+/// assert!(string_needs_quotes(" padded"));
+/// assert!(!string_needs_quotes("Weapon_Sword_001"));
+/// assert!(!string_needs_quotes("a-b"));
+/// ```
+///
+/// Strings containing carriage returns are also quoted, so that they are
+/// written as `\r` escapes instead of being turned into line breaks:
+/// ```
+/// # use roead::yaml::string_needs_quotes;
+/// assert!(string_needs_quotes("Line 1\r\nLine 2"));
+/// ```
+pub fn string_needs_quotes(value: &str) -> bool {
     matches!(value, "true" | "false")
-        || value.starts_with('!')
+        || matches!(value, "null" | "Null" | "NULL" | "~" | "")
+        || value.starts_with([
+            '!', '*', '&', '>', '|', '%', '@', '`', '#', '\'', '"', '{', '}', '[', ']', ',',
+        ])
+        || (value.starts_with(['-', '?', ':'])
+            && !value[1..].starts_with(|c: char| !c.is_whitespace()))
+        || value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace)
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.ends_with(':')
+        || value.contains('\r')
         || (value.contains('.')
             && (is_infinity(value)
                 || is_negative_infinity(value)
                 || in_nan(value)
                 || lexical::parse::<f64, &[u8]>(value.as_bytes()).is_ok()))
-        || lexical::parse::<u64, &[u8]>(value.as_bytes()).is_ok()
-        || value == "null"
+        || lexical::parse::<i128, &[u8]>(value.as_bytes()).is_ok()
+}
+
+/// Normalizes text edited on Windows or with tab indentation so that it can
+/// be parsed: CRLF line endings are converted to LF, and tabs in the
+/// indentation of each line are replaced with two spaces. YAML does not allow
+/// tabs for indentation, so this only accepts files which are otherwise
+/// valid, as long as they indent consistently.
+pub(crate) fn normalize_text(text: &str) -> std::borrow::Cow<'_, str> {
+    let has_tab_indent = text.starts_with('\t') || text.contains("\n\t");
+    if !has_tab_indent && !text.contains("\r\n") {
+        return text.into();
+    }
+    let mut out = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        let content = line.trim_start_matches([' ', '\t']);
+        for c in line[..line.len() - content.len()].chars() {
+            out.push_str(if c == '\t' { "  " } else { " " });
+        }
+        out.push_str(content);
+        out.push('\n');
+    }
+    if !text.ends_with('\n') {
+        out.pop();
+    }
+    out.into()
+}
+
+/// Normalizes emitted text to LF line endings without a byte order mark.
+/// YAML parsers read any line break in a scalar as LF, so converting CRLF or
+/// lone CR breaks does not change the meaning of the document.
+pub(crate) fn normalize_output(text: String) -> String {
+    let text = match text.strip_prefix('\u{feff}') {
+        Some(stripped) => stripped.to_owned(),
+        None => text,
+    };
+    if text.contains('\r') {
+        text.replace("\r\n", "\n").replace('\r', "\n")
+    } else {
+        text
+    }
 }
 
 macro_rules! format_hex {
@@ -103,19 +103,21 @@ private:
 };
 }  // namespace
 
-rust::Vec<u8> Compress(rust::Slice<const u8> src, u32 data_alignment, int level) {
-  util::BinaryWriter writer{util::Endianness::Big};
-  writer.Buffer().reserve(src.size());
+void CompressInto(rust::Slice<const u8> src, rust::Vec<u8>& dst, u32 data_alignment, int level) {
+  dst.reserve(dst.size() + sizeof(Header) + src.size());
 
   // Write the header.
-  Header header;
-  header.magic = Magic;
-  header.uncompressed_size = u32(src.size());
-  header.data_alignment = data_alignment;
-  header.reserved.fill(0);
-  writer.Write(header);
-
-  GroupWriter group_writer{writer.Buffer()};
+  const auto write_u32 = [&dst](u32 value) {
+    for (int shift = 24; shift >= 0; shift -= 8)
+      dst.push_back(u8(value >> shift));
+  };
+  for (const char c : Magic)
+    dst.push_back(u8(c));
+  write_u32(u32(src.size()));
+  write_u32(data_alignment);
+  write_u32(0);
+
+  GroupWriter group_writer{dst};
 
   // Let zlib do the heavy lifting.
   std::array<u8, 8> dummy{};
@@ -128,10 +130,15 @@ rust::Vec<u8> Compress(rust::Slice<const u8> src, u32 data_alignment, int level)
     throw std::runtime_error("zng_compress failed");
 
   group_writer.Finalise();
-  return writer.Finalize();
 }
 
-template <bool Safe>
+rust::Vec<u8> Compress(rust::Slice<const u8> src, u32 data_alignment, int level) {
+  rust::Vec<u8> result;
+  CompressInto(src, result, data_alignment, level);
+  return result;
+}
+
+template <bool Safe, bool Truncate = false>
 static void Decompress(rust::Slice<const u8> src, rust::Slice<u8> dst) {
   util::BinaryReader reader{src, util::Endianness::Big};
   reader.Seek(sizeof(Header));
@@ -149,8 +156,11 @@ static void Decompress(rust::Slice<const u8> src, rust::Slice<u8> dst) {
     } else {
       const u16 pair = reader.Read<u16, Safe>().value();
       const size_t distance = (pair & 0x0FFF) + 1;
-      const size_t length =
+      size_t length =
           ((pair >> 12) ? (pair >> 12) : (reader.Read<u8, Safe>().value() + 16)) + 2;
+      // When only decompressing a prefix, the last copy may run past the end.
+      if constexpr (Truncate)
+        length = std::min<size_t>(length, dst.end() - dst_it);
 
       const auto base = dst_it - distance;
       if (base < dst.begin() || dst_it + length > dst.end()) {
@@ -174,4 +184,8 @@ void DecompressUnsafe(rust::Slice<const u8> src, rust::Slice<u8> dst) {
   Decompress<false>(src, dst);
 }
 
+void DecompressPrefix(rust::Slice<const u8> src, rust::Slice<u8> dst) {
+  Decompress<true, true>(src, dst);
+}
+
 }  // namespace oead::yaz0
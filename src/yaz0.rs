@@ -17,8 +17,8 @@ pub struct Header {
     pub uncompressed_size: u32,
     /// [Newer files only] Required buffer alignment
     pub data_alignment: u32,
-    #[doc(hidden)]
-    reserved: [u8; 4],
+    /// Reserved bytes. Usually zero.
+    pub reserved: [u8; 4],
 }
 
 /// Get the header of Yaz0 compressed data, if it exists.
@@ -26,9 +26,15 @@ pub fn get_header(data: impl AsRef<[u8]>) -> Option<Header> {
     binrw::BinRead::read(&mut std::io::Cursor::new(data.as_ref())).ok()
 }
 
-/// Decompress Yaz0 data to vector.
-pub fn decompress(data: impl AsRef<[u8]>) -> Result<Vec<u8>> {
-    let data = data.as_ref();
+/// The most bytes a single byte of compressed data can expand to. A group of
+/// eight back-references takes 25 bytes (one flag byte and three bytes per
+/// reference) and copies at most 0x111 bytes each, so this is generous.
+const MAX_EXPANSION: usize = 0x111;
+
+/// Reads and validates the header. The uncompressed size is checked against
+/// the most the compressed data could possibly expand to, so that a corrupt
+/// or malicious header cannot make the output allocation arbitrarily large.
+fn read_header(data: &[u8]) -> Result<Header> {
     if data.len() < 0x16 {
         return Err(Error::InsufficientData(data.len(), 0x16));
     }
@@ -39,6 +45,24 @@ pub fn decompress(data: impl AsRef<[u8]>) -> Result<Vec<u8>> {
             "Yaz0",
         ));
     }
+    let max_size = (data.len() - 0x10).saturating_mul(MAX_EXPANSION);
+    if header.uncompressed_size as usize > max_size {
+        return Err(Error::InvalidDataD(format!(
+            "Yaz0 header claims {} uncompressed bytes, but {} bytes of compressed data can hold \
+             at most {max_size}",
+            header.uncompressed_size,
+            data.len() - 0x10,
+        )));
+    }
+    Ok(header)
+}
+
+/// Decompress Yaz0 data to vector. The output is allocated once at the exact
+/// size given in the header, after checking that the compressed data could
+/// actually hold that much.
+pub fn decompress(data: impl AsRef<[u8]>) -> Result<Vec<u8>> {
+    let data = data.as_ref();
+    let header = read_header(data)?;
     let mut out = vec![0; header.uncompressed_size as usize];
     ffi::DecompressIntoBuffer(data, &mut out)?;
     Ok(out)
@@ -48,16 +72,7 @@ pub fn decompress(data: impl AsRef<[u8]>) -> Result<Vec<u8>> {
 /// bytes written.
 pub fn decompress_into(data: impl AsRef<[u8]>, mut buffer: impl AsMut<[u8]>) -> Result<usize> {
     let data = data.as_ref();
-    if data.len() < 0x16 {
-        return Err(Error::InsufficientData(data.len(), 0x16));
-    }
-    let header = get_header(data).ok_or(Error::InvalidData("Missing or corrupt Yaz0 header"))?;
-    if &header.magic != b"Yaz0" {
-        return Err(Error::BadMagic(
-            String::from_utf8_lossy(header.magic.as_slice()).to_string(),
-            "Yaz0",
-        ));
-    }
+    let header = read_header(data)?;
     let buffer = buffer.as_mut();
     if buffer.len() < header.uncompressed_size as usize {
         return Err(Error::InsufficientData(
@@ -69,6 +84,33 @@ pub fn decompress_into(data: impl AsRef<[u8]>, mut buffer: impl AsMut<[u8]>) ->
     Ok(header.uncompressed_size as usize)
 }
 
+/// Decompress Yaz0 data, appending it to the end of a vector and returning
+/// the number of bytes written. Unlike [`decompress_into`], the buffer grows
+/// as needed, so it can be reused for many files without reallocating once
+/// its capacity is large enough.
+pub fn decompress_append(data: impl AsRef<[u8]>, buffer: &mut Vec<u8>) -> Result<usize> {
+    let data = data.as_ref();
+    let header = read_header(data)?;
+    let start = buffer.len();
+    buffer.resize(start + header.uncompressed_size as usize, 0);
+    if let Err(e) = ffi::DecompressIntoBuffer(data, &mut buffer[start..]) {
+        buffer.truncate(start);
+        return Err(e.into());
+    }
+    Ok(header.uncompressed_size as usize)
+}
+
+/// Decompress only the first `max_out` bytes of Yaz0 data (or all of it, if
+/// it is shorter), for peeking at the header of a large compressed file
+/// without decompressing all of it. The Yaz0 header is still validated.
+pub fn decompress_prefix(data: impl AsRef<[u8]>, max_out: usize) -> Result<Vec<u8>> {
+    let data = data.as_ref();
+    let header = read_header(data)?;
+    let mut out = vec![0; max_out.min(header.uncompressed_size as usize)];
+    ffi::DecompressPrefix(data, &mut out)?;
+    Ok(out)
+}
+
 /// Decompress Yaz0 data into an existing buffer, returning the number of
 /// bytes written.
 ///
@@ -88,21 +130,9 @@ pub unsafe fn decompress_unchecked(data: impl AsRef<[u8]>, mut buffer: impl AsMu
 /// decompression fails, or containing the decompressed data otherwise.
 #[inline]
 pub fn decompress_if(data: &[u8]) -> Cow<'_, [u8]> {
-    if data.len() < 0x16 {
-        return Cow::Borrowed(data);
-    }
-    if let Some(header) = get_header(data) {
-        if &header.magic != b"Yaz0" {
-            return Cow::Borrowed(data);
-        }
-        let mut out = vec![0; header.uncompressed_size as usize];
-        if ffi::DecompressIntoBuffer(data, &mut out).is_ok() {
-            Cow::Owned(out)
-        } else {
-            Cow::Borrowed(data)
-        }
-    } else {
-        Cow::Borrowed(data)
+    match decompress(data) {
+        Ok(out) => Cow::Owned(out),
+        Err(_) => Cow::Borrowed(data),
     }
 }
 
@@ -113,6 +143,13 @@ pub fn compress(data: impl AsRef<[u8]>) -> Vec<u8> {
     ffi::Compress(data, 0, 7)
 }
 
+/// Compress data with default compression settings, appending it to the end
+/// of a vector. This allows reusing one buffer for many files.
+pub fn compress_into(data: impl AsRef<[u8]>, buffer: &mut Vec<u8>) {
+    ffi::CompressInto(data.as_ref(), buffer, 0, 7)
+        .expect("Yaz0 compression should not fail");
+}
+
 /// Yaz0 compression options.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct CompressOptions {
@@ -143,6 +180,25 @@ pub fn compress_with_options(data: impl AsRef<[u8]>, options: CompressOptions) -
     )
 }
 
+/// Compress data with custom compression settings, writing the given
+/// alignment and reserved bytes to the header as-is. This allows matching the
+/// exact header bytes used by a particular game or platform. For example,
+/// Wii U files usually leave both fields zeroed, while some newer Switch files
+/// set an alignment too large for [`CompressOptions::alignment`].
+///
+/// The alignment in the options is ignored.
+pub fn compress_with_header(
+    data: impl AsRef<[u8]>,
+    options: CompressOptions,
+    data_alignment: u32,
+    reserved: [u8; 4],
+) -> Vec<u8> {
+    let data = data.as_ref();
+    let mut compressed = ffi::Compress(data, data_alignment, options.compression_level as i32);
+    compressed[0xC..0x10].copy_from_slice(&reserved);
+    compressed
+}
+
 /// Compress data conditionally, if an associated path has a Yaz0-associated
 /// file extension (starts with `s`, but does not equal `sarc`). Returns a
 /// [`Cow`] which contains the original data if the data does not need to be
@@ -169,7 +225,14 @@ mod ffi {
         #[rust_name = "DecompressIntoBuffer"]
         fn Decompress(data: &[u8], dest: &mut [u8]) -> Result<()>;
         unsafe fn DecompressUnsafe(data: &[u8], dest: &mut [u8]) -> Result<()>;
+        fn DecompressPrefix(data: &[u8], dest: &mut [u8]) -> Result<()>;
         fn Compress(data: &[u8], data_alignment: u32, level: i32) -> Vec<u8>;
+        fn CompressInto(
+            data: &[u8],
+            dest: &mut Vec<u8>,
+            data_alignment: u32,
+            level: i32,
+        ) -> Result<()>;
     }
 }
 
@@ -208,6 +271,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_prefix() {
+        for (file, magic, len) in FILES {
+            let path = std::path::Path::new("test/yaz0").join(file);
+            let data = std::fs::read(path).unwrap();
+            let decompressed = super::decompress(&data).unwrap();
+            for max_out in [4, 0x13, 0x1000] {
+                let prefix = super::decompress_prefix(&data, max_out).unwrap();
+                assert_eq!(&prefix[..4], magic.as_slice());
+                assert_eq!(prefix.as_slice(), &decompressed[..max_out]);
+            }
+            assert_eq!(super::decompress_prefix(&data, usize::MAX).unwrap().len(), *len);
+        }
+        assert!(super::decompress_prefix([0u8; 0x20], 4).is_err());
+    }
+
     #[test]
     fn test_roundtrip() {
         for (file, ..) in FILES {
@@ -220,6 +299,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_header_options() {
+        let data = b"Nothing you have not given away will ever really be yours.";
+        let compressed =
+            super::compress_with_header(data, Default::default(), 0x2000, [1, 2, 3, 4]);
+        assert_eq!(&compressed[..0x10], &[
+            b'Y', b'a', b'z', b'0', 0, 0, 0, 58, 0, 0, 0x20, 0, 1, 2, 3, 4
+        ]);
+        let header = super::get_header(&compressed).unwrap();
+        assert_eq!(header.data_alignment, 0x2000);
+        assert_eq!(header.reserved, [1, 2, 3, 4]);
+        assert_eq!(super::decompress(compressed).unwrap(), data);
+    }
+
     #[test]
     fn test_unchecked() {
         let data = b"Nothing you have not given away will ever really be yours.";
@@ -228,4 +321,30 @@ mod tests {
         let size = unsafe { super::decompress_unchecked(compressed, &mut buffer) };
         assert_eq!(data.as_slice(), &buffer[..size]);
     }
+
+    #[test]
+    fn test_append() {
+        let data = b"Nothing you have not given away will ever really be yours.";
+        let mut compressed = b"prefix".to_vec();
+        super::compress_into(data, &mut compressed);
+        assert_eq!(&compressed[6..], super::compress(data).as_slice());
+
+        let mut buffer = b"prefix".to_vec();
+        let size = super::decompress_append(&compressed[6..], &mut buffer).unwrap();
+        assert_eq!(size, data.len());
+        assert_eq!(&buffer[6..], data.as_slice());
+        assert!(super::decompress_append(data, &mut buffer).is_err());
+        assert_eq!(buffer.len(), 6 + data.len());
+    }
+
+    #[test]
+    fn test_size_limit() {
+        let mut data = b"Yaz0".to_vec();
+        data.extend(u32::MAX.to_be_bytes());
+        data.resize(0x20, 0);
+        let err = super::decompress(&data).unwrap_err();
+        assert!(err.to_string().contains("claims 4294967295 uncompressed bytes"));
+        assert!(super::decompress_append(&data, &mut Vec::new()).is_err());
+        assert!(matches!(super::decompress_if(&data), super::Cow::Borrowed(_)));
+    }
 }